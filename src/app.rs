@@ -1,12 +1,89 @@
 use pleco::{ core::piece_move::{ MoveFlag, PreMoveInfo }, BitMove, PieceType, Player };
-use ratatui::{ crossterm::event::KeyEventKind, DefaultTerminal };
+use ratatui::{ crossterm::event::{ KeyCode, KeyEvent, KeyEventKind }, layout::{ Position, Rect, Size } };
 use color_eyre::{ eyre::WrapErr, Result };
+use std::{
+    collections::BTreeMap,
+    path::PathBuf,
+    sync::atomic::{ AtomicU64, Ordering },
+    time::{ Duration, Instant, SystemTime },
+};
+use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
-    event::{ Event, EventHandler },
-    handler::{ handle_key_event, handle_resize_event },
-    tui::Tui,
-    utils::{ dest_in_moves, get_current_player, is_game_over, move_to_square, moves_from_square, Coord },
+    event::Event,
+    error::{ AppError, AppResult },
+    config::{ config_modified, get_config_dir, read_config, Config, CoordinateLabels, Notation, PanelOrder },
+    clipboard::copy_to_clipboard,
+    command::{ parse_command, Command },
+    engine::{
+        blunder_warning,
+        engine_move,
+        evaluate,
+        evaluate_move,
+        format_eval,
+        skill_depth,
+        weaken_move,
+        EngineSearch,
+        analyse,
+        Analysis,
+        EngineStats,
+        MoveEval,
+        MAX_ANALYSIS_DEPTH,
+        ENGINE_DEPTH,
+        EVAL_DEPTH,
+    },
+    pgn::{ parse_pgn, result_token },
+    handler::{
+        handle_focus_event,
+        handle_key_event,
+        handle_mouse_event,
+        handle_paste_event,
+        handle_resize_event,
+    },
+    save::{
+        append_snapshot,
+        get_save_dir,
+        load_slot,
+        occupied_slots,
+        save_file,
+        save_slot,
+        SaveState,
+        SlotAction,
+    },
+    openings::{ book_moves, find_opening, BookMove, Opening },
+    scenarios::{ Scenario, SCENARIOS },
+    start_menu::{ StartChoice, StartMenu },
+    training::CoordinateTraining,
+    widgets::ply_at_column,
+    utils::{
+        board_ascii,
+        captured_square,
+        mirror_fen,
+        move_invariant_violations,
+        get_min_terminal_size,
+        moves_from_square,
+        format_move,
+        move_number,
+        move_to_san,
+        san_to_move,
+        uci_to_move,
+        move_to_square,
+        dest_in_moves,
+        game_result,
+        RESET_HINT,
+        get_board_size,
+        get_cell_pitch,
+        is_game_over,
+        parse_fen,
+        validate_side_to_move,
+        with_side_to_move,
+        Coord,
+        CoordEvent,
+        LogEntry,
+        LogKind,
+        Mark,
+        Orientation,
+    },
 };
 
 /// Application.
@@ -14,26 +91,268 @@ use crate::{
 pub struct App {
     /// All possible moves from the selected coordinate.
     pub moves_from_selected_coord: Vec<BitMove>,
-    /// The piece type to promote to.
-    pub promotion_piece: PieceType,
     /// Indicates if the terminal is too small to display the application.
     pub terminal_too_small: bool,
+    /// The last known size of the terminal.
+    pub terminal_size: Size,
+    /// The number of rows per cell of the board.
+    pub zoom: u16,
+    /// Whether only the board is shown, filling the terminal.
+    pub focus_board: bool,
+    /// The zoom to restore when leaving the focused board.
+    zoom_before_focus: u16,
+    /// Whether the terminal has focus.
+    pub focused: bool,
+    /// The rotation of the board on the screen.
+    pub orientation: Orientation,
+    /// When the last input was received.
+    pub last_input: Instant,
+    /// When the cursor was last moved by a key, to limit the rate of held keys.
+    last_cursor_key: Option<Instant>,
+    /// The count typed before a cursor movement, repeating it like in vim.
+    pub motion_count: Option<u8>,
+    /// When the player to move started thinking.
+    pub turn_started: Instant,
+    /// The thinking time of each player in the finished turns, indexed by player.
+    pub time_spent: [Duration; 2],
+    /// Whether the interface is dimmed after being idle.
+    pub dimmed: bool,
+    /// Whether the debug overlay may be shown, as enabled by the `--debug` flag.
+    pub debug_allowed: bool,
+    /// Whether the debug overlay with internal state is shown.
+    pub debug_overlay: bool,
     /// The selected coordinate.
     pub selected_coord: Coord,
     /// The cursor coordinate.
     pub cursor_coord: Coord,
+    /// The coordinate under the mouse pointer.
+    pub hover_coord: Option<Coord>,
+    /// The mouse position while the selected piece is dragged.
+    pub drag_position: Option<Position>,
+    /// The area the squares of the board were last rendered in.
+    pub board_area: Rect,
+    /// The area the timeline was last rendered in, empty if it was hidden.
+    pub timeline_area: Rect,
+    /// The ply shown on the board instead of the current position, if looking back.
+    pub viewed_ply: Option<usize>,
+    /// The position after the viewed ply.
+    viewed_board: Option<pleco::Board>,
     /// The player out of which perspective the board is viewed
     pub main_player: Player,
+    /// The player controlled by the engine, if playing against it.
+    pub engine_player: Option<Player>,
+    /// Whether the engine plays both sides.
+    pub self_play: bool,
+    /// The number of consecutive turns each player was hopelessly lost in self-play.
+    lost_turns: [u8; 2],
+    /// How a self-play game was ended before the board decided it, if it was.
+    pub adjudication: Option<Adjudication>,
+    /// The label of the white player.
+    pub white_label: String,
+    /// The label of the black player.
+    pub black_label: String,
+    /// The search depth of the engine.
+    pub engine_depth: u16,
+    /// The skill level limiting the engine, playing at full strength if not set.
+    pub engine_skill: Option<u8>,
+    /// The engine search running in the background, if the engine is thinking.
+    pub engine_search: Option<EngineSearch>,
+    /// The effort of the last engine move.
+    pub engine_stats: Option<EngineStats>,
+    /// The analysis of the current position, deepened one ply per step.
+    pub analysis: Option<Analysis>,
+    /// The identifier of the next analysis step running in the background, if any.
+    analysis_id: Option<u64>,
+    /// Sender for events of background tasks, searching synchronously if not set.
+    event_sender: Option<UnboundedSender<Event>>,
     /// The board.
     pub board: pleco::Board,
     /// Whether to block all non-universal key events.
     pub block_inputs: bool,
+    /// Whether moves have to be confirmed before they are applied.
+    pub confirm_moves: bool,
+    /// The move waiting for confirmation.
+    pub pending_move: Option<BitMove>,
+    /// The attempted illegal target, flashed until the next tick or input.
+    pub illegal_flash: Option<Coord>,
+    /// Whether to show the material difference bar above the board.
+    pub material_bar: bool,
+    /// The promotion waiting for a piece to be chosen on the board.
+    pub promotion_menu: Option<PromotionMenu>,
+    /// The captured piece fading out after the last move.
+    pub capture_animation: Option<CaptureAnimation>,
+    /// Whether to preview the selected piece on the hovered target square.
+    pub move_preview: bool,
+    /// Whether to draw arrows from the selected piece to its targets.
+    pub move_arrows: bool,
+    /// The directory the save slots are stored in.
+    pub save_dir: Option<PathBuf>,
+    /// The directory the configuration file is read from, if known.
+    pub config_dir: Option<PathBuf>,
+    /// When the loaded configuration file was last modified, to reload it after changes.
+    config_modified: Option<SystemTime>,
+    /// The configuration as last read from the file, before any settings were toggled.
+    file_config: Config,
+    /// The action to perform on the next chosen save slot.
+    pub slot_action: Option<SlotAction>,
+    /// The save slots which contain a game.
+    pub occupied_slots: Vec<u8>,
+    /// Whether the board is hidden until the next player is ready.
+    pub handoff: bool,
+    /// Whether the summary of the finished game is shown.
+    pub game_summary: bool,
+    /// Whether the game waits for the first key before accepting moves.
+    pub paused: bool,
+    /// The coordinate training, if it is active.
+    pub coordinate_training: Option<CoordinateTraining>,
+    /// The input of the command line, if command mode is active.
+    pub command_input: Option<String>,
+    /// Whether the scenario menu is open.
+    pub scenario_prompt: bool,
+    /// The start menu, while choosing how to start playing.
+    pub start_menu: Option<StartMenu>,
+    /// The practice scenario being played.
+    pub scenario: Option<Scenario>,
+    /// The opening practiced against the engine.
+    pub opening: Option<Opening>,
+    /// The highlighted continuation while the opening explorer is open.
+    pub opening_explorer: Option<usize>,
+    /// The moves played since the start or the last loaded position, in SAN.
+    pub move_history: Vec<String>,
+    /// The moves of the history as played, to show them in other notations.
+    pub played_moves: Vec<BitMove>,
+    /// The evaluations of the moves in the history, if they were annotated.
+    pub move_evals: Vec<Option<MoveEval>>,
+    /// The marks drawn by the user, by the ply of the position they annotate.
+    pub marks: BTreeMap<usize, Vec<Mark>>,
+    /// The Zobrist keys of all positions since the start or the last loaded position.
+    pub position_keys: Vec<u64>,
+    /// The FEN the current game started from, if not the standard start.
+    pub start_fen: Option<String>,
+    /// The panel which receives scroll input.
+    pub focused_panel: Panel,
+    /// The scroll offset of the log panel.
+    pub log_scroll: u16,
+    /// The scroll offset of the info panel.
+    pub info_scroll: u16,
+    /// The random number generator picking random moves.
+    pub rng: fastrand::Rng,
+    /// The application configuration.
+    pub config: Config,
     /// The log of events.
-    pub log: Vec<String>,
+    pub log: Vec<LogEntry>,
     /// Whether the application is running.
     running: bool,
 }
 
+/// Panel of the interface which receives scroll input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Panel {
+    /// The log panel.
+    Log,
+    /// The board panel.
+    Board,
+    /// The info panel.
+    Info,
+}
+
+/// Identifier of the last started engine search, shared by all games so their results can't be mixed up.
+static LAST_SEARCH_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Number of moves per log line when listing the legal moves.
+const LEGAL_MOVES_PER_LINE: usize = 8;
+
+/// Environment variable with the FEN of the starting position.
+pub const FEN_ENV_VAR: &str = "TUI_CHESS_FEN";
+
+/// Environment variable with moves in SAN, played from the starting position.
+pub const MOVES_ENV_VAR: &str = "TUI_CHESS_MOVES";
+
+/// Pieces offered when promoting, in menu order.
+pub const PROMOTION_PIECES: [PieceType; 4] = [PieceType::Q, PieceType::R, PieceType::B, PieceType::N];
+
+/// Time a captured piece stays on its square while fading out.
+const CAPTURE_ANIMATION_DURATION: Duration = Duration::from_millis(500);
+
+/// Columns and rows around the board in which clicks still count for the nearest square.
+const CLICK_MARGIN: (u16, u16) = (2, 1);
+
+/// Captured piece fading out on its square.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaptureAnimation {
+    /// The square the piece was captured on, behind the destination for en passant.
+    pub square: pleco::SQ,
+    /// The captured piece.
+    pub piece: pleco::Piece,
+    /// When the piece was captured.
+    pub started: Instant,
+}
+
+impl CaptureAnimation {
+    /// Whether the animation is in its second half, where the piece is drawn fainter.
+    pub fn is_fading(&self) -> bool {
+        self.started.elapsed() >= CAPTURE_ANIMATION_DURATION / 2
+    }
+
+    /// Whether the animation is over and the piece should disappear.
+    pub fn is_finished(&self) -> bool {
+        self.started.elapsed() >= CAPTURE_ANIMATION_DURATION
+    }
+}
+
+/// Promotion waiting for the player to choose a piece.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PromotionMenu {
+    /// The promoting move.
+    pub bit_move: BitMove,
+    /// Index of the highlighted piece in [`PROMOTION_PIECES`].
+    pub selected: usize,
+}
+
+/// Step of choosing a move with the cursor, picking a piece up and dropping it on a target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionStep {
+    /// Pick up the piece of the player to move under the cursor, showing its moves,
+    /// or pick up another one instead of the piece held.
+    PickUp,
+    /// Drop the held piece on a legal target, playing the move.
+    Drop,
+    /// Put the held piece back, as its own square was chosen.
+    PutBack,
+    /// Let go of the held piece, as a square it can't move to was chosen.
+    Cancel,
+    /// Nothing to pick up under the cursor.
+    Ignore,
+}
+
+/// End of a self-play game by the players rather than by the board.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Adjudication {
+    /// The player resigned.
+    Resigned(Player),
+    /// Both players agreed to a draw.
+    DrawAgreed,
+}
+
+impl Adjudication {
+    /// Gets how the game ended, like `game_result` for endings on the board.
+    pub fn result(self) -> &'static str {
+        match self {
+            Adjudication::Resigned(_) => "Resignation!",
+            Adjudication::DrawAgreed => "Draw agreed!",
+        }
+    }
+
+    /// Gets the result of the game as written at the end of a PGN.
+    pub fn result_token(self) -> &'static str {
+        match self {
+            Adjudication::Resigned(Player::White) => "0-1",
+            Adjudication::Resigned(Player::Black) => "1-0",
+            Adjudication::DrawAgreed => "1/2-1/2",
+        }
+    }
+}
+
 impl Default for App {
     fn default() -> Self {
         Self::new()
@@ -47,102 +366,877 @@ impl App {
             selected_coord: Coord::new(0, 0, false),
             moves_from_selected_coord: Default::default(),
             terminal_too_small: Default::default(),
+            terminal_size: Default::default(),
             cursor_coord: Default::default(),
-            promotion_piece: PieceType::Q,
+            hover_coord: None,
+            drag_position: None,
+            board_area: Default::default(),
+            timeline_area: Default::default(),
+            viewed_ply: None,
+            viewed_board: None,
             main_player: Player::White,
+            engine_player: None,
+            self_play: false,
+            lost_turns: [0; 2],
+            adjudication: None,
+            white_label: Player::White.to_string(),
+            black_label: Player::Black.to_string(),
+            engine_depth: ENGINE_DEPTH,
+            engine_skill: None,
+            engine_search: None,
+            engine_stats: None,
+            analysis: None,
+            analysis_id: None,
+            event_sender: None,
             board: Default::default(),
             log: Default::default(),
             block_inputs: false,
+            confirm_moves: false,
+            pending_move: None,
+            illegal_flash: None,
+            material_bar: false,
+            promotion_menu: None,
+            capture_animation: None,
+            move_preview: false,
+            move_arrows: false,
+            save_dir: get_save_dir(),
+            config_dir: get_config_dir(),
+            config_modified: None,
+            file_config: Config::default(),
+            slot_action: None,
+            occupied_slots: Default::default(),
+            handoff: false,
+            game_summary: false,
+            paused: false,
+            coordinate_training: None,
+            command_input: None,
+            scenario_prompt: false,
+            start_menu: None,
+            scenario: None,
+            opening: None,
+            opening_explorer: None,
+            move_history: Default::default(),
+            played_moves: Default::default(),
+            move_evals: Default::default(),
+            marks: Default::default(),
+            position_keys: vec![pleco::Board::default().zobrist()],
+            start_fen: None,
+            focused_panel: Panel::Board,
+            log_scroll: 0,
+            info_scroll: 0,
+            rng: fastrand::Rng::new(),
+            config: Default::default(),
             running: true,
+            zoom: 3,
+            focus_board: false,
+            zoom_before_focus: 3,
+            focused: true,
+            orientation: Orientation::Deg0,
+            last_input: Instant::now(),
+            last_cursor_key: None,
+            motion_count: None,
+            turn_started: Instant::now(),
+            time_spent: [Duration::ZERO; 2],
+            dimmed: false,
+            debug_allowed: false,
+            debug_overlay: false,
         }
     }
 
-    /// runs the application's main loop until the user quits
-    pub async fn run(&mut self, terminal: DefaultTerminal) -> Result<()> {
-        let events = EventHandler::new(250);
-        let mut tui = Tui::new(terminal, events);
+    /// Creates a new game sharing the configuration, terminal state and event sender of this one.
+    pub fn spawn_game(&self) -> Self {
+        let mut game = Self::new();
+        game.config = self.config.clone();
+        game.file_config = self.file_config.clone();
+        game.white_label = self.white_label.clone();
+        game.black_label = self.black_label.clone();
+        game.event_sender = self.event_sender.clone();
+        game.terminal_size = self.terminal_size;
+        game.zoom = self.zoom;
+        game.focused = self.focused;
+        game.debug_allowed = self.debug_allowed;
+        game.engine_skill = self.engine_skill;
+        game.update_terminal_too_small();
+        game
+    }
 
-        while self.running {
-            tui.draw(self)?;
-            self.handle_events(tui.events.next().await).wrap_err("Error handling events")?;
-        }
-        Ok(())
+    /// Sets the sender for the events of background tasks, like the engine search.
+    pub fn set_event_sender(&mut self, sender: UnboundedSender<Event>) {
+        self.event_sender = Some(sender);
+    }
+
+    /// Whether the application is still running.
+    pub fn is_running(&self) -> bool {
+        self.running
     }
 
     /// updates the application's state based on user input
-    fn handle_events(&mut self, event: Result<Event>) -> Result<()> {
-        match event? {
+    ///
+    /// Errors of actions in the game are shown in the log, other errors end the application.
+    pub fn handle_events(&mut self, event: Result<Event>) -> Result<()> {
+        // Checked on every event, as the tick doesn't run with `event_driven`
+        if self.config.watch_config {
+            self.reload_changed_config();
+        }
+        let result = self.dispatch_event(self.bind_key(event?));
+        if let Err(error) = &result {
+            if let Some(app_error) = error.downcast_ref::<AppError>() {
+                self.report_error(app_error);
+                return Ok(());
+            }
+        }
+        result
+    }
+
+    /// Replaces a typed key by the default key it is bound to, except while typing a command.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The event to translate.
+    ///
+    /// # Returns
+    ///
+    /// The event with the key it stands for, other events unchanged.
+    fn bind_key(&self, event: Event) -> Event {
+        let Event::Key(key_event @ KeyEvent { code: KeyCode::Char(char), .. }) = event else {
+            return event;
+        };
+        match self.config.key_bindings.get(&char) {
+            Some(&bound) if self.command_input.is_none() =>
+                Event::Key(KeyEvent { code: KeyCode::Char(bound), ..key_event }),
+            _ => event,
+        }
+    }
+
+    /// Passes an event to its handler.
+    fn dispatch_event(&mut self, event: Event) -> Result<()> {
+        match event {
             Event::Tick => self.tick(),
+            // Stop the loop so the terminal is restored before exiting
+            Event::Shutdown => {
+                self.quit();
+                Ok(())
+            }
+            Event::EngineMove(id, bit_move) => {
+                self.finish_engine_search(id, bit_move);
+                Ok(())
+            }
+            Event::Analysis(id, analysis) => {
+                self.finish_analysis(id, analysis);
+                Ok(())
+            }
             // it's important to check that the event is a key press event as
             // crossterm also emits key release and repeat events on Windows.
-            Event::Key(key_event) if key_event.kind == KeyEventKind::Press =>
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                self.register_input();
+                // Held cursor keys can repeat faster than the cursor should move
+                if self.is_too_fast(key_event) {
+                    return Ok(());
+                }
                 handle_key_event(key_event, self).wrap_err_with(||
                     format!("Handling key event failed:\n{key_event:#?}")
-                ),
+                )
+            }
             Event::Resize(width, height) => handle_resize_event(self, width, height),
-            Event::Mouse(_) => { Ok(()) }
+            Event::Focus(focused) => handle_focus_event(self, focused),
+            Event::Mouse(mouse_event) => {
+                self.register_input();
+                handle_mouse_event(mouse_event, self)
+            }
+            Event::Paste(text) => {
+                self.register_input();
+                handle_paste_event(&text, self)
+            }
             _ => Ok(()),
         }
     }
 
+    /// Loads the configuration file, keeping the current configuration if it can't be read.
+    pub fn load_config(&mut self) {
+        let Some(dir) = self.config_dir.clone() else {
+            return;
+        };
+
+        self.config_modified = config_modified(&dir);
+        match read_config(&dir) {
+            Ok(config) => self.apply_config(config),
+            Err(error) => self.log(&format!("Loading config failed: {}", error)),
+        }
+    }
+
+    /// Reloads the configuration file if it changed since it was last read, keeping the current
+    /// configuration if the changed file can't be read.
+    fn reload_changed_config(&mut self) {
+        let Some(dir) = self.config_dir.clone() else {
+            return;
+        };
+        let modified = config_modified(&dir);
+        if modified == self.config_modified {
+            return;
+        }
+
+        // Remember the failed edit too, so it is only reported once
+        self.config_modified = modified;
+        match read_config(&dir) {
+            Ok(config) => {
+                self.apply_config(config);
+                self.log("Reloaded config");
+            }
+            Err(error) => {
+                let message = format!("Reloading config failed, keeping the last one: {}", error);
+                self.log_with_kind(&message, LogKind::Error);
+            }
+        }
+    }
+
+    /// Replaces the configuration, along with the player labels it sets.
+    ///
+    /// Settings toggled while running are kept unless the file changes them too.
+    fn apply_config(&mut self, config: Config) {
+        let mut applied = config.clone();
+        if config.notation == self.file_config.notation {
+            applied.notation = self.config.notation;
+        }
+        if config.coordinate_labels == self.file_config.coordinate_labels {
+            applied.coordinate_labels = self.config.coordinate_labels;
+        }
+        if config.keep_selection == self.file_config.keep_selection {
+            applied.keep_selection = self.config.keep_selection;
+        }
+        self.white_label = config.white_label.clone().unwrap_or_else(|| Player::White.to_string());
+        self.black_label = config.black_label.clone().unwrap_or_else(|| Player::Black.to_string());
+        self.file_config = config;
+        self.config = applied;
+    }
+
     /// Handles the tick event of the terminal.
-    pub fn tick(&self) -> Result<()> {
+    pub fn tick(&mut self) -> Result<()> {
+        self.illegal_flash = None;
+        self.capture_animation = self.capture_animation.filter(|animation| !animation.is_finished());
+        if let Some(seconds) = self.config.idle_dim_seconds {
+            self.dimmed = self.last_input.elapsed() >= Duration::from_secs(seconds);
+        }
         Ok(())
     }
 
-    /// Appends a message to the log.
+    /// Records an input and restores the brightness after being idle.
+    fn register_input(&mut self) {
+        self.last_input = Instant::now();
+        // Any key skips the animation and ends the flash, which is otherwise only cleared by ticks
+        self.capture_animation = None;
+        self.illegal_flash = None;
+        self.dimmed = false;
+    }
+
+    /// Checks whether a cursor key follows the last one faster than the configured rate, recording it if not.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the key has to be dropped, as it comes from a held key repeating too fast.
+    fn is_too_fast(&mut self, key_event: KeyEvent) -> bool {
+        let cursor_key = matches!(
+            key_event.code,
+            KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right |
+                KeyCode::Char('w' | 'a' | 's' | 'd')
+        );
+        // Typed commands are never limited
+        let limit = self.config.cursor_repeat_ms.filter(|_| cursor_key && self.command_input.is_none());
+        let Some(millis) = limit else {
+            return false;
+        };
+
+        let now = Instant::now();
+        if self.last_cursor_key.is_some_and(|last| now.duration_since(last) < Duration::from_millis(millis)) {
+            return true;
+        }
+        self.last_cursor_key = Some(now);
+        false
+    }
+
+    /// Appends a plain message to the log.
     pub fn log(&mut self, message: &str) {
-        self.log.push(message.to_string());
+        self.log_with_kind(message, LogKind::Info);
+    }
+
+    /// Appends a message of the given kind to the log.
+    pub fn log_with_kind(&mut self, message: &str, kind: LogKind) {
+        self.log.push(LogEntry::new(message, kind));
+    }
+
+    /// Appends an error to the log, styled by its severity.
+    pub fn report_error(&mut self, error: &AppError) {
+        self.log_with_kind(&error.to_string(), error.log_kind());
     }
 
     /// Resets the application.
+    ///
+    /// The perspective, the engine side and the display toggles are kept,
+    /// as they are preferences of the players rather than state of the game.
     pub fn reset(&mut self) {
         self.cursor_coord.move_to(7, 0);
-        self.selected_coord.active = true;
         self.selected_coord.move_to(7, 0);
-        self.selected_coord.active = false;
-        self.board = Default::default();
+        self.set_board(Default::default());
+        self.start_fen = None;
+        self.engine_stats = None;
+        self.illegal_flash = None;
         self.log.clear();
-
-        self.block_inputs = false;
+        self.log_scroll = 0;
 
         self.log(&format!("Reseted"));
+        self.play_engine_move_if_due();
     }
 
-    /// Handles the move of a player.
-    pub fn handle_move(&mut self) {
+    /// Gets the move from the selected coordinate to the cursor coordinate.
+    fn cursor_move(&self) -> BitMove {
         let mut player_move = move_to_square(
             self.cursor_coord.to_square(self.main_player),
             &self.moves_from_selected_coord
         );
 
-        // Set the correct promotion piece
+        // Promote to a queen unless another piece is chosen from the promotion menu
         if player_move.is_promo() {
-            player_move = BitMove::init(PreMoveInfo {
-                src: player_move.get_src(),
-                dst: player_move.get_dest(),
-                flags: MoveFlag::Promotion {
-                    capture: player_move.is_capture(),
-                    prom: self.promotion_piece,
-                },
-            });
+            player_move = with_promotion_piece(player_move, PieceType::Q);
         }
+        player_move
+    }
 
-        self.log(
-            &format!("Player Move: {} ({})", player_move, get_current_player(self.board.moves_played() + 1))
-        );
+    /// Loads a position from a FEN string.
+    pub fn load_fen(&mut self, fen: &str) -> AppResult<()> {
+        self.set_board(parse_fen(fen)?);
+        self.start_fen = Some(fen.to_string());
+        self.play_engine_move_if_due();
+        Ok(())
+    }
+
+    /// Replaces the position with its color-mirrored counterpart, starting a new game from it.
+    pub fn mirror_position(&mut self) -> AppResult<()> {
+        self.load_fen(&mirror_fen(&self.board.fen()))
+    }
+
+    /// Loads a pasted FEN, or plays a pasted move in SAN or coordinate notation.
+    pub fn paste_input(&mut self, text: &str) -> AppResult<()> {
+        let text = text.trim();
+
+        // Only FENs contain slashes, moves never do
+        if text.contains('/') {
+            self.load_fen(text)?;
+            self.log("Loaded pasted FEN");
+            return Ok(());
+        }
+
+        if self.block_inputs || self.engine_plays(self.board.turn()) {
+            self.log_with_kind(&format!("Can't play pasted move {} now", text), LogKind::Warning);
+            return Ok(());
+        }
+        let bit_move = san_to_move(text, &self.board)
+            .or_else(|| uci_to_move(text, &self.board))
+            .ok_or_else(|| AppError::IllegalMove(text.to_string()))?;
+        self.handle_move(bit_move);
+        Ok(())
+    }
+
+    /// Gets the board of the current position.
+    pub fn current_board(&self) -> &pleco::Board {
+        &self.board
+    }
+
+    /// Gets the player to move.
+    pub fn current_player(&self) -> Player {
+        self.board.turn()
+    }
+
+    /// Whether the game is over by checkmate or a draw.
+    pub fn is_over(&self) -> bool {
+        is_game_over(&self.board)
+    }
+
+    /// Gets all legal moves of the player to move.
+    pub fn legal_moves(&self) -> Vec<BitMove> {
+        self.board.generate_moves().vec()
+    }
+
+    /// Plays a move given in SAN, as if it was entered on the board.
+    pub fn apply_san(&mut self, san: &str) -> AppResult<()> {
+        if self.is_over() {
+            return Err(AppError::GameOver);
+        }
+        let bit_move = san_to_move(san, &self.board).ok_or_else(|| AppError::IllegalMove(san.to_string()))?;
+        self.handle_move(bit_move);
+        Ok(())
+    }
+
+    /// Loads the mainline of a PGN game and continues from its final position.
+    pub fn load_pgn(&mut self, pgn: &str) -> Result<()> {
+        let game = parse_pgn(pgn)?;
+        self.replay_game(game.fen, &game.moves)?;
+        self.main_player = self.board.turn();
+        self.play_engine_move_if_due();
+        Ok(())
+    }
+
+    /// Starts from the position given by the environment variables, if any are set.
+    ///
+    /// The FEN is validated like the `fen` command, the default position is kept if it or a move is invalid.
+    pub fn load_env_position(&mut self) {
+        let fen = std::env::var(FEN_ENV_VAR).ok().filter(|fen| !fen.trim().is_empty());
+        let moves: Vec<String> = std::env::var(MOVES_ENV_VAR)
+            .map(|moves| moves.split_whitespace().map(String::from).collect())
+            .unwrap_or_default();
+        if fen.is_none() && moves.is_empty() {
+            return;
+        }
+
+        let source = if fen.is_some() { FEN_ENV_VAR } else { MOVES_ENV_VAR };
+        match self.replay_game(fen.map(|fen| fen.trim().to_string()), &moves) {
+            Ok(()) => {
+                self.main_player = self.board.turn();
+                self.log(&format!("Loaded the starting position from {}", source));
+                self.play_engine_move_if_due();
+            }
+            Err(error) => {
+                self.log_with_kind(&format!("Loading {} failed: {}", source, error), LogKind::Error);
+            }
+        }
+    }
+
+    /// Creates a new instance of [`App`] resuming a saved game.
+    pub fn from_save(state: SaveState) -> Result<Self> {
+        let mut app = Self::new();
+        app.restore_save(state)?;
+        Ok(app)
+    }
+
+    /// Gets the state needed to resume the current game.
+    pub fn to_save(&self) -> SaveState {
+        SaveState {
+            fen: self.board.fen(),
+            start_fen: self.start_fen.clone(),
+            moves: self.move_history.clone(),
+            main_player: self.main_player.into(),
+            engine_player: self.engine_player.map(Into::into),
+            annotations: self.marks
+                .iter()
+                .map(|(ply, marks)| (*ply, marks.iter().map(|mark| mark.to_notation()).collect()))
+                .collect(),
+        }
+    }
+
+    /// Resumes a saved game, replaying its moves from the starting position.
+    pub fn restore_save(&mut self, state: SaveState) -> Result<()> {
+        if state.start_fen.is_none() && state.moves.is_empty() {
+            self.load_fen(&state.fen)?;
+        } else {
+            self.replay_game(state.start_fen, &state.moves)?;
+        }
+
+        // Marks which can't be parsed or annotate plies beyond the game are dropped
+        self.marks = state.annotations
+            .iter()
+            .filter(|(ply, _)| **ply <= self.played_moves.len())
+            .map(|(ply, marks)| (*ply, marks.iter().filter_map(|mark| Mark::from_notation(mark)).collect()))
+            .collect();
+        self.main_player = state.main_player.into();
+        self.engine_player = state.engine_player.map(Into::into);
+        self.play_engine_move_if_due();
+        Ok(())
+    }
 
+    /// Replaces the game with the given moves played from a starting position.
+    ///
+    /// The current game is kept if the position or any move is invalid.
+    fn replay_game(&mut self, start_fen: Option<String>, moves: &[String]) -> Result<()> {
+        let mut board = match &start_fen {
+            Some(fen) => parse_fen(fen).map_err(AppError::Fen)?,
+            None => pleco::Board::default(),
+        };
+
+        let mut move_history = Vec::new();
+        let mut played_moves = Vec::new();
+        let mut position_keys = vec![board.zobrist()];
+        for san in moves {
+            let bit_move = san_to_move(san, &board).ok_or_else(|| AppError::IllegalMove(san.clone()))?;
+            move_history.push(move_to_san(bit_move, &board));
+            played_moves.push(bit_move);
+            board.apply_move(bit_move);
+            position_keys.push(board.zobrist());
+        }
+
+        self.set_board(board);
+        self.start_fen = start_fen;
+        self.move_evals = vec![None; move_history.len()];
+        self.move_history = move_history;
+        self.played_moves = played_moves;
+        self.position_keys = position_keys;
+        Ok(())
+    }
+
+    /// Replaces the board and clears the state belonging to the previous position.
+    fn set_board(&mut self, board: pleco::Board) {
+        self.cancel_engine_search();
+        self.view_current_position();
+        self.board = board;
+        self.selected_coord.active = false;
+        self.moves_from_selected_coord.clear();
+        self.pending_move = None;
+        self.promotion_menu = None;
+        self.capture_animation = None;
+        self.analysis = None;
+        self.analysis_id = None;
+        self.handoff = false;
+        self.game_summary = false;
+        self.scenario = None;
+        self.opening = None;
+        self.opening_explorer = None;
+        self.move_history.clear();
+        self.played_moves.clear();
+        self.move_evals.clear();
+        self.marks.clear();
+        self.position_keys = vec![self.board.zobrist()];
+        self.time_spent = [Duration::ZERO; 2];
+        self.turn_started = Instant::now();
+        self.lost_turns = [0; 2];
+        self.adjudication = None;
+
+        self.block_inputs = is_game_over(&self.board);
+        self.cursor_coord.active = !self.block_inputs;
+    }
+
+    /// Handles the move of a player.
+    pub fn handle_move(&mut self, player_move: BitMove) {
+        let san = move_to_san(player_move, &self.board);
+        let en_passant = if player_move.is_en_passant() { " e.p." } else { "" };
+        let eval = self.config.annotate_moves.then(|| evaluate_move(player_move, &self.board, EVAL_DEPTH));
+        let marker = eval.map_or("", |eval| eval.marker());
+        let notated = format_move(player_move, &self.board, self.config.notation);
+        let message = format!("{} {}{}{}", move_number(&self.board), notated, marker, en_passant);
+        self.move_history.push(san);
+        self.played_moves.push(player_move);
+        self.move_evals.push(eval);
+        self.analysis = None;
+        self.analysis_id = None;
+        self.capture_animation = captured_square(player_move)
+            .filter(|_| self.config.animations)
+            .map(|square| {
+                CaptureAnimation { square, piece: self.board.piece_at_sq(square), started: Instant::now() }
+            });
+
+        self.view_current_position();
+        let mover = self.board.turn();
+        self.time_spent[mover as usize] += self.turn_started.elapsed();
+        self.turn_started = Instant::now();
+        // Only pay for the self-check in development
+        let before = (cfg!(debug_assertions) || self.debug_allowed).then(|| self.board.shallow_clone());
         self.board.apply_move(player_move);
-        self.selected_coord.toggle_active();
+        self.position_keys.push(self.board.zobrist());
+        let violations = before.map_or_else(Vec::new, |before| {
+            move_invariant_violations(player_move, &before, &self.board)
+        });
+        for violation in violations {
+            self.log_with_kind(&format!("Invariant violated: {}", violation), LogKind::Error);
+        }
 
-        if is_game_over(&self.board) {
+        let kind = if self.board.checkmate() {
+            LogKind::Checkmate
+        } else if is_game_over(&self.board) {
+            LogKind::Draw
+        } else if self.board.in_check() {
+            LogKind::Check
+        } else if player_move.is_capture() {
+            LogKind::Capture
+        } else {
+            LogKind::Info
+        };
+        self.log_with_kind(&message, kind);
+        if self.config.keep_selection {
+            // Follow the piece moved by input, which stays selected while its side is to move
+            if !self.engine_plays(mover) {
+                let target = Coord::from_square(player_move.get_dest(), self.main_player);
+                self.selected_coord.move_to(target.row, target.col);
+            }
+            let selected_piece = self.board.piece_at_sq(self.selected_coord.to_square(self.main_player));
+            self.selected_coord.active = selected_piece.player() == Some(self.board.turn());
+            self.moves_from_selected_coord = moves_from_square(
+                self.selected_coord.to_square(self.main_player),
+                &self.board
+            );
+        } else {
             self.selected_coord.active = false;
+        }
+
+        if is_game_over(&self.board) {
             self.cursor_coord.active = false;
 
             self.block_inputs = true;
+            self.game_summary = true;
+        } else if self.engine_player.is_some() || self.self_play {
+            self.play_engine_move_if_due();
+        } else if self.config.handoff {
+            self.handoff = true;
+        }
+    }
+
+    /// Gets the total thinking time of a player, including the running turn while the game goes on.
+    pub fn thinking_time(&self, player: Player) -> Duration {
+        let running = self.board.turn() == player && !is_game_over(&self.board) && !self.paused;
+        self.time_spent[player as usize] + if running { self.turn_started.elapsed() } else { Duration::ZERO }
+    }
+
+    /// Gets how often the current position occurred since the start or the last loaded position.
+    pub fn repetition_count(&self) -> usize {
+        let key = self.board.zobrist();
+        self.position_keys.iter().filter(|position_key| **position_key == key).count()
+    }
+
+    /// Lets the engine move if it controls the player to move.
+    fn play_engine_move_if_due(&mut self) {
+        if !self.engine_plays(self.board.turn()) || self.engine_search.is_some() || self.paused {
+            return;
+        }
+        if self.self_play && self.adjudicate_self_play() {
+            return;
+        }
+
+        // Search in the background when running, so the engine can be forced to move
+        let Some(sender) = self.event_sender.clone() else {
+            let started = Instant::now();
+            if let Some(bit_move) = engine_move(&self.board, self.search_depth()) {
+                let elapsed = started.elapsed();
+                self.engine_stats = Some(EngineStats { depth: self.search_depth(), elapsed });
+                self.play_engine_move(bit_move);
+            }
+            return;
+        };
+        if self.board.generate_moves().is_empty() {
+            return;
+        }
+        let id = LAST_SEARCH_ID.fetch_add(1, Ordering::Relaxed) + 1;
+        let min_think_time = self.config.min_think_time();
+        let started = Instant::now();
+        self.engine_search = Some(
+            EngineSearch::start(id, &self.board, self.search_depth(), move |bit_move| {
+                // Wait in the search thread, so the search can still be forced or cancelled meanwhile
+                std::thread::sleep(min_think_time.saturating_sub(started.elapsed()));
+                let _ = sender.send(Event::EngineMove(id, bit_move));
+            })
+        );
+    }
+
+    /// Plays the move of a finished engine search, ignoring results of cancelled searches.
+    fn finish_engine_search(&mut self, id: u64, bit_move: BitMove) {
+        let Some(search) = self.engine_search.take_if(|search| search.id == id) else {
+            return;
+        };
+        self.engine_stats = Some(search.stats());
+        self.play_engine_move(bit_move);
+    }
+
+    /// Gets the search depth of the engine, limited by its skill level.
+    fn search_depth(&self) -> u16 {
+        self.engine_skill.map_or(self.engine_depth, |skill| skill_depth(self.engine_depth, skill))
+    }
+
+    /// Plays the move found by the engine, or a weaker one if its skill level is limited.
+    fn play_engine_move(&mut self, bit_move: BitMove) {
+        let bit_move = match self.engine_skill {
+            Some(skill) => weaken_move(bit_move, &self.board, skill, &mut self.rng),
+            None => bit_move,
+        };
+        self.handle_move(bit_move);
+    }
+
+    /// Analyses the current position one ply deeper than the last step, logging the best line.
+    ///
+    /// The analysis starts again at depth 1 once the position changes. When running, the step is analysed in
+    /// the background, as deeper steps take seconds.
+    pub fn deepen_analysis(&mut self) {
+        if self.analysis_id.is_some() {
+            self.log("Still analysing, wait for the current depth");
+            return;
+        }
+        let depth = self.analysis.as_ref().map_or(1, |analysis| analysis.depth + 1);
+        if depth > MAX_ANALYSIS_DEPTH {
+            self.log(&format!("Analysis stops at depth {}", MAX_ANALYSIS_DEPTH));
+            return;
+        }
+
+        let Some(sender) = self.event_sender.clone() else {
+            let analysis = analyse(&self.board, depth);
+            self.log(&format!("Analysis {}", analysis.summary()));
+            self.analysis = Some(analysis);
+            return;
+        };
+        let id = LAST_SEARCH_ID.fetch_add(1, Ordering::Relaxed) + 1;
+        let board = self.board.parallel_clone();
+        std::thread::spawn(move || {
+            let _ = sender.send(Event::Analysis(id, analyse(&board, depth)));
+        });
+        self.analysis_id = Some(id);
+        self.log(&format!("Analysing at depth {}", depth));
+    }
+
+    /// Shows the result of a finished analysis step, ignoring results for positions left since.
+    fn finish_analysis(&mut self, id: u64, analysis: Analysis) {
+        if self.analysis_id.take_if(|analysis_id| *analysis_id == id).is_none() {
+            return;
+        }
+        self.log(&format!("Analysis {}", analysis.summary()));
+        self.analysis = Some(analysis);
+    }
+
+    /// Plays a random legal move for the player to move.
+    pub fn play_random_move(&mut self) {
+        if self.block_inputs || self.engine_plays(self.board.turn()) {
+            return;
+        }
+        let moves = self.board.generate_moves();
+        if moves.is_empty() {
+            return;
+        }
+
+        let bit_move = moves[self.rng.usize(..moves.len())];
+        self.handle_move(bit_move);
+    }
+
+    /// Stops the engine search and plays the best move found so far.
+    pub fn force_engine_move(&mut self) -> AppResult<()> {
+        let Some(search) = &self.engine_search else {
+            return Ok(());
+        };
+        let bit_move = search.best_move().ok_or_else(|| AppError::Engine(String::from("no move found yet")))?;
+
+        self.engine_stats = Some(search.stats());
+        self.cancel_engine_search();
+        self.log("Forced the engine to move");
+        self.play_engine_move(bit_move);
+        Ok(())
+    }
+
+    /// Stops the engine search without playing its move.
+    pub fn cancel_engine_search(&mut self) {
+        if let Some(search) = self.engine_search.take() {
+            search.stop();
+        }
+    }
+
+    /// Gets the label of a player, noting if the engine plays it.
+    pub fn player_label(&self, player: Player) -> String {
+        let label = match player {
+            Player::White => &self.white_label,
+            Player::Black => &self.black_label,
+        };
+        if self.engine_plays(player) {
+            match self.engine_skill {
+                Some(skill) => format!("{} (engine, depth {}, skill {})", label, self.search_depth(), skill),
+                None => format!("{} (engine, depth {})", label, self.engine_depth),
+            }
+        } else {
+            label.clone()
         }
     }
 
+    /// Checks if the engine moves for a player, against the other one or in self-play.
+    pub fn engine_plays(&self, player: Player) -> bool {
+        self.self_play || self.engine_player == Some(player)
+    }
+
+    /// Starts or stops the engine playing against itself.
+    pub fn toggle_self_play(&mut self) {
+        self.cancel_engine_search();
+        self.self_play = !self.self_play;
+        self.lost_turns = [0; 2];
+        if self.self_play {
+            self.log("Engine plays both sides");
+        } else {
+            self.log("Self-play stopped");
+        }
+        self.play_engine_move_if_due();
+    }
+
+    /// Ends a self-play game early if the player to move is hopelessly lost or the position is drawn.
+    ///
+    /// # Returns
+    ///
+    /// Whether the game was ended by resignation or agreed draw.
+    fn adjudicate_self_play(&mut self) -> bool {
+        let thresholds = self.config.self_play;
+        let player = self.board.turn();
+        let eval = evaluate(&self.board, EVAL_DEPTH);
+        let own_eval = if player == Player::White { eval } else { -eval };
+
+        let lost_turns = &mut self.lost_turns[player as usize];
+        *lost_turns = if own_eval <= -thresholds.resign_threshold { lost_turns.saturating_add(1) } else { 0 };
+        let (adjudication, message, kind) = if *lost_turns >= thresholds.resign_moves {
+            let message = format!("{} resigns at {}", player, format_eval(eval));
+            (Adjudication::Resigned(player), message, LogKind::Info)
+        } else if own_eval.abs() <= thresholds.draw_threshold
+            && self.board.count_all_pieces() <= thresholds.draw_pieces
+        {
+            (Adjudication::DrawAgreed, format!("Draw agreed at {}", format_eval(eval)), LogKind::Draw)
+        } else {
+            return false;
+        };
+
+        // Ended like a game decided on the board
+        self.adjudication = Some(adjudication);
+        self.self_play = false;
+        self.block_inputs = true;
+        self.game_summary = true;
+        self.cursor_coord.active = false;
+        self.log_with_kind(&message, kind);
+        true
+    }
+
+    /// Gets the player whose pieces can be moved by input.
+    ///
+    /// # Returns
+    ///
+    /// The side opposite of the engine, or the main player if not playing against the engine.
+    pub fn controllable_player(&self) -> Player {
+        self.engine_player.map_or(self.main_player, |player| player.other_player())
+    }
+
+    /// Starts or stops playing against the engine, which takes the side opposite of the main player.
+    pub fn toggle_engine(&mut self) {
+        self.cancel_engine_search();
+        self.engine_player = match self.engine_player {
+            Some(_) => None,
+            None => Some(self.main_player.other_player()),
+        };
+        match self.engine_player {
+            Some(player) => self.log(&format!("Engine plays {}", player)),
+            None => self.log("Engine disabled"),
+        }
+        self.play_engine_move_if_due();
+    }
+
+    /// Swaps the sides of the engine and the player at the current position.
+    pub fn swap_engine_side(&mut self) {
+        let Some(player) = self.engine_player else {
+            return;
+        };
+        self.cancel_engine_search();
+        self.engine_player = Some(player.other_player());
+        self.main_player = player;
+        self.selected_coord.active = false;
+        self.pending_move = None;
+        self.log(&format!("Swapped sides, engine plays {}", player.other_player()));
+        self.play_engine_move_if_due();
+    }
+
+    /// Starts the game paused at launch, letting the engine move if it is its turn.
+    pub fn end_pause(&mut self) {
+        self.paused = false;
+        self.turn_started = Instant::now();
+        self.log("Game started");
+        self.play_engine_move_if_due();
+    }
+
+    /// Shows the board again from the perspective of the player to move.
+    pub fn end_handoff(&mut self) {
+        self.handoff = false;
+        self.main_player = self.board.turn();
+    }
+
     // Functions used for keyevents
 
     /// Set running to false in order to quit the application.
@@ -152,17 +1246,40 @@ impl App {
 
     /// Update the selected coordinate and handle moves.
     pub fn update_selected_coord(&mut self) {
-        if self.selected_coord.active {
-            if dest_in_moves(self.cursor_coord.to_square(self.main_player), &self.moves_from_selected_coord) {
-                self.handle_move();
-            } else if self.selected_coord.is_on(self.cursor_coord.row, self.cursor_coord.col) {
-                self.selected_coord.toggle_active();
-            } else {
+        if self.engine_search.is_some() {
+            self.log("The engine is thinking, press f to make it move now");
+            return;
+        }
+        if self.viewed_ply.is_some() {
+            self.view_current_position();
+            self.log("Back at the current position");
+            return;
+        }
+        // The engine may not have started searching yet, e.g. while paused
+        if self.engine_plays(self.board.turn()) {
+            self.log_with_kind("Not your turn", LogKind::Warning);
+            return;
+        }
+        let pending_move = self.pending_move.take();
+        let previous_selection = self.selected_coord;
+        let plies = self.played_moves.len();
+
+        match self.selection_step() {
+            SelectionStep::PickUp => {
                 self.selected_coord.move_to(self.cursor_coord.row, self.cursor_coord.col);
+                self.selected_coord.active = true;
             }
-        } else {
-            self.selected_coord.move_to(self.cursor_coord.row, self.cursor_coord.col);
-            self.selected_coord.toggle_active();
+            SelectionStep::Drop => self.drop_selected_piece(pending_move),
+            SelectionStep::PutBack => self.selected_coord.active = false,
+            SelectionStep::Cancel => {
+                if self.config.flash_illegal_moves {
+                    self.illegal_flash = Some(self.cursor_coord);
+                }
+                self.selected_coord.active = false;
+                let square = self.cursor_coord.to_square(self.main_player).to_string().to_uppercase();
+                self.log(&format!("Can't move to {}, selection cancelled", square));
+            }
+            SelectionStep::Ignore => self.log_selection_hint(),
         }
 
         // update moves from selected coord
@@ -170,10 +1287,2566 @@ impl App {
             self.selected_coord.to_square(self.main_player),
             &self.board
         );
+
+        // Play the only move of a newly selected piece as if its target was chosen,
+        // but not of a piece kept selected after a move
+        let newly_selected = self.selected_coord.active &&
+            self.selected_coord != previous_selection &&
+            self.played_moves.len() == plies;
+        if self.config.auto_play_only_move && newly_selected && self.moves_from_selected_coord.len() == 1 {
+            let target = Coord::from_square(self.moves_from_selected_coord[0].get_dest(), self.main_player);
+            self.cursor_coord.move_to(target.row, target.col);
+            self.update_selected_coord();
+        }
+    }
+
+    /// Gets what choosing the square under the cursor does, depending on whether a piece is held.
+    pub fn selection_step(&self) -> SelectionStep {
+        let holding = self.selected_coord.active;
+        let cursor_square = self.cursor_coord.to_square(self.main_player);
+        if holding && dest_in_moves(cursor_square, &self.moves_from_selected_coord) {
+            SelectionStep::Drop
+        } else if holding && self.selected_coord.is_on(self.cursor_coord.row, self.cursor_coord.col) {
+            SelectionStep::PutBack
+        } else if self.cursor_on_current_player_piece() {
+            SelectionStep::PickUp
+        } else if holding {
+            SelectionStep::Cancel
+        } else {
+            SelectionStep::Ignore
+        }
+    }
+
+    /// Plays the move of the held piece to the cursor, asking for the promotion piece or confirmation first.
+    ///
+    /// # Arguments
+    ///
+    /// * `pending_move` - The move waiting for confirmation before this one was chosen.
+    fn drop_selected_piece(&mut self, pending_move: Option<BitMove>) {
+        let cursor_move = self.cursor_move();
+        // A pending promotion is confirmed with the piece chosen for it
+        let squares = |bit_move: BitMove| (bit_move.get_src(), bit_move.get_dest());
+        let player_move = pending_move
+            .filter(|pending| squares(*pending) == squares(cursor_move))
+            .unwrap_or(cursor_move);
+        let confirmed = pending_move == Some(player_move);
+
+        // Each promotion asks the mover for the piece, unless always promoting to a queen
+        if player_move.is_promo() && !self.config.auto_queen && !confirmed {
+            self.promotion_menu = Some(PromotionMenu { bit_move: player_move, selected: 0 });
+        } else {
+            self.play_or_confirm(player_move, confirmed);
+        }
+    }
+
+    /// Plays a move, or keeps it waiting for confirmation if moves are confirmed or it is a blunder.
+    ///
+    /// # Arguments
+    ///
+    /// * `player_move` - The move to play.
+    /// * `confirmed` - Whether the move was already waiting for confirmation.
+    fn play_or_confirm(&mut self, player_move: BitMove, confirmed: bool) {
+        let warning = if self.config.training_wheels && !confirmed {
+            blunder_warning(player_move, &self.board)
+        } else {
+            None
+        };
+
+        if (self.confirm_moves || warning.is_some()) && !confirmed {
+            self.pending_move = Some(player_move);
+            if let Some(warning) = warning {
+                self.log_with_kind(&format!("Careful: {}", warning), LogKind::Check);
+            }
+            self.log("Press Enter to confirm or Esc to cancel");
+        } else {
+            self.handle_move(player_move);
+        }
+    }
+
+    /// Moves the highlight of the promotion menu.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The number of entries to move by, negative values move up.
+    pub fn move_promotion_selection(&mut self, offset: isize) {
+        if let Some(menu) = &mut self.promotion_menu {
+            menu.selected = menu.selected.saturating_add_signed(offset).min(PROMOTION_PIECES.len() - 1);
+        }
+    }
+
+    /// Highlights the given entry of the promotion menu.
+    pub fn select_promotion(&mut self, index: usize) {
+        if let Some(menu) = &mut self.promotion_menu {
+            menu.selected = index.min(PROMOTION_PIECES.len() - 1);
+        }
+    }
+
+    /// Plays the promotion with the highlighted piece, or asks for confirmation first like other moves.
+    pub fn confirm_promotion(&mut self) {
+        let Some(menu) = self.promotion_menu.take() else {
+            return;
+        };
+        self.play_or_confirm(with_promotion_piece(menu.bit_move, PROMOTION_PIECES[menu.selected]), false);
+        // The held pawn is still needed to confirm a pending promotion
+        if self.pending_move.is_none() {
+            self.moves_from_selected_coord.clear();
+        }
+    }
+
+    /// Closes the promotion menu without moving.
+    pub fn cancel_promotion(&mut self) {
+        self.promotion_menu = None;
+        self.log("Promotion cancelled");
+    }
+
+    /// Cancels the move waiting for confirmation.
+    pub fn cancel_pending_move(&mut self) {
+        self.pending_move = None;
+        self.log("Move cancelled");
+    }
+
+    /// Toggles whether moves have to be confirmed before they are applied.
+    pub fn toggle_confirm_moves(&mut self) {
+        self.confirm_moves = !self.confirm_moves;
+        self.pending_move = None;
+        self.log(if self.confirm_moves { "Move confirmation enabled" } else { "Move confirmation disabled" });
+    }
+
+    /// Toggles the preview of the selected move on the board.
+    pub fn toggle_move_preview(&mut self) {
+        self.move_preview = !self.move_preview;
+        self.log(if self.move_preview { "Move preview enabled" } else { "Move preview disabled" });
+    }
+
+    /// Toggles the arrows from the selected piece to its targets.
+    pub fn toggle_move_arrows(&mut self) {
+        self.move_arrows = !self.move_arrows;
+        self.log(if self.move_arrows { "Move arrows enabled" } else { "Move arrows disabled" });
+    }
+
+    /// Toggles the material difference bar above the board.
+    pub fn toggle_material_bar(&mut self) {
+        self.material_bar = !self.material_bar;
+    }
+
+    /// Toggles between showing moves in SAN and coordinate notation.
+    pub fn toggle_notation(&mut self) {
+        self.config.notation = self.config.notation.toggled();
+        match self.config.notation {
+            Notation::San => self.log("Showing moves in SAN"),
+            Notation::Coordinate => self.log("Showing moves in coordinate notation"),
+        }
+    }
+
+    /// Switches to the next mode of labeling the squares with their coordinates.
+    pub fn cycle_coordinate_labels(&mut self) {
+        self.config.coordinate_labels = self.config.coordinate_labels.next();
+        match self.config.coordinate_labels {
+            CoordinateLabels::Full => self.log("Labeling every square"),
+            CoordinateLabels::Cursor => self.log("Labeling the file and rank of the cursor"),
+            CoordinateLabels::Hidden => self.log("Hiding the coordinate labels"),
+        }
+    }
+
+    /// Toggles whether the moved piece stays selected after a move.
+    pub fn toggle_keep_selection(&mut self) {
+        self.config.keep_selection = !self.config.keep_selection;
+        if self.config.keep_selection {
+            self.log("Keeping the selection after moves");
+        } else {
+            self.log("Deselecting after moves");
+        }
+    }
+
+    /// Gets the moves of the history in the chosen notation.
+    pub fn notated_history(&self) -> Vec<String> {
+        match self.config.notation {
+            Notation::San => self.move_history.clone(),
+            Notation::Coordinate => self.played_moves.iter().map(|bit_move| bit_move.stringify()).collect(),
+        }
+    }
+
+    /// Gets the played moves in coordinate notation, as used by `position moves` of UCI.
+    pub fn uci_move_list(&self) -> String {
+        self.played_moves.iter().map(|bit_move| bit_move.stringify()).collect::<Vec<String>>().join(" ")
+    }
+
+    /// Gets the game as PGN with the seven tag roster, and `SetUp` and `FEN` tags if it didn't start
+    /// from the initial position. Event, site, date and round aren't known, so they are marked unknown.
+    pub fn pgn(&self) -> String {
+        let result = self.adjudication.map_or_else(|| result_token(&self.board), Adjudication::result_token);
+        let mut tags = vec![
+            ("Event", "?"),
+            ("Site", "?"),
+            ("Date", "????.??.??"),
+            ("Round", "?"),
+            ("White", self.white_label.as_str()),
+            ("Black", self.black_label.as_str()),
+            ("Result", result),
+        ];
+        if let Some(fen) = &self.start_fen {
+            tags.extend([("SetUp", "1"), ("FEN", fen.as_str())]);
+        }
+
+        // Numbered from the fullmove field of the start position, with `...` if black moves first
+        let fields: Vec<&str> = self.start_fen.as_deref().unwrap_or_default().split_whitespace().collect();
+        let black_starts = fields.get(1) == Some(&"b");
+        let first_number = fields.get(5).and_then(|number| number.parse().ok()).unwrap_or(1);
+        let mut tokens = Vec::new();
+        for (index, san) in self.move_history.iter().enumerate() {
+            let ply = index + usize::from(black_starts);
+            match ply % 2 {
+                0 => tokens.push(format!("{}.", first_number + ply / 2)),
+                _ if index == 0 => tokens.push(format!("{}...", first_number + ply / 2)),
+                _ => {}
+            }
+            tokens.push(san.clone());
+        }
+        tokens.push(result.to_string());
+
+        let tags: Vec<String> = tags
+            .iter()
+            .map(|(name, value)| format!("[{} \"{}\"]", name, value))
+            .collect();
+        format!("{}\n\n{}", tags.join("\n"), tokens.join(" "))
+    }
+
+    /// Copies the game as PGN to the clipboard.
+    pub fn copy_pgn(&mut self) -> AppResult<()> {
+        copy_to_clipboard(&self.pgn())?;
+        self.log("Copied the PGN to the clipboard");
+        Ok(())
+    }
+
+    /// Copies the FEN of the current position to the clipboard.
+    pub fn copy_fen(&mut self) -> AppResult<()> {
+        copy_to_clipboard(&self.board.fen())?;
+        self.log("Copied the FEN to the clipboard");
+        Ok(())
+    }
+
+    /// Copies the FEN of the current position to the clipboard as if a player was to move, warning if
+    /// the resulting position is not legal. The FEN shown in the info panel keeps the real side to move.
+    ///
+    /// # Arguments
+    ///
+    /// * `player` - The player to move in the copied FEN, or `None` to keep the side to move.
+    pub fn copy_fen_as(&mut self, player: Option<Player>) -> AppResult<()> {
+        let Some(player) = player else {
+            return self.copy_fen();
+        };
+
+        let fen = with_side_to_move(&self.board.fen(), player);
+        copy_to_clipboard(&fen)?;
+        // Warned about first, so the confirmation below doesn't bury it
+        if let Err(error) = validate_side_to_move(&self.board, player) {
+            self.log_with_kind(&format!("Careful, the copied FEN is illegal: {}", error), LogKind::Warning);
+        }
+        self.log(&format!("Copied the FEN with {} to move to the clipboard", player));
+        Ok(())
+    }
+
+    /// Closes the summary of the finished game, showing the final position.
+    pub fn close_game_summary(&mut self) {
+        self.game_summary = false;
+    }
+
+    /// Appends the shown position as an ASCII grid under its FEN to the snapshots file, for comparing
+    /// positions as text. Only the path is logged, as the grid would crowd the log.
+    pub fn snapshot_board(&mut self) {
+        let Some(dir) = self.save_dir.clone() else {
+            self.log("No config directory for snapshots");
+            return;
+        };
+
+        let board = self.displayed_board();
+        let snapshot = format!("{}\n{}", board.fen(), board_ascii(board));
+        match append_snapshot(&dir, &snapshot) {
+            Ok(path) => self.log(&format!("Appended a snapshot to {}", path.display())),
+            Err(error) => self.log_with_kind(&format!("Snapshot failed: {}", error), LogKind::Error),
+        }
+    }
+
+    /// Copies the played moves in coordinate notation to the clipboard.
+    pub fn copy_move_list(&mut self) -> AppResult<()> {
+        copy_to_clipboard(&self.uci_move_list())?;
+        self.log(&format!("Copied {} moves to the clipboard", self.played_moves.len()));
+        Ok(())
+    }
+
+    /// Lists all legal moves of the side to move in the log, sorted in the chosen notation.
+    pub fn log_legal_moves(&mut self) {
+        let mut moves: Vec<String> = self.board
+            .generate_moves()
+            .into_iter()
+            .map(|bit_move| format_move(bit_move, &self.board, self.config.notation))
+            .collect();
+        moves.sort();
+
+        // The log shows the newest entry first, so add the lines bottom up to read them in order
+        for chunk in moves.chunks(LEGAL_MOVES_PER_LINE).rev() {
+            self.log(&format!("  {}", chunk.join(" ")));
+        }
+        self.log(&format!("Legal moves ({}) for {}:", moves.len(), self.board.turn()));
+    }
+
+    /// Clears all overlays drawn on the board, keeping the position and selection.
+    pub fn clear_annotations(&mut self) {
+        self.illegal_flash = None;
+        self.hover_coord = None;
+        self.move_preview = false;
+        self.move_arrows = false;
+        self.material_bar = false;
+        self.marks.remove(&self.marked_ply());
+        self.log("Cleared annotations");
+    }
+
+    /// Gets the ply of the position shown on the board, which marks are drawn on.
+    fn marked_ply(&self) -> usize {
+        self.viewed_ply.unwrap_or(self.played_moves.len())
+    }
+
+    /// Gets the marks drawn on the position shown on the board.
+    pub fn shown_marks(&self) -> &[Mark] {
+        self.marks.get(&self.marked_ply()).map_or(&[], Vec::as_slice)
+    }
+
+    /// Toggles a mark on the position shown on the board.
+    ///
+    /// Draws an arrow from the selected piece to the cursor, or circles the cursor if no piece is selected.
+    pub fn toggle_mark(&mut self) {
+        let cursor = self.cursor_coord.to_square(self.main_player);
+        let mark = if self.selected_coord.active
+            && !self.cursor_coord.is_on(self.selected_coord.row, self.selected_coord.col)
+        {
+            Mark::Arrow(self.selected_coord.to_square(self.main_player), cursor)
+        } else {
+            Mark::Circle(cursor)
+        };
+
+        let ply = self.marked_ply();
+        let marks = self.marks.entry(ply).or_default();
+        if let Some(index) = marks.iter().position(|drawn| *drawn == mark) {
+            marks.remove(index);
+            if marks.is_empty() {
+                self.marks.remove(&ply);
+            }
+        } else {
+            marks.push(mark);
+        }
+    }
+
+    /// Opens the save slot prompt for the given action.
+    pub fn open_slot_prompt(&mut self, action: SlotAction) {
+        self.occupied_slots = self.save_dir.as_deref().map(occupied_slots).unwrap_or_default();
+        self.slot_action = Some(action);
+    }
+
+    /// Closes the save slot prompt.
+    pub fn close_slot_prompt(&mut self) {
+        self.slot_action = None;
+    }
+
+    /// Saves to or loads from the given slot, depending on the open slot prompt.
+    pub fn select_slot(&mut self, slot: u8) {
+        let Some(action) = self.slot_action.take() else {
+            return;
+        };
+        let Some(dir) = self.save_dir.clone() else {
+            self.log("No config directory for save slots");
+            return;
+        };
+
+        match action {
+            SlotAction::Save =>
+                match save_slot(&dir, slot, &self.to_save()) {
+                    Ok(()) => self.log(&format!("Saved to slot {}", slot)),
+                    Err(error) => self.log(&format!("Saving to slot {} failed: {}", slot, error)),
+                }
+            SlotAction::Load =>
+                match load_slot(&dir, slot) {
+                    Ok(Some(state)) =>
+                        match self.restore_save(state) {
+                            Ok(()) => self.log(&format!("Loaded slot {}", slot)),
+                            Err(error) => self.log(&format!("Loading slot {} failed: {}", slot, error)),
+                        }
+                    Ok(None) => self.log(&format!("Slot {} is empty", slot)),
+                    Err(error) => self.log(&format!("Loading slot {} failed: {}", slot, error)),
+                }
+        }
+    }
+
+    /// Starts or stops the coordinate training.
+    pub fn toggle_coordinate_training(&mut self) {
+        self.coordinate_training = match self.coordinate_training {
+            Some(training) => {
+                let score = format!("{}/{}", training.correct, training.attempts);
+                self.log(&format!("Coordinate training ended: {} correct", score));
+                None
+            }
+            None => {
+                self.log("Coordinate training: move the cursor to the named square and press Enter");
+                Some(CoordinateTraining::new())
+            }
+        };
+    }
+
+    /// Answers the coordinate training with the square under the cursor.
+    pub fn answer_coordinate_training(&mut self) {
+        let square = self.cursor_coord.to_square(self.main_player);
+        let Some(training) = &mut self.coordinate_training else {
+            return;
+        };
+
+        if training.answer(square) {
+            let time = training.last_time.unwrap_or_default();
+            let message = format!("Correct: {} in {:.1}s", square, time.as_secs_f64());
+            self.log_with_kind(&message, LogKind::Success);
+        } else {
+            let target = training.target;
+            self.log_with_kind(&format!("Wrong: that is {}, not {}", square, target), LogKind::Error);
+        }
+    }
+
+    /// Enters command mode with an empty command line.
+    pub fn open_command_line(&mut self) {
+        self.command_input = Some(String::new());
+    }
+
+    /// Leaves command mode without running the command.
+    pub fn close_command_line(&mut self) {
+        self.command_input = None;
+    }
+
+    /// Appends a character to the command line.
+    pub fn push_command_char(&mut self, char: char) {
+        if let Some(input) = &mut self.command_input {
+            input.push(char);
+        }
+    }
+
+    /// Removes the last character of the command line, leaving command mode if it is empty.
+    pub fn pop_command_char(&mut self) {
+        if let Some(input) = &mut self.command_input {
+            if input.pop().is_none() {
+                self.command_input = None;
+            }
+        }
+    }
+
+    /// Runs the entered command and leaves command mode.
+    pub fn submit_command(&mut self) {
+        let Some(input) = self.command_input.take() else {
+            return;
+        };
+
+        match parse_command(&input) {
+            Ok(command) => self.run_command(command),
+            Err(error) => self.log_with_kind(&error, LogKind::Error),
+        }
+    }
+
+    /// Runs a command of the command mode.
+    fn run_command(&mut self, command: Command) {
+        match command {
+            Command::Fen(fen) =>
+                match self.load_fen(&fen) {
+                    Ok(()) => self.log("Loaded FEN"),
+                    Err(error) => self.report_error(&error),
+                }
+            Command::Flip => {
+                self.main_player = self.main_player.other_player();
+                self.selected_coord.active = false;
+                self.log(&format!("Viewing as {}", self.main_player));
+            }
+            Command::Mirror =>
+                match self.mirror_position() {
+                    Ok(()) => self.log("Mirrored the position"),
+                    Err(error) => self.report_error(&error),
+                }
+            Command::Reset => self.reset(),
+            Command::Depth(depth) => {
+                self.engine_depth = depth;
+                self.log(&format!("Engine depth set to {}", depth));
+            }
+            Command::Opening(query) => self.practice_opening(&query),
+            Command::Pgn(path) => {
+                let loaded = std::fs::read_to_string(&path)
+                    .map_err(Into::into)
+                    .and_then(|pgn| self.load_pgn(&pgn));
+                match loaded {
+                    Ok(()) => self.log(&format!("Loaded {}", path.display())),
+                    Err(error) => {
+                        let message = format!("Loading {} failed: {}", path.display(), error);
+                        self.log_with_kind(&message, LogKind::Error);
+                    }
+                }
+            }
+            Command::CopyFen(player) => {
+                if let Err(error) = self.copy_fen_as(player) {
+                    self.report_error(&error);
+                }
+            }
+            Command::Save(path) => {
+                match save_file(&path, &self.to_save()) {
+                    Ok(()) => self.log(&format!("Saved to {}", path.display())),
+                    Err(error) => self.log_with_kind(&format!("Saving failed: {}", error), LogKind::Error),
+                }
+            }
+        }
+    }
+
+    /// Opens the scenario menu.
+    pub fn open_scenario_prompt(&mut self) {
+        self.scenario_prompt = true;
+    }
+
+    /// Opens the start menu, with the engine strength preset to its skill level.
+    ///
+    /// The menu already waits for a choice, so a game launched paused isn't paused too.
+    pub fn open_start_menu(&mut self) {
+        self.start_menu = Some(StartMenu::new(self.engine_skill));
+        self.paused = false;
+    }
+
+    /// Moves the highlight of the start menu.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The number of entries to move by, negative values move up.
+    pub fn move_start_selection(&mut self, offset: isize) {
+        if let Some(menu) = &mut self.start_menu {
+            menu.move_selection(offset);
+        }
+    }
+
+    /// Changes the engine strength of the start menu while it is highlighted.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The number of skill levels to change by, negative values weaken the engine.
+    pub fn change_start_skill(&mut self, offset: i8) {
+        if let Some(menu) = &mut self.start_menu {
+            menu.change_skill(offset);
+        }
+    }
+
+    /// Closes the start menu and sets up the game as chosen.
+    pub fn confirm_start_choice(&mut self) {
+        let Some(menu) = self.start_menu else {
+            return;
+        };
+        let choice = menu.choice();
+        match choice {
+            // The strength is changed with left and right instead
+            StartChoice::Strength => return,
+            StartChoice::TwoPlayers => {}
+            StartChoice::EngineAsWhite | StartChoice::EngineAsBlack => {
+                let as_white = choice == StartChoice::EngineAsWhite;
+                self.main_player = if as_white { Player::White } else { Player::Black };
+                self.engine_skill = menu.engine_skill();
+                self.engine_player = Some(self.main_player.other_player());
+                self.log(&format!("Engine plays {}", self.main_player.other_player()));
+                self.play_engine_move_if_due();
+            }
+            StartChoice::LoadFen => self.command_input = Some(String::from("fen ")),
+            StartChoice::LoadPgn => self.command_input = Some(String::from("pgn ")),
+            StartChoice::LoadSave => self.open_slot_prompt(SlotAction::Load),
+            StartChoice::Practice => self.open_scenario_prompt(),
+        }
+        self.start_menu = None;
+    }
+
+    /// Closes the scenario menu.
+    pub fn close_scenario_prompt(&mut self) {
+        self.scenario_prompt = false;
+    }
+
+    /// Plays the moves of an opening and lets the player continue against the engine.
+    ///
+    /// # Arguments
+    ///
+    /// * `query` - The ECO code or name of the opening.
+    pub fn practice_opening(&mut self, query: &str) {
+        let Some(opening) = find_opening(query) else {
+            self.log_with_kind(&format!("Unknown opening: {}", query), LogKind::Error);
+            return;
+        };
+        let moves: Vec<String> = opening.moves.iter().map(|san| san.to_string()).collect();
+        if let Err(error) = self.replay_game(None, &moves) {
+            self.log_with_kind(&format!("Setting up {} failed: {}", opening.name, error), LogKind::Error);
+            return;
+        }
+
+        // The player continues with the side to move, the engine answers
+        self.main_player = self.board.turn();
+        self.engine_player = Some(self.main_player.other_player());
+        self.opening = Some(opening);
+        self.log(&format!("Opening: {} {} ({})", opening.eco, opening.name, opening.moves.join(" ")));
+    }
+
+    /// Gets the continuations of the played moves from the table of openings.
+    ///
+    /// Games set up from a FEN never follow the table, so they have none.
+    pub fn explorer_moves(&self) -> Vec<BookMove> {
+        if self.start_fen.is_some() {
+            return Vec::new();
+        }
+        book_moves(&self.move_history)
+    }
+
+    /// Opens or closes the opening explorer.
+    pub fn toggle_opening_explorer(&mut self) {
+        self.opening_explorer = match self.opening_explorer {
+            Some(_) => None,
+            None => Some(0),
+        };
+    }
+
+    /// Moves the highlight of the opening explorer.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The number of entries to move by, negative values move up.
+    pub fn move_explorer_selection(&mut self, offset: isize) {
+        let last = self.explorer_moves().len().saturating_sub(1);
+        if let Some(selected) = &mut self.opening_explorer {
+            *selected = selected.saturating_add_signed(offset).min(last);
+        }
+    }
+
+    /// Plays the highlighted continuation of the opening explorer, keeping it open for the next move.
+    pub fn play_explorer_move(&mut self) {
+        let Some(selected) = self.opening_explorer else {
+            return;
+        };
+        if self.engine_plays(self.board.turn()) || self.engine_search.is_some() {
+            self.log("It is the engine's turn");
+            return;
+        }
+        let Some(book_move) = self.explorer_moves().get(selected).copied() else {
+            return;
+        };
+
+        if let Some(bit_move) = san_to_move(book_move.san, &self.board) {
+            self.handle_move(bit_move);
+            self.opening_explorer = Some(0);
+        }
+    }
+
+    /// Loads the practice scenario with the given number.
+    pub fn select_scenario(&mut self, number: usize) {
+        let Some(scenario) = number.checked_sub(1).and_then(|index| SCENARIOS.get(index)) else {
+            return;
+        };
+        self.scenario_prompt = false;
+
+        match self.load_fen(scenario.fen) {
+            Ok(()) => {
+                self.main_player = self.board.turn();
+                self.scenario = Some(*scenario);
+                self.log(&format!("Practice: {}", scenario.name));
+            }
+            Err(error) => self.report_error(&error),
+        }
+    }
+
+    /// Checks whether the cursor is on a piece of the player whose turn it is.
+    fn cursor_on_current_player_piece(&self) -> bool {
+        let piece = self.board.piece_at_sq(self.cursor_coord.to_square(self.main_player));
+        piece.player() == Some(self.board.turn())
+    }
+
+    /// Gets the moves to preview for the piece under the cursor, without selecting it.
+    ///
+    /// # Returns
+    ///
+    /// The legal moves of the piece if the preview is enabled, nothing is selected and the piece
+    /// belongs to the player to move, otherwise no moves.
+    pub fn hover_moves(&self) -> Vec<BitMove> {
+        let previewed = self.config.hover_moves && self.cursor_coord.active && self.viewed_ply.is_none();
+        if !previewed || self.selected_coord.active || !self.cursor_on_current_player_piece() {
+            return Vec::new();
+        }
+        moves_from_square(self.cursor_coord.to_square(self.main_player), &self.board)
+    }
+
+    /// Logs a hint when the cursor is not on a selectable piece.
+    fn log_selection_hint(&mut self) {
+        let square = self.cursor_coord.to_square(self.main_player);
+        self.log(
+            &format!(
+                "No {} piece on {}",
+                self.board.turn(),
+                square.to_string().to_uppercase()
+            )
+        );
+    }
+
+    /// Gets the board coordinate at the given terminal position.
+    ///
+    /// Positions just outside the board are moved onto its nearest edge, so clicks that slightly miss
+    /// the small cells of a dense board still hit the intended square.
+    ///
+    /// # Arguments
+    ///
+    /// * `column` - The terminal column.
+    /// * `row` - The terminal row.
+    ///
+    /// # Returns
+    ///
+    /// The coordinate of the nearest square, or `None` if the position is too far outside the board.
+    pub fn coord_at(&self, column: u16, row: u16) -> Option<Coord> {
+        let grid = self.config.grid_lines as u16;
+        // The squares lie inside the outer grid lines
+        let area = Rect::new(
+            self.board_area.x + grid,
+            self.board_area.y + grid,
+            self.board_area.width.saturating_sub(2 * grid),
+            self.board_area.height.saturating_sub(2 * grid)
+        );
+        let (margin_x, margin_y) = CLICK_MARGIN;
+        let target_area = Rect::new(
+            area.x.saturating_sub(margin_x),
+            area.y.saturating_sub(margin_y),
+            area.width + 2 * margin_x,
+            area.height + 2 * margin_y
+        );
+        if area.is_empty() || !target_area.contains(Position::new(column, row)) {
+            return None;
+        }
+        let column = column.clamp(area.left(), area.right() - 1);
+        let row = row.clamp(area.top(), area.bottom() - 1);
+        let (pitch_width, pitch_height) = get_cell_pitch(self.zoom, self.config.grid_lines);
+        let (row, col) = self.orientation.to_board(
+            ((row - area.y) / pitch_height) as i8,
+            ((column - area.x) / pitch_width) as i8
+        );
+        let coord = Coord::new(col, row, true);
+        coord.is_valid().then_some(coord)
+    }
+
+    /// Moves the cursor to the next piece with a legal move, in reading order as seen on the screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `forward` - Whether to go to the next piece, or else to the previous one.
+    pub fn cycle_movable_pieces(&mut self, forward: bool) {
+        let screen_position = |coord: Coord| self.orientation.to_screen(coord.row, coord.col);
+        let mut positions: Vec<(i8, i8)> = self.board
+            .generate_moves()
+            .iter()
+            .map(|bit_move| screen_position(Coord::from_square(bit_move.get_src(), self.main_player)))
+            .collect();
+        positions.sort();
+        positions.dedup();
+        let (Some(first), Some(last)) = (positions.first(), positions.last()) else {
+            self.log("No piece can move");
+            return;
+        };
+
+        let cursor = screen_position(self.cursor_coord);
+        let next = if forward {
+            positions.iter().find(|position| **position > cursor).unwrap_or(first)
+        } else {
+            positions.iter().rev().find(|position| **position < cursor).unwrap_or(last)
+        };
+
+        let (row, col) = self.orientation.to_board(next.0, next.1);
+        self.cursor_coord.move_to(row, col);
+    }
+
+    /// Moves the cursor in the direction of the event as seen on the screen, repeated by the typed count.
+    pub fn move_cursor(&mut self, event: CoordEvent) {
+        let count = self.motion_count.take().unwrap_or(1);
+        let (row, col) = self.cursor_target(event, count);
+        self.cursor_coord.move_to(row, col);
+    }
+
+    /// Gets where the cursor ends up moving repeatedly in the direction of the event, stopping at the edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `event` - The direction as seen on the screen.
+    /// * `count` - The number of squares to move.
+    ///
+    /// # Returns
+    ///
+    /// The row and column of the target on the board.
+    fn cursor_target(&self, event: CoordEvent, count: u8) -> (i8, i8) {
+        let (row, col) = self.orientation.to_screen(self.cursor_coord.row, self.cursor_coord.col);
+        let mut screen_coord = Coord::new(col, row, true);
+        for _ in 0..count {
+            screen_coord.handle_event(event);
+        }
+        self.orientation.to_board(screen_coord.row, screen_coord.col)
+    }
+
+    /// Appends a digit to the count repeating the next cursor movement.
+    ///
+    /// A leading zero is ignored, as a count of zero moves nowhere.
+    pub fn push_motion_digit(&mut self, digit: char) {
+        let count = self.motion_count
+            .unwrap_or(0)
+            .saturating_mul(10)
+            .saturating_add((digit as u8) - b'0');
+        self.motion_count = (count > 0).then_some(count);
+    }
+
+    /// Gets the squares the cursor moves to in each direction with the typed count.
+    pub fn count_targets(&self) -> Vec<Coord> {
+        let Some(count) = self.motion_count else {
+            return Vec::new();
+        };
+        [CoordEvent::UP, CoordEvent::DOWN, CoordEvent::LEFT, CoordEvent::RIGHT]
+            .into_iter()
+            .map(|event| {
+                let (row, col) = self.cursor_target(event, count);
+                Coord::new(col, row, true)
+            })
+            .collect()
+    }
+
+    /// Rotates the board clockwise by 90°.
+    pub fn rotate_board(&mut self) {
+        self.orientation = self.orientation.next();
+        self.log(&format!("Board rotated by {}°", self.orientation.degrees()));
+    }
+
+    /// Updates the hovered coordinate from the mouse position.
+    pub fn update_hover_coord(&mut self, column: u16, row: u16) {
+        self.hover_coord = self.coord_at(column, row);
+    }
+
+    /// Chooses the square under the mouse as if it was chosen with the keyboard, picking up a piece to drag.
+    ///
+    /// With a piece held, clicking one of its targets plays the move and clicking elsewhere cancels it.
+    pub fn start_drag(&mut self, column: u16, row: u16) {
+        let prompt_open = self.slot_action.is_some()
+            || self.scenario_prompt
+            || self.promotion_menu.is_some()
+            || self.start_menu.is_some();
+        if self.block_inputs || self.handoff || prompt_open || self.command_input.is_some() {
+            return;
+        }
+        let Some(coord) = self.coord_at(column, row) else {
+            return;
+        };
+        self.cursor_coord.move_to(coord.row, coord.col);
+
+        let step = self.selection_step();
+        match step {
+            // Choosing the held piece again would put it back, so it stays held to be dragged
+            SelectionStep::PutBack => {}
+            SelectionStep::Ignore => return,
+            _ => self.update_selected_coord(),
+        }
+        let picked_up = matches!(step, SelectionStep::PickUp | SelectionStep::PutBack);
+        if picked_up && self.selected_coord.active && self.selected_coord.is_on(coord.row, coord.col) {
+            self.drag_position = Some(Position::new(column, row));
+        }
+    }
+
+    /// Moves the dragged piece along with the mouse.
+    pub fn update_drag(&mut self, column: u16, row: u16) {
+        if let Some(position) = &mut self.drag_position {
+            *position = Position::new(column, row);
+        }
+    }
+
+    /// Drops the dragged piece, playing the move if it is released on one of its targets.
+    ///
+    /// Releasing it on its own square keeps it selected, anywhere else cancels the drag.
+    pub fn end_drag(&mut self, column: u16, row: u16) {
+        if self.drag_position.take().is_none() {
+            return;
+        }
+
+        let targets = &self.moves_from_selected_coord;
+        let is_target = |coord: Coord| dest_in_moves(coord.to_square(self.main_player), targets);
+        match self.coord_at(column, row) {
+            Some(coord) if self.selected_coord.is_on(coord.row, coord.col) => {}
+            Some(coord) if is_target(coord) => {
+                self.cursor_coord.move_to(coord.row, coord.col);
+                self.update_selected_coord();
+            }
+            _ => {
+                self.selected_coord.active = false;
+                self.moves_from_selected_coord.clear();
+            }
+        }
+    }
+
+    /// Gets the board shown on the screen, which is a past position while looking back.
+    pub fn displayed_board(&self) -> &pleco::Board {
+        self.viewed_board.as_ref().unwrap_or(&self.board)
+    }
+
+    /// Shows the position after a ply of the game, or the current position from the last ply on.
+    ///
+    /// # Arguments
+    ///
+    /// * `ply` - The number of plies played in the shown position.
+    pub fn view_ply(&mut self, ply: usize) {
+        if ply >= self.played_moves.len() {
+            self.view_current_position();
+            return;
+        }
+
+        let mut board = match &self.start_fen {
+            Some(fen) => pleco::Board::from_fen(fen).unwrap_or_default(),
+            None => pleco::Board::default(),
+        };
+        for bit_move in &self.played_moves[..ply] {
+            board.apply_move(*bit_move);
+        }
+        self.viewed_ply = Some(ply);
+        self.viewed_board = Some(board);
+        self.selected_coord.active = false;
+    }
+
+    /// Steps through the plies of the game.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The number of plies to step, negative to go back.
+    pub fn step_viewed_ply(&mut self, offset: isize) {
+        let ply = self.viewed_ply.unwrap_or(self.played_moves.len());
+        self.view_ply(ply.saturating_add_signed(offset));
+    }
+
+    /// Shows the current position again after looking back.
+    pub fn view_current_position(&mut self) {
+        self.viewed_ply = None;
+        self.viewed_board = None;
+    }
+
+    /// Shows the ply at a position of the timeline.
+    pub fn click_timeline(&mut self, column: u16, row: u16) {
+        if !self.timeline_area.contains(Position::new(column, row)) {
+            return;
+        }
+        let offset = column - self.timeline_area.x;
+        self.view_ply(ply_at_column(offset, self.played_moves.len(), self.timeline_area.width));
+    }
+
+    /// Moves the focus to the next panel to the right, wrapping around to the leftmost one.
+    pub fn cycle_panel_focus(&mut self) {
+        self.focused_panel = match (self.config.panel_order, self.focused_panel) {
+            (PanelOrder::LogLeft, Panel::Log) | (PanelOrder::InfoLeft, Panel::Info) => Panel::Board,
+            (PanelOrder::LogLeft, Panel::Board) | (PanelOrder::InfoLeft, Panel::Log) => Panel::Info,
+            (PanelOrder::LogLeft, Panel::Info) | (PanelOrder::InfoLeft, Panel::Board) => Panel::Log,
+        };
+    }
+
+    /// Scrolls the focused panel.
+    ///
+    /// # Arguments
+    ///
+    /// * `lines` - The number of lines to scroll by, negative values scroll up.
+    pub fn scroll_focused_panel(&mut self, lines: i16) {
+        match self.focused_panel {
+            Panel::Log => {
+                let max = self.log.len().saturating_sub(1) as u16;
+                self.log_scroll = self.log_scroll.saturating_add_signed(lines).min(max);
+            }
+            // The info panel clamps the offset itself, as only it knows its line count
+            Panel::Info => {
+                self.info_scroll = self.info_scroll.saturating_add_signed(lines);
+            }
+            Panel::Board => {}
+        }
+    }
+
+    /// Gets the minimum terminal size needed for the current zoom and layout.
+    pub fn min_terminal_size(&self) -> (u16, u16) {
+        if self.focus_board {
+            focused_board_size(self.zoom, self.config.grid_lines)
+        } else {
+            get_min_terminal_size(self.zoom, self.config.grid_lines)
+        }
+    }
+
+    /// Shows or hides the debug overlay, if it was enabled with the `--debug` flag.
+    pub fn toggle_debug_overlay(&mut self) {
+        self.debug_overlay = self.debug_allowed && !self.debug_overlay;
+    }
+
+    /// Shows only the board and a status line, or the panels again.
+    ///
+    /// The focused board is zoomed in as far as the terminal allows.
+    /// The previous zoom is restored afterwards.
+    pub fn toggle_focus_board(&mut self) {
+        self.focus_board = !self.focus_board;
+        if self.focus_board {
+            self.zoom_before_focus = self.zoom;
+            self.zoom = [7, 5, 3]
+                .into_iter()
+                .find(|zoom| {
+                    let (width, height) = focused_board_size(*zoom, self.config.grid_lines);
+                    width <= self.terminal_size.width && height <= self.terminal_size.height
+                })
+                .unwrap_or(3);
+        } else {
+            self.zoom = self.zoom_before_focus;
+        }
+        self.update_terminal_too_small();
+    }
+
+    /// Gets how the game ended, on the board or by resignation or agreed draw.
+    ///
+    /// # Returns
+    ///
+    /// The result, like `Checkmate!`, or `None` if the game is still going on.
+    pub fn final_result(&self) -> Option<&'static str> {
+        self.adjudication.map(Adjudication::result).or_else(|| game_result(&self.board))
+    }
+
+    /// Gets the state of the game in one line, for the status line of the focused board.
+    pub fn status_line(&self) -> String {
+        if let Some(result) = self.final_result() {
+            return format!("{} {}", result, RESET_HINT);
+        }
+        let check = if self.board.in_check() { ", check" } else { "" };
+        format!("{} to move{}", self.player_label(self.board.turn()), check)
+    }
+
+    /// Updates whether the terminal is too small for the current zoom.
+    pub fn update_terminal_too_small(&mut self) {
+        let (min_width, min_height) = self.min_terminal_size();
+        self.terminal_too_small = self.terminal_size.width < min_width || self.terminal_size.height < min_height;
+    }
+
+    /// Increases the number of rows per cell of the board.
+    pub fn zoom_in(&mut self) {
+        self.zoom = (self.zoom + 2).min(7);
+        self.update_terminal_too_small();
+    }
+
+    /// Decreases the number of rows per cell of the board.
+    pub fn zoom_out(&mut self) {
+        self.zoom = self.zoom.saturating_sub(2).max(3);
+        self.update_terminal_too_small();
+    }
+}
+
+/// Gets the terminal size needed to show only the board and a status line.
+///
+/// # Arguments
+///
+/// * `zoom` - The number of rows per cell.
+/// * `grid_lines` - Whether grid lines are drawn between and around the cells.
+///
+/// # Returns
+///
+/// The width and height of the board with the status line below it.
+fn focused_board_size(zoom: u16, grid_lines: bool) -> (u16, u16) {
+    let (board_width, board_height) = get_board_size(zoom, grid_lines);
+    (board_width, board_height + 1)
+}
+
+/// Replaces the promotion piece of a promoting move.
+///
+/// # Arguments
+///
+/// * `bit_move` - The promoting move.
+/// * `piece_type` - The piece to promote to.
+///
+/// # Returns
+///
+/// The move promoting to `piece_type`.
+fn with_promotion_piece(bit_move: BitMove, piece_type: PieceType) -> BitMove {
+    BitMove::init(PreMoveInfo {
+        src: bit_move.get_src(),
+        dst: bit_move.get_dest(),
+        flags: MoveFlag::Promotion {
+            capture: bit_move.is_capture(),
+            prom: piece_type,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use pleco::SQ;
+    use ratatui::crossterm::event::{ KeyCode, KeyEvent };
+
+    use super::*;
+
+    #[test]
+    fn test_select_own_piece() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+        assert!(app.selected_coord.active);
+        assert_eq!(app.moves_from_selected_coord.len(), 2);
+    }
+
+    #[test]
+    fn test_auto_play_only_move() {
+        let mut app = App::new();
+        app.load_fen("k7/8/8/8/8/8/1r6/K7 w - - 0 1").unwrap();
+        app.cursor_coord.move_to(7, 0);
+        app.update_selected_coord();
+        assert!(app.move_history.is_empty());
+        app.update_selected_coord();
+
+        app.config.auto_play_only_move = true;
+        app.update_selected_coord();
+        assert_eq!(app.move_history, vec!["Kxb2"]);
+        assert!(!app.selected_coord.active);
+    }
+
+    #[test]
+    fn test_keep_selection() {
+        let select_and_play = |app: &mut App, from: (i8, i8), to: (i8, i8)| {
+            app.cursor_coord.move_to(from.0, from.1);
+            app.update_selected_coord();
+            app.cursor_coord.move_to(to.0, to.1);
+            app.update_selected_coord();
+        };
+
+        // By default the selection is dropped after the move
+        let mut app = App::new();
+        app.engine_depth = 1;
+        app.toggle_engine();
+        select_and_play(&mut app, (7, 6), (5, 5));
+        assert_eq!(app.move_history.len(), 2);
+        assert!(!app.selected_coord.active);
+
+        // Kept, the knight is selected again once the engine replied
+        let mut app = App::new();
+        app.engine_depth = 1;
+        app.toggle_keep_selection();
+        app.toggle_engine();
+        select_and_play(&mut app, (7, 6), (5, 5));
+        assert_eq!(app.move_history.len(), 2);
+        assert!(app.selected_coord.active);
+        assert_eq!(app.selected_coord.to_square(app.main_player), SQ::F3);
+        assert!(!app.moves_from_selected_coord.is_empty());
+
+        // Without an engine the other side is to move, so nothing is selected in between
+        app.toggle_engine();
+        app.cursor_coord.move_to(4, 7);
+        app.update_selected_coord();
+        assert_eq!(app.move_history.len(), 3);
+        assert!(!app.selected_coord.active);
+    }
+
+    #[test]
+    fn test_reset_clears_game_state() {
+        let mut app = App::new();
+        app.load_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert!(!app.cursor_coord.active);
+        app.reset();
+        assert!(app.cursor_coord.active && !app.block_inputs);
+
+        app.handle_move(san_to_move("e4", &app.board).unwrap());
+        app.cursor_coord.move_to(1, 3);
+        app.update_selected_coord();
+        assert!(!app.moves_from_selected_coord.is_empty());
+        app.cursor_coord.move_to(0, 6);
+        app.illegal_flash = Some(app.cursor_coord);
+        app.main_player = Player::Black;
+
+        app.reset();
+        assert!(app.moves_from_selected_coord.is_empty());
+        assert!(!app.selected_coord.active);
+        assert_eq!(app.board.last_move(), None);
+        assert_eq!(app.illegal_flash, None);
+        assert!(app.move_history.is_empty() && app.played_moves.is_empty());
+        assert_eq!(app.main_player, Player::Black);
+    }
+
+    #[test]
+    fn test_select_opponent_piece() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(1, 4);
+        app.update_selected_coord();
+        assert!(!app.selected_coord.active);
+        assert!(app.moves_from_selected_coord.is_empty());
+        assert_eq!(app.log.last().unwrap().message, "No White piece on E7");
+    }
+
+    #[test]
+    fn test_confirm_move() {
+        let mut app = App::new();
+        app.toggle_confirm_moves();
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(4, 4);
+        app.update_selected_coord();
+        assert!(app.pending_move.is_some());
+        assert_eq!(app.board.moves_played(), 0);
+
+        app.update_selected_coord();
+        assert!(app.pending_move.is_none());
+        assert_eq!(app.board.moves_played(), 1);
+    }
+
+    #[test]
+    fn test_cancel_pending_move() {
+        let mut app = App::new();
+        app.toggle_confirm_moves();
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(4, 4);
+        app.update_selected_coord();
+        app.cancel_pending_move();
+        assert!(app.pending_move.is_none());
+        assert_eq!(app.board.moves_played(), 0);
+    }
+
+    #[test]
+    fn test_reload_changed_config() {
+        let mut app = App::new();
+        let dir = std::env::temp_dir().join(format!("tui-chess-app-config-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("config.json"), r#"{ "watch_config": true }"#).unwrap();
+        app.config_dir = Some(dir.clone());
+        app.load_config();
+        assert!(app.config.watch_config);
+
+        // Unchanged files are not read again
+        app.handle_events(Ok(Event::Tick)).unwrap();
+        assert!(app.log.is_empty());
+
+        let config = r#"{ "watch_config": true, "crosshair": true, "white_label": "Alice" }"#;
+        std::fs::write(dir.join("config.json"), config).unwrap();
+        app.config_modified = Some(SystemTime::UNIX_EPOCH);
+        app.handle_events(Ok(Event::Tick)).unwrap();
+        assert!(app.config.crosshair);
+        assert_eq!(app.white_label, "Alice");
+        assert_eq!(app.log.last().unwrap().message, "Reloaded config");
+
+        // Invalid edits keep the last configuration and are reported once
+        std::fs::write(dir.join("config.json"), "{ crosshair }").unwrap();
+        app.config_modified = Some(SystemTime::UNIX_EPOCH);
+        app.handle_events(Ok(Event::Tick)).unwrap();
+        app.handle_events(Ok(Event::Tick)).unwrap();
+        assert!(app.config.crosshair);
+        assert_eq!(app.log.len(), 2);
+        assert!(app.log.last().unwrap().message.starts_with("Reloading config failed"));
+
+        // Toggled settings survive reloads which don't change them, removed labels are reset
+        app.toggle_notation();
+        std::fs::write(dir.join("config.json"), r#"{ "watch_config": true, "grid_lines": true }"#).unwrap();
+        app.config_modified = Some(SystemTime::UNIX_EPOCH);
+        app.handle_events(Ok(Event::Key(KeyEvent::from(KeyCode::Char('x'))))).unwrap();
+        assert!(app.config.grid_lines);
+        assert_eq!(app.config.notation, Notation::Coordinate);
+        assert_eq!(app.white_label, "White");
+    }
+
+    #[test]
+    fn test_key_bindings() {
+        let mut app = App::new();
+        app.config.key_bindings.insert('h', 'a');
+        app.cursor_coord.move_to(6, 4);
+        app.handle_events(Ok(Event::Key(KeyEvent::from(KeyCode::Char('h'))))).unwrap();
+        assert_eq!(app.cursor_coord.col, 3);
+
+        // Commands are typed as is
+        app.open_command_line();
+        app.handle_events(Ok(Event::Key(KeyEvent::from(KeyCode::Char('h'))))).unwrap();
+        assert_eq!(app.command_input.as_deref(), Some("h"));
+    }
+
+    #[test]
+    fn test_snapshot_board() {
+        let mut app = App::new();
+        let dir = std::env::temp_dir().join(format!("tui-chess-app-snapshots-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        app.save_dir = Some(dir.clone());
+        app.load_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+
+        app.snapshot_board();
+        let path = dir.join("snapshots.txt");
+        assert_eq!(app.log.last().unwrap().message, format!("Appended a snapshot to {}", path.display()));
+        let snapshot = std::fs::read_to_string(path).unwrap();
+        assert!(snapshot.starts_with("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1\n8 . . . . k . . .\n"));
+        assert!(snapshot.ends_with("2 . . . . P . . .\n1 . . . . K . . .\n  a b c d e f g h\n\n"));
+    }
+
+    #[test]
+    fn test_save_and_load_slot() {
+        let mut app = App::new();
+        let dir = std::env::temp_dir().join(format!("tui-chess-app-slots-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        app.save_dir = Some(dir);
+        app.load_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+
+        app.open_slot_prompt(SlotAction::Save);
+        app.select_slot(2);
+        assert_eq!(app.log.last().unwrap().message, "Saved to slot 2");
+
+        app.reset();
+        app.open_slot_prompt(SlotAction::Load);
+        assert_eq!(app.occupied_slots, vec![2]);
+        app.select_slot(2);
+        assert_eq!(app.board.fen(), "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+
+        app.open_slot_prompt(SlotAction::Load);
+        app.select_slot(5);
+        assert_eq!(app.log.last().unwrap().message, "Slot 5 is empty");
+        assert!(app.slot_action.is_none());
+    }
+
+    #[test]
+    fn test_game_summary_and_pgn() {
+        let mut app = App::new();
+        for san in ["f3", "e5", "g4"] {
+            app.apply_san(san).unwrap();
+        }
+        assert!(app.pgn().ends_with("\n\n1. f3 e5 2. g4 *"));
+        assert!(!app.game_summary);
+
+        app.apply_san("Qh4#").unwrap();
+        assert!(app.game_summary);
+        let roster = [
+            "[Event \"?\"]",
+            "[Site \"?\"]",
+            "[Date \"????.??.??\"]",
+            "[Round \"?\"]",
+            "[White \"White\"]",
+            "[Black \"Black\"]",
+            "[Result \"0-1\"]",
+        ];
+        assert_eq!(app.pgn(), format!("{}\n\n1. f3 e5 2. g4 Qh4# 0-1", roster.join("\n")));
+        app.close_game_summary();
+        assert!(!app.game_summary);
+
+        // Black moving first continues the numbering of the FEN
+        app.load_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 42").unwrap();
+        app.apply_san("Kd7").unwrap();
+        app.apply_san("e4").unwrap();
+        let pgn = app.pgn();
+        let setup = "[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 42\"]";
+        assert!(pgn.contains(&format!("{}\n\n42... Kd7 43. e4 *", setup)));
+        app.load_pgn(&pgn).unwrap();
+        assert_eq!(app.move_history, ["Kd7", "e4"]);
+    }
+
+    #[test]
+    fn test_deepen_analysis() {
+        let mut app = App::new();
+        app.deepen_analysis();
+        app.deepen_analysis();
+        let analysis = app.analysis.clone().unwrap();
+        assert_eq!(analysis.depth, 2);
+        assert_eq!(analysis.pv.len(), 2);
+        assert!(app.log.last().unwrap().message.starts_with("Analysis depth 2: "));
+
+        app.apply_san(&analysis.pv[0]).unwrap();
+        assert_eq!(app.analysis, None);
+        app.deepen_analysis();
+        assert_eq!(app.analysis.unwrap().depth, 1);
+    }
+
+    #[test]
+    fn test_background_analysis() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut app = App::new();
+        app.event_sender = Some(sender);
+        app.deepen_analysis();
+        assert_eq!(app.log.last().unwrap().message, "Analysing at depth 1");
+        app.deepen_analysis();
+        assert_eq!(app.log.last().unwrap().message, "Still analysing, wait for the current depth");
+
+        let event = receiver.blocking_recv().unwrap();
+        app.handle_events(Ok(event)).unwrap();
+        assert_eq!(app.analysis.as_ref().unwrap().depth, 1);
+
+        // A result for a position left meanwhile is dropped
+        app.deepen_analysis();
+        app.apply_san("e4").unwrap();
+        let event = receiver.blocking_recv().unwrap();
+        app.handle_events(Ok(event)).unwrap();
+        assert_eq!(app.analysis, None);
+    }
+
+    #[test]
+    fn test_start_paused() {
+        let mut app = App::new();
+        app.paused = true;
+        app.engine_player = Some(Player::White);
+        app.play_engine_move_if_due();
+        assert!(app.move_history.is_empty());
+
+        app.handle_events(Ok(Event::Key(KeyEvent::from(KeyCode::Char('e'))))).unwrap();
+        assert!(!app.paused);
+        assert_eq!(app.move_history.len(), 1);
+
+        app.handle_events(Ok(Event::Key(KeyEvent::from(KeyCode::Char('e'))))).unwrap();
+        assert_eq!(app.engine_player, None);
+    }
+
+    #[test]
+    fn test_handoff() {
+        let mut app = App::new();
+        app.config.handoff = true;
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(4, 4);
+        app.update_selected_coord();
+        assert!(app.handoff);
+
+        app.end_handoff();
+        assert!(!app.handoff);
+        assert_eq!(app.main_player, Player::Black);
+    }
+
+    #[test]
+    fn test_zoom() {
+        let mut app = App::new();
+        app.terminal_size = Size::new(106, 24);
+
+        app.zoom_in();
+        assert_eq!(app.zoom, 5);
+        assert!(app.terminal_too_small);
+
+        app.zoom_in();
+        app.zoom_in();
+        assert_eq!(app.zoom, 7);
+
+        app.zoom_out();
+        app.zoom_out();
+        app.zoom_out();
+        assert_eq!(app.zoom, 3);
+        assert!(!app.terminal_too_small);
+    }
+
+    #[test]
+    fn test_resize_keeps_game() {
+        use ratatui::crossterm::event::{ KeyModifiers, MouseButton, MouseEvent, MouseEventKind };
+
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut app = App::new();
+        app.event_sender = Some(sender);
+        app.config.min_think_ms = Some(0);
+        app.engine_depth = 1;
+        app.toggle_engine();
+        handle_resize_event(&mut app, 106, 30).unwrap();
+        app.board_area = Rect::new(0, 0, 56, 24);
+        app.handle_move(san_to_move("e4", &app.board).unwrap());
+
+        // The reply of the engine is played on the hidden board
+        handle_resize_event(&mut app, 40, 10).unwrap();
+        assert!(app.terminal_too_small);
+        let log_length = app.log.len();
+        let event = receiver.blocking_recv().unwrap();
+        app.handle_events(Ok(event)).unwrap();
+        handle_resize_event(&mut app, 106, 30).unwrap();
+        assert_eq!(app.move_history.len(), 2);
+        assert!(app.log.len() > log_length);
+
+        // Shrinking drops a drag but keeps its piece selected, and clicks on the hidden board are ignored
+        app.start_drag(43, 19);
+        handle_resize_event(&mut app, 40, 10).unwrap();
+        assert_eq!(app.drag_position, None);
+        let click = MouseEvent {
+            kind: MouseEventKind::Down(MouseButton::Left),
+            column: 8,
+            row: 19,
+            modifiers: KeyModifiers::NONE,
+        };
+        handle_mouse_event(click, &mut app).unwrap();
+        assert!(app.selected_coord.is_on(6, 6));
+
+        handle_resize_event(&mut app, 106, 30).unwrap();
+        assert!(!app.terminal_too_small);
+        assert!(app.selected_coord.active && app.selected_coord.is_on(6, 6));
+        assert_eq!(app.moves_from_selected_coord.len(), 2);
+        assert_eq!(app.board.turn(), Player::White);
+    }
+
+    #[test]
+    fn test_capture_animation() {
+        let mut app = App::new();
+        app.load_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        app.apply_san("exd6").unwrap();
+        assert_eq!(app.capture_animation, None);
+
+        app.config.animations = true;
+        app.load_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        app.apply_san("exd6").unwrap();
+        let animation = app.capture_animation.unwrap();
+        assert_eq!(animation.square, SQ::D5);
+        assert_eq!(animation.piece, pleco::Piece::BlackPawn);
+
+        app.tick().unwrap();
+        assert!(app.capture_animation.is_some());
+        app.capture_animation = Some(CaptureAnimation {
+            started: Instant::now() - CAPTURE_ANIMATION_DURATION,
+            ..animation
+        });
+        app.tick().unwrap();
+        assert_eq!(app.capture_animation, None);
+    }
+
+    #[test]
+    fn test_debug_overlay_needs_flag() {
+        let mut app = App::new();
+        app.toggle_debug_overlay();
+        assert!(!app.debug_overlay);
+
+        app.debug_allowed = true;
+        app.toggle_debug_overlay();
+        assert!(app.debug_overlay);
+        assert!(app.spawn_game().debug_allowed);
+        app.toggle_debug_overlay();
+        assert!(!app.debug_overlay);
+    }
+
+    #[test]
+    fn test_focus_board() {
+        let mut app = App::new();
+        app.terminal_size = Size::new(106, 25);
+        app.update_terminal_too_small();
+        app.zoom_out();
+
+        app.toggle_focus_board();
+        assert_eq!(app.zoom, 3);
+        assert!(!app.terminal_too_small);
+
+        app.terminal_size = Size::new(200, 60);
+        app.toggle_focus_board();
+        app.toggle_focus_board();
+        assert_eq!(app.zoom, 7);
+        assert_eq!(app.min_terminal_size(), (120, 57));
+        assert!(!app.terminal_too_small);
+
+        app.toggle_focus_board();
+        assert_eq!(app.zoom, 3);
+        assert_eq!(app.status_line(), "White to move");
+    }
+
+    #[test]
+    fn test_select_scenario() {
+        let mut app = App::new();
+        app.open_scenario_prompt();
+        app.select_scenario(4);
+        assert!(!app.scenario_prompt);
+        assert_eq!(app.scenario, Some(SCENARIOS[3]));
+        assert_eq!(app.main_player, Player::Black);
+
+        app.reset();
+        assert!(app.scenario.is_none());
+    }
+
+    #[test]
+    fn test_drag_to_move() {
+        let mut app = App::new();
+        app.board_area = Rect::new(0, 0, 56, 24);
+
+        // e2 to e4, with cells of 7 by 3 characters
+        app.start_drag(29, 19);
+        assert!(app.selected_coord.active);
+        app.update_drag(30, 15);
+        assert_eq!(app.drag_position, Some(Position::new(30, 15)));
+        app.end_drag(29, 13);
+        assert_eq!(app.move_history, ["e4"]);
+        assert_eq!(app.drag_position, None);
+
+        // e7 onto the occupied e4 and off the board are cancelled
+        app.start_drag(29, 4);
+        app.end_drag(29, 13);
+        assert!(!app.selected_coord.active);
+        app.start_drag(29, 4);
+        app.end_drag(100, 4);
+        assert!(!app.selected_coord.active);
+        assert_eq!(app.move_history.len(), 1);
+
+        // Pieces of the player not to move can't be picked up
+        app.start_drag(29, 19);
+        assert_eq!(app.drag_position, None);
+    }
+
+    #[test]
+    fn test_click_to_move() {
+        let mut app = App::new();
+        app.board_area = Rect::new(0, 0, 56, 24);
+        let click = |app: &mut App, column, row| {
+            app.start_drag(column, row);
+            app.end_drag(column, row);
+        };
+
+        // e2, then e4
+        click(&mut app, 29, 19);
+        assert!(app.selected_coord.active);
+        click(&mut app, 29, 13);
+        assert_eq!(app.move_history, ["e4"]);
+
+        // Clicking an enemy piece captures it
+        click(&mut app, 22, 4);
+        click(&mut app, 22, 10);
+        click(&mut app, 29, 13);
+        click(&mut app, 22, 10);
+        assert_eq!(app.move_history, ["e4", "d5", "exd5"]);
+        assert!(!app.selected_coord.active);
+    }
+
+    #[test]
+    fn test_hover_coord() {
+        let mut app = App::new();
+        app.board_area = Rect::new(10, 2, 56, 24);
+
+        app.update_hover_coord(10, 2);
+        assert_eq!(app.hover_coord, Some(Coord::new(0, 0, true)));
+
+        app.update_hover_coord(65, 25);
+        assert_eq!(app.hover_coord, Some(Coord::new(7, 7, true)));
+
+        app.update_hover_coord(24, 8);
+        assert_eq!(app.hover_coord, Some(Coord::new(2, 2, true)));
+        assert_eq!(app.cursor_coord, Coord::default());
+
+        app.update_hover_coord(68, 8);
+        assert!(app.hover_coord.is_none());
+    }
+
+    #[test]
+    fn test_scroll_focused_panel() {
+        let mut app = App::new();
+        app.log("first");
+        app.log("second");
+        app.scroll_focused_panel(5);
+        assert_eq!(app.log_scroll, 0);
+
+        app.cycle_panel_focus();
+        assert_eq!(app.focused_panel, Panel::Info);
+        app.scroll_focused_panel(3);
+        app.scroll_focused_panel(-1);
+        assert_eq!(app.info_scroll, 2);
+
+        app.cycle_panel_focus();
+        assert_eq!(app.focused_panel, Panel::Log);
+        app.config.panel_order = PanelOrder::InfoLeft;
+        app.cycle_panel_focus();
+        assert_eq!(app.focused_panel, Panel::Info);
+        app.cycle_panel_focus();
+        app.cycle_panel_focus();
+        assert_eq!(app.focused_panel, Panel::Log);
+        app.scroll_focused_panel(5);
+        assert_eq!(app.log_scroll, 1);
+        app.scroll_focused_panel(-5);
+        assert_eq!(app.log_scroll, 0);
+    }
+
+    #[test]
+    fn test_out_of_turn_move() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+
+        // The engine takes over white while e2 is held
+        app.engine_player = Some(Player::White);
+        app.cursor_coord.move_to(4, 4);
+        app.update_selected_coord();
+        assert_eq!(app.log.last().unwrap().message, "Not your turn");
+        assert_eq!(app.log.last().unwrap().kind, LogKind::Warning);
+        assert!(app.played_moves.is_empty());
+
+        app.engine_player = Some(Player::Black);
+        app.update_selected_coord();
+        assert_eq!(app.move_history[0], "e4");
+    }
+
+    #[test]
+    fn test_selection_steps() {
+        let mut app = App::new();
+        let select = |app: &mut App, row, col| {
+            app.cursor_coord.move_to(row, col);
+            let step = app.selection_step();
+            app.update_selected_coord();
+            step
+        };
+
+        // Pick up and put back
+        assert_eq!(select(&mut app, 6, 4), SelectionStep::PickUp);
+        assert!(app.selected_coord.active);
+        assert_eq!(app.moves_from_selected_coord.len(), 2);
+        assert_eq!(select(&mut app, 6, 4), SelectionStep::PutBack);
+        assert!(!app.selected_coord.active);
+
+        // Re-pick another piece while holding one
+        select(&mut app, 6, 4);
+        assert_eq!(select(&mut app, 7, 6), SelectionStep::PickUp);
+        assert!(app.selected_coord.is_on(7, 6) && app.selected_coord.active);
+
+        // Drop on an illegal target cancels
+        assert_eq!(select(&mut app, 4, 6), SelectionStep::Cancel);
+        assert!(!app.selected_coord.active);
+        assert_eq!(app.log.last().unwrap().message, "Can't move to G4, selection cancelled");
+        assert!(app.played_moves.is_empty());
+
+        // Drop on a legal target moves
+        select(&mut app, 7, 6);
+        assert_eq!(select(&mut app, 5, 5), SelectionStep::Drop);
+        assert_eq!(app.move_history, ["Nf3"]);
+
+        // Nothing to pick up on an empty square
+        assert_eq!(select(&mut app, 4, 4), SelectionStep::Ignore);
+        assert!(!app.selected_coord.active);
+
+        // Clicks take the same steps, with cells of 7 by 3 characters
+        app.board_area = Rect::new(0, 0, 56, 24);
+        let click = |app: &mut App, column, row| {
+            app.start_drag(column, row);
+            app.end_drag(column, row);
+        };
+        click(&mut app, 29, 4);
+        assert!(app.selected_coord.is_on(1, 4) && app.selected_coord.active);
+        click(&mut app, 29, 13);
+        assert!(!app.selected_coord.active);
+        assert_eq!(app.log.last().unwrap().message, "Can't move to E4, selection cancelled");
+
+        click(&mut app, 29, 4);
+        click(&mut app, 29, 10);
+        assert_eq!(app.move_history, ["Nf3", "e5"]);
+    }
+
+    #[test]
+    fn test_illegal_flash() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(3, 4);
+        app.update_selected_coord();
+        assert!(app.illegal_flash.is_none());
+
+        // The illegal target let go of the pawn, so it is picked up again
+        app.config.flash_illegal_moves = true;
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(3, 4);
+        app.update_selected_coord();
+        assert_eq!(app.illegal_flash, Some(app.cursor_coord));
+
+        app.tick().unwrap();
+        assert!(app.illegal_flash.is_none());
+
+        // Without ticks the next key clears it
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(3, 4);
+        app.update_selected_coord();
+        app.handle_events(Ok(Event::Key(KeyEvent::from(KeyCode::Char('w'))))).unwrap();
+        assert!(app.illegal_flash.is_none());
+    }
+
+    #[test]
+    fn test_move_log_kinds() {
+        let mut app = App::new();
+        for (src, dest) in [(SQ::E2, SQ::E4), (SQ::D7, SQ::D5), (SQ::E4, SQ::D5)] {
+            app.handle_move(move_to_square(dest, &moves_from_square(src, &app.board)));
+        }
+        assert_eq!(app.log.last().unwrap().kind, LogKind::Capture);
+
+        for (src, dest) in [(SQ::E7, SQ::E6), (SQ::F1, SQ::B5)] {
+            app.handle_move(move_to_square(dest, &moves_from_square(src, &app.board)));
+        }
+        assert_eq!(app.log.last().unwrap().kind, LogKind::Check);
+
+        app.load_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        app.handle_move(move_to_square(SQ::A8, &moves_from_square(SQ::A1, &app.board)));
+        assert_eq!(app.log.last().unwrap().kind, LogKind::Checkmate);
+    }
+
+    #[test]
+    fn test_move_log_format() {
+        let mut app = App::new();
+        for (src, dest) in [(SQ::E2, SQ::E4), (SQ::E7, SQ::E5)] {
+            app.handle_move(move_to_square(dest, &moves_from_square(src, &app.board)));
+        }
+        let messages: Vec<&str> = app.log.iter().map(|entry| entry.message.as_str()).collect();
+        assert_eq!(messages, vec!["1. e4", "1... e5"]);
+    }
+
+    #[test]
+    fn test_apply_san() {
+        let mut app = App::new();
+        assert_eq!(app.legal_moves().len(), 20);
+        assert!(app.apply_san("e5").is_err());
+
+        for san in ["f3", "e5", "g4", "Qh4#"] {
+            app.apply_san(san).unwrap();
+        }
+        assert!(app.is_over());
+        assert!(app.current_board().checkmate());
+        assert_eq!(app.current_player(), Player::White);
+        assert!(app.legal_moves().is_empty());
+        assert_eq!(app.move_history, vec!["f3", "e5", "g4", "Qh4#"]);
+        assert!(app.apply_san("Kf2").is_err());
+    }
+
+    #[test]
+    fn test_play_random_move() {
+        let mut app = App::new();
+        app.rng = fastrand::Rng::with_seed(7);
+        app.play_random_move();
+        assert_eq!(app.move_history.len(), 1);
+        assert_eq!(app.board.turn(), Player::Black);
+
+        let played = app.played_moves[0];
+        assert!(pleco::Board::default().generate_moves().contains(&played));
+
+        // Nothing happens once the game is over
+        app.load_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        app.play_random_move();
+        assert!(app.move_history.is_empty());
+    }
+
+    #[test]
+    fn test_random_game_keeps_invariants() {
+        let mut app = App::new();
+        app.rng = fastrand::Rng::with_seed(11);
+        for _ in 0..300 {
+            if is_game_over(&app.board) {
+                break;
+            }
+            let turn = app.board.turn();
+            app.play_random_move();
+            assert_eq!(app.board.turn(), turn.other_player());
+            assert_eq!(app.board.moves_played() as usize, app.move_history.len());
+        }
+
+        assert!(app.move_history.len() > 20);
+        assert!(!app.log.iter().any(|entry| entry.message.starts_with("Invariant violated")));
+    }
+
+    #[test]
+    fn test_thinking_time() {
+        let mut app = App::new();
+        app.turn_started = Instant::now() - Duration::from_secs(5);
+        app.apply_san("e4").unwrap();
+        app.turn_started = Instant::now() - Duration::from_secs(3);
+        app.apply_san("e5").unwrap();
+        app.turn_started = Instant::now() - Duration::from_secs(2);
+        app.apply_san("Nf3").unwrap();
+
+        assert_eq!(app.time_spent[Player::White as usize].as_secs(), 7);
+        assert_eq!(app.time_spent[Player::Black as usize].as_secs(), 3);
+        assert_eq!(app.thinking_time(Player::Black).as_secs(), 3);
+
+        app.reset();
+        assert_eq!(app.time_spent, [Duration::ZERO; 2]);
+    }
+
+    #[test]
+    fn test_engine_replies() {
+        let mut app = App::new();
+        app.toggle_engine();
+        assert_eq!(app.engine_player, Some(Player::Black));
+
+        app.handle_move(move_to_square(SQ::E4, &moves_from_square(SQ::E2, &app.board)));
+        assert_eq!(app.move_history.len(), 2);
+        assert_eq!(app.board.turn(), Player::White);
+        assert_eq!(app.log.last().unwrap().message, format!("1... {}", app.move_history[1]));
+        assert_eq!(app.engine_stats.unwrap().depth, ENGINE_DEPTH);
+
+        app.reset();
+        assert_eq!(app.engine_stats, None);
+    }
+
+    #[test]
+    fn test_engine_skill() {
+        let mut app = App::new();
+        app.rng = fastrand::Rng::with_seed(3);
+        app.engine_skill = Some(0);
+        app.main_player = Player::Black;
+        app.toggle_engine();
+        assert_eq!(app.player_label(Player::White), "White (engine, depth 1, skill 0)");
+
+        // Without the limit the engine always mates with Ra8#
+        let mut missed_mate = false;
+        for _ in 0..10 {
+            app.load_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+            assert_eq!(app.move_history.len(), 1);
+            missed_mate |= !app.board.checkmate();
+        }
+        assert!(missed_mate);
+    }
+
+    #[test]
+    fn test_force_engine_move() {
+        let (sender, _receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut app = App::new();
+        app.force_engine_move().unwrap();
+        assert!(app.move_history.is_empty());
+
+        app.event_sender = Some(sender);
+        app.engine_depth = 5;
+        app.toggle_engine();
+        app.handle_move(san_to_move("e4", &app.board).unwrap());
+        assert_eq!(app.move_history.len(), 1);
+
+        while app.engine_search.as_ref().unwrap().best_move().is_none() {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        app.force_engine_move().unwrap();
+        assert!(app.engine_search.is_none());
+        assert_eq!(app.move_history.len(), 2);
+
+        // A result arriving after forcing the move is ignored
+        app.finish_engine_search(1, san_to_move("Nf3", &app.board).unwrap());
+        assert_eq!(app.move_history.len(), 2);
+    }
+
+    #[test]
+    fn test_background_engine_move() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut app = App::new();
+        app.event_sender = Some(sender);
+        app.engine_depth = 2;
+        app.toggle_engine();
+        app.handle_move(san_to_move("e4", &app.board).unwrap());
+
+        let event = receiver.blocking_recv().unwrap();
+        app.handle_events(Ok(event)).unwrap();
+        assert_eq!(app.move_history.len(), 2);
+        assert!(app.engine_search.is_none());
+    }
+
+    #[test]
+    fn test_min_think_time() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let mut app = App::new();
+        app.event_sender = Some(sender);
+        app.config.min_think_ms = Some(500);
+        app.engine_depth = 1;
+        app.toggle_engine();
+        let started = Instant::now();
+        app.handle_move(san_to_move("e4", &app.board).unwrap());
+
+        // The move is found at once at depth 1 but only sent after the minimum time
+        let event = receiver.blocking_recv().unwrap();
+        assert!(started.elapsed() >= Duration::from_millis(500));
+        app.handle_events(Ok(event)).unwrap();
+        assert_eq!(app.move_history.len(), 2);
+    }
+
+    #[test]
+    fn test_swap_engine_side() {
+        let mut app = App::new();
+        app.toggle_engine();
+        app.swap_engine_side();
+        assert_eq!(app.engine_player, Some(Player::White));
+        assert_eq!(app.main_player, Player::Black);
+        assert_eq!(app.move_history.len(), 1);
+        assert_eq!(app.board.turn(), Player::Black);
+    }
+
+    #[test]
+    fn test_load_pgn() {
+        let mut app = App::new();
+        app.load_pgn("1. e4 {Best by test} e5 2. Nf3 (2. f4 exf4) Nc6 $1 3. Bb5 *").unwrap();
+        assert_eq!(app.move_history, ["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+        assert_eq!(app.board.turn(), Player::Black);
+        assert_eq!(app.main_player, Player::Black);
+
+        assert!(app.load_pgn("1. e4 e5 2. Ke3").is_err());
+        assert_eq!(app.move_history.len(), 5);
+    }
+
+    #[test]
+    fn test_load_env_position() {
+        std::env::set_var(FEN_ENV_VAR, "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1");
+        std::env::set_var(MOVES_ENV_VAR, "e4 Kd7");
+        let mut app = App::new();
+        app.load_env_position();
+        assert_eq!(app.board.fen(), "8/3k4/8/8/4P3/8/8/4K3 w - - 1 2");
+        assert_eq!(app.move_history, ["e4", "Kd7"]);
+        assert_eq!(app.log.last().unwrap().message, "Loaded the starting position from TUI_CHESS_FEN");
+
+        std::env::set_var(FEN_ENV_VAR, "4k3/8/8/8/8/8/4P3/4K3 x - - 0 1");
+        let mut app = App::new();
+        app.load_env_position();
+        assert_eq!(app.board.fen(), pleco::Board::default().fen());
+        assert_eq!(app.log.last().unwrap().kind, LogKind::Error);
+
+        std::env::remove_var(FEN_ENV_VAR);
+        std::env::remove_var(MOVES_ENV_VAR);
+    }
+
+    #[test]
+    fn test_training_wheels() {
+        let mut app = App::new();
+        app.config.training_wheels = true;
+        app.load_fen("rnbqkbnr/pppp1ppp/8/4p3/8/5P2/PPPPP1PP/RNBQKBNR w KQkq - 0 2").unwrap();
+
+        app.cursor_coord.move_to(6, 6);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(4, 6);
+        app.update_selected_coord();
+        assert!(app.pending_move.is_some());
+        assert_eq!(app.log[app.log.len() - 2].message, "Careful: Qh4# is mate");
+
+        app.update_selected_coord();
+        assert!(app.pending_move.is_none());
+        assert_eq!(app.board.turn(), Player::Black);
+    }
+
+    #[test]
+    fn test_promotion_menu() {
+        let mut app = App::new();
+        app.load_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        app.cursor_coord.move_to(1, 1);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(0, 1);
+        app.update_selected_coord();
+        assert_eq!(app.promotion_menu.unwrap().selected, 0);
+
+        app.move_promotion_selection(5);
+        app.move_promotion_selection(-1);
+        app.confirm_promotion();
+        assert!(app.promotion_menu.is_none());
+        assert_eq!(app.board.piece_at_sq(SQ::B8), pleco::Piece::WhiteBishop);
+    }
+
+    #[test]
+    fn test_confirm_promotion() {
+        let mut app = App::new();
+        app.load_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        app.toggle_confirm_moves();
+
+        app.cursor_coord.move_to(1, 1);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(0, 1);
+        app.update_selected_coord();
+        app.select_promotion(3);
+        app.confirm_promotion();
+        assert!(app.pending_move.is_some());
+        assert_eq!(app.board.moves_played(), 0);
+
+        // Enter plays the chosen piece instead of opening the menu again
+        app.handle_events(Ok(Event::Key(KeyEvent::from(KeyCode::Enter)))).unwrap();
+        assert!(app.pending_move.is_none() && app.promotion_menu.is_none());
+        assert_eq!(app.board.piece_at_sq(SQ::B8), pleco::Piece::WhiteKnight);
+    }
+
+    #[test]
+    fn test_under_promotion_by_each_color() {
+        let mut app = App::new();
+        app.load_fen("4k3/1P6/8/8/8/8/6p1/4K3 w - - 0 1").unwrap();
+
+        // White takes a knight
+        app.cursor_coord.move_to(1, 1);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(0, 1);
+        app.update_selected_coord();
+        app.select_promotion(3);
+        app.confirm_promotion();
+        assert_eq!(app.board.piece_at_sq(SQ::B8), pleco::Piece::WhiteKnight);
+
+        // Black independently takes a rook
+        app.cursor_coord.move_to(6, 6);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(7, 6);
+        app.update_selected_coord();
+        assert_eq!(app.promotion_menu.unwrap().selected, 0);
+        app.select_promotion(1);
+        app.confirm_promotion();
+        assert_eq!(app.board.piece_at_sq(SQ::G1), pleco::Piece::BlackRook);
+        assert_eq!(app.move_history, ["b8=N", "g1=R+"]);
+    }
+
+    #[test]
+    fn test_auto_queen() {
+        let mut app = App::new();
+        app.config.auto_queen = true;
+        app.load_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+
+        app.cursor_coord.move_to(1, 1);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(0, 1);
+        app.update_selected_coord();
+        assert!(app.promotion_menu.is_none());
+        assert_eq!(app.board.piece_at_sq(SQ::B8), pleco::Piece::WhiteQueen);
+    }
+
+    #[test]
+    fn test_save_round_trip() {
+        let mut app = App::new();
+        app.load_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+        for (src, dest) in [(SQ::E2, SQ::E4), (SQ::E8, SQ::D7)] {
+            app.handle_move(move_to_square(dest, &moves_from_square(src, &app.board)));
+        }
+        app.main_player = Player::Black;
+
+        let json = serde_json::to_string(&app.to_save()).unwrap();
+        let restored = App::from_save(serde_json::from_str(&json).unwrap()).unwrap();
+        assert_eq!(restored.board.fen(), app.board.fen());
+        assert_eq!(restored.move_history, ["e4", "Kd7"]);
+        assert_eq!(restored.start_fen, app.start_fen);
+        assert_eq!(restored.main_player, Player::Black);
+        assert_eq!(restored.to_save(), app.to_save());
+    }
+
+    #[test]
+    fn test_marks_in_save() {
+        let mut app = App::new();
+        for san in ["e4", "e5"] {
+            app.handle_move(san_to_move(san, &app.board).unwrap());
+        }
+        app.cursor_coord.move_to(7, 6);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(5, 5);
+        app.toggle_mark();
+        app.selected_coord.active = false;
+        app.view_ply(1);
+        app.cursor_coord.move_to(4, 4);
+        app.toggle_mark();
+        app.toggle_mark();
+        assert!(app.shown_marks().is_empty());
+        app.toggle_mark();
+
+        let state = app.to_save();
+        assert_eq!(state.annotations[&1], ["e4"]);
+        assert_eq!(state.annotations[&2], ["g1f3"]);
+        let mut restored = App::from_save(state).unwrap();
+        assert_eq!(restored.marks, app.marks);
+        assert_eq!(restored.shown_marks(), [Mark::Arrow(SQ::G1, SQ::F3)]);
+
+        restored.clear_annotations();
+        assert!(restored.shown_marks().is_empty());
+        assert_eq!(restored.marks[&1], [Mark::Circle(SQ::E4)]);
+    }
+
+    #[test]
+    fn test_self_play_adjudication() {
+        let mut app = App::new();
+        app.load_fen("4k3/8/8/8/8/8/8/QQ2K3 b - - 0 1").unwrap();
+        app.self_play = true;
+        assert!(!app.adjudicate_self_play());
+        assert!(!app.adjudicate_self_play());
+        assert!(app.adjudicate_self_play());
+        // The exact score depends on the evaluation, only its side matters
+        assert!(app.log.last().unwrap().message.starts_with("Black resigns at +"));
+        assert!(!app.self_play && app.block_inputs);
+        assert!(app.game_summary);
+        assert_eq!(app.adjudication, Some(Adjudication::Resigned(Player::Black)));
+        assert!(app.pgn().ends_with("\n\n1-0"));
+
+        app.load_fen("3rk3/8/8/8/8/8/8/3RK3 w - - 0 1").unwrap();
+        app.self_play = true;
+        app.config.self_play.draw_pieces = 3;
+        assert!(!app.adjudicate_self_play());
+        app.config.self_play.draw_pieces = 4;
+        assert!(app.adjudicate_self_play());
+        assert!(app.log.last().unwrap().message.starts_with("Draw agreed at "));
+        assert_eq!(app.final_result(), Some("Draw agreed!"));
+    }
+
+    #[test]
+    fn test_clear_annotations() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+        app.toggle_move_preview();
+        app.toggle_move_arrows();
+        app.toggle_material_bar();
+        app.hover_coord = Some(Coord::new(2, 2, true));
+
+        app.clear_annotations();
+        assert!(!app.move_preview && !app.move_arrows && !app.material_bar);
+        assert!(app.hover_coord.is_none());
+        assert!(app.selected_coord.active);
+        assert_eq!(app.log.last().unwrap().message, "Cleared annotations");
+    }
+
+    #[test]
+    fn test_repetition_count() {
+        let mut app = App::new();
+        assert_eq!(app.repetition_count(), 1);
+
+        for _ in 0..2 {
+            for san in ["Nf3", "Nf6", "Ng1", "Ng8"] {
+                app.handle_move(san_to_move(san, &app.board).unwrap());
+            }
+        }
+        assert_eq!(app.repetition_count(), 3);
+
+        app.reset();
+        assert_eq!(app.repetition_count(), 1);
+    }
+
+    #[test]
+    fn test_paste_input() {
+        let mut app = App::new();
+        app.paste_input("e4\n").unwrap();
+        app.paste_input("e7e5").unwrap();
+        assert_eq!(app.move_history, ["e4", "e5"]);
+
+        assert!(matches!(app.paste_input("Ke3"), Err(AppError::IllegalMove(_))));
+        assert_eq!(app.move_history.len(), 2);
+
+        app.paste_input("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1").unwrap();
+        assert_eq!(app.board.turn(), Player::Black);
+        assert!(app.move_history.is_empty());
+    }
+
+    #[test]
+    fn test_idle_dim() {
+        use ratatui::crossterm::event::{ KeyCode, KeyEvent, KeyModifiers };
+
+        let mut app = App::new();
+        app.last_input -= Duration::from_secs(60);
+        app.tick().unwrap();
+        assert!(!app.dimmed);
+
+        app.config.idle_dim_seconds = Some(30);
+        app.tick().unwrap();
+        assert!(app.dimmed);
+
+        // The waking key still moves the cursor
+        let key = KeyEvent::new(KeyCode::Up, KeyModifiers::NONE);
+        app.handle_events(Ok(Event::Key(key))).unwrap();
+        assert!(!app.dimmed);
+        assert_ne!(app.cursor_coord, Coord::default());
+        app.tick().unwrap();
+        assert!(!app.dimmed);
+    }
+
+    #[test]
+    fn test_log_legal_moves() {
+        let mut app = App::new();
+        app.log_legal_moves();
+
+        let messages: Vec<&str> = app.log.iter().rev().map(|entry| entry.message.as_str()).collect();
+        assert_eq!(messages[0], "Legal moves (20) for White:");
+        assert_eq!(messages[1], "  Na3 Nc3 Nf3 Nh3 a3 a4 b3 b4");
+        assert_eq!(messages[3], "  g3 g4 h3 h4");
+    }
+
+    #[test]
+    fn test_toggle_notation() {
+        let mut app = App::new();
+        app.toggle_notation();
+        app.handle_move(move_to_square(SQ::F3, &moves_from_square(SQ::G1, &app.board)));
+        assert_eq!(app.log.last().unwrap().message, "1. g1f3");
+        assert_eq!(app.notated_history(), vec!["g1f3"]);
+        assert_eq!(app.move_history, vec!["Nf3"]);
+
+        app.toggle_notation();
+        assert_eq!(app.notated_history(), vec!["Nf3"]);
+    }
+
+    #[test]
+    fn test_view_ply() {
+        let mut app = App::new();
+        for san in ["e4", "e5", "Nf3"] {
+            app.handle_move(san_to_move(san, &app.board).unwrap());
+        }
+        let mut after_e5 = pleco::Board::default();
+        for san in ["e4", "e5"] {
+            after_e5.apply_move(san_to_move(san, &after_e5).unwrap());
+        }
+
+        app.step_viewed_ply(-1);
+        assert_eq!(app.viewed_ply, Some(2));
+        assert_eq!(app.displayed_board().fen(), after_e5.fen());
+        app.step_viewed_ply(-5);
+        assert_eq!(app.viewed_ply, Some(0));
+        assert_eq!(app.displayed_board().fen(), pleco::Board::default().fen());
+
+        app.timeline_area = Rect::new(10, 30, 31, 1);
+        app.click_timeline(25, 30);
+        assert_eq!(app.viewed_ply, Some(2));
+        app.click_timeline(40, 30);
+        assert_eq!(app.viewed_ply, None);
+
+        // Selecting a square goes back to the current position first
+        app.step_viewed_ply(-1);
+        app.update_selected_coord();
+        assert_eq!(app.viewed_ply, None);
+        assert_eq!(app.displayed_board().fen(), app.board.fen());
+    }
+
+    #[test]
+    fn test_uci_move_list() {
+        let mut app = App::new();
+        assert_eq!(app.uci_move_list(), "");
+        for san in ["e4", "e5", "Nf3", "Nc6", "Bc4", "Nf6", "O-O"] {
+            app.apply_san(san).unwrap();
+        }
+        assert_eq!(app.uci_move_list(), "e2e4 e7e5 g1f3 b8c6 f1c4 g8f6 e1g1");
+    }
+
+    #[test]
+    fn test_annotate_moves() {
+        let mut app = App::new();
+        app.handle_move(san_to_move("e4", &app.board).unwrap());
+        assert_eq!(app.move_evals, [None]);
+
+        app.config.annotate_moves = true;
+        app.load_fen("4k3/3p4/8/8/Q7/8/8/4K3 w - - 0 1").unwrap();
+        app.handle_move(san_to_move("Qc6", &app.board).unwrap());
+        assert_eq!(app.move_evals.len(), 1);
+        assert_eq!(app.move_evals[0].unwrap().marker(), "??");
+        assert!(app.log.last().unwrap().message.contains("Qc6??"));
+    }
+
+    #[test]
+    fn test_coordinate_training() {
+        let mut app = App::new();
+        app.toggle_coordinate_training();
+        let target = app.coordinate_training.unwrap().target;
+
+        let coord = Coord::from_square(target, app.main_player);
+        app.cursor_coord.move_to(coord.row, coord.col);
+        app.answer_coordinate_training();
+        let training = app.coordinate_training.unwrap();
+        assert_eq!((training.correct, training.attempts), (1, 1));
+        assert_ne!(training.target, target);
+        assert_eq!(app.log.last().unwrap().kind, LogKind::Success);
+
+        app.toggle_coordinate_training();
+        assert!(app.coordinate_training.is_none());
+    }
+
+    #[test]
+    fn test_shutdown_event_stops_loop() {
+        let mut app = App::new();
+        app.handle_events(Ok(Event::Shutdown)).unwrap();
+        assert!(!app.running);
+    }
+
+    #[test]
+    fn test_rotated_cursor_and_mouse() {
+        let mut app = App::new();
+        app.rotate_board();
+        assert_eq!(app.orientation, Orientation::Deg90);
+
+        // a1 is drawn in the top left corner, so moving right on the screen goes up the a-file
+        app.cursor_coord.move_to(7, 0);
+        app.move_cursor(CoordEvent::RIGHT);
+        assert_eq!(app.cursor_coord.to_square(app.main_player), SQ::A2);
+        app.move_cursor(CoordEvent::DOWN);
+        assert_eq!(app.cursor_coord.to_square(app.main_player), SQ::B2);
+
+        app.board_area = Rect::new(0, 0, 56, 24);
+        let (cell_width, _) = get_cell_pitch(app.zoom, false);
+        let top_right = app.coord_at(cell_width * 8 - 1, 0).unwrap();
+        assert_eq!(top_right.to_square(app.main_player), SQ::A8);
+    }
+
+    #[test]
+    fn test_mirror_position() {
+        let mut app = App::new();
+        app.apply_san("e4").unwrap();
+        app.apply_san("d5").unwrap();
+        let fen = app.board.fen();
+
+        app.run_command(Command::Mirror);
+        assert_eq!(app.board.turn(), Player::Black);
+        assert_eq!(app.board.piece_at_sq(SQ::E5), pleco::Piece::BlackPawn);
+        assert_eq!(app.log.last().unwrap().message, "Mirrored the position");
+
+        app.mirror_position().unwrap();
+        assert_eq!(app.board.fen(), fen);
+    }
+
+    #[test]
+    fn test_cursor_repeat_limit() {
+        let mut app = App::new();
+        app.config.cursor_repeat_ms = Some(100);
+        let right = || Ok(Event::Key(KeyEvent::from(KeyCode::Right)));
+        for _ in 0..3 {
+            app.handle_events(right()).unwrap();
+        }
+        assert_eq!(app.cursor_coord.col, 1);
+
+        // Other keys pass, and the cursor moves again once the time passed
+        app.handle_events(Ok(Event::Key(KeyEvent::from(KeyCode::Char('v'))))).unwrap();
+        assert!(app.move_preview);
+        app.last_cursor_key = Some(Instant::now() - Duration::from_millis(100));
+        app.handle_events(right()).unwrap();
+        assert_eq!(app.cursor_coord.col, 2);
+
+        app.config.cursor_repeat_ms = None;
+        app.handle_events(right()).unwrap();
+        app.handle_events(right()).unwrap();
+        assert_eq!(app.cursor_coord.col, 4);
+    }
+
+    #[test]
+    fn test_start_menu() {
+        let mut app = App::new();
+        app.paused = true;
+        app.open_start_menu();
+        assert!(!app.paused);
+        app.move_start_selection(3);
+        app.change_start_skill(-4);
+        app.confirm_start_choice();
+        assert!(app.start_menu.is_some());
+
+        app.move_start_selection(-1);
+        app.confirm_start_choice();
+        assert!(app.start_menu.is_none());
+        assert_eq!(app.main_player, Player::Black);
+        assert_eq!(app.engine_player, Some(Player::White));
+        assert_eq!(app.engine_skill, Some(16));
+        assert_eq!(app.played_moves.len(), 1);
+
+        let mut app = App::new();
+        app.open_start_menu();
+        app.move_start_selection(4);
+        app.confirm_start_choice();
+        assert_eq!(app.command_input.as_deref(), Some("fen "));
+        assert!(app.engine_player.is_none());
+    }
+
+    #[test]
+    fn test_motion_count() {
+        let mut app = App::new();
+        let press = |app: &mut App, code| app.handle_events(Ok(Event::Key(KeyEvent::from(code)))).unwrap();
+        press(&mut app, KeyCode::Char('3'));
+        assert_eq!(app.count_targets()[3], Coord::new(3, 7, true));
+        press(&mut app, KeyCode::Right);
+        assert_eq!(app.cursor_coord.col, 3);
+        assert!(app.motion_count.is_none());
+
+        // Counts stop at the edge of the board
+        for code in [KeyCode::Char('1'), KeyCode::Char('2'), KeyCode::Right] {
+            press(&mut app, code);
+        }
+        assert_eq!(app.cursor_coord.col, 7);
+
+        // Other keys drop the count, and a leading zero is no count
+        for code in [KeyCode::Char('5'), KeyCode::Char('v'), KeyCode::Char('0'), KeyCode::Left] {
+            press(&mut app, code);
+        }
+        assert_eq!(app.cursor_coord.col, 6);
+    }
+
+    #[test]
+    fn test_cycle_movable_pieces() {
+        let mut app = App::new();
+        app.load_fen("4k3/8/8/8/8/8/P7/R3K2N w - - 0 1").unwrap();
+        let cursor_square = |app: &App| app.cursor_coord.to_square(app.main_player);
+
+        // The a1 rook is stuck behind its pawn, the cursor starts on it and wraps around from h1 to a2
+        app.cursor_coord = Coord::from_square(SQ::A1, Player::White);
+        app.cycle_movable_pieces(true);
+        assert_eq!(cursor_square(&app), SQ::E1);
+        app.cycle_movable_pieces(true);
+        assert_eq!(cursor_square(&app), SQ::H1);
+        app.cycle_movable_pieces(true);
+        assert_eq!(cursor_square(&app), SQ::A2);
+        app.cycle_movable_pieces(false);
+        assert_eq!(cursor_square(&app), SQ::H1);
+
+        app.load_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        app.cycle_movable_pieces(true);
+        assert_eq!(app.log.last().unwrap().message, "No piece can move");
+    }
+
+    #[test]
+    fn test_clicks_near_the_board_hit_the_nearest_square() {
+        let mut app = App::new();
+        app.board_area = Rect::new(4, 2, 56, 24);
+        let square_at = |app: &App, column, row| {
+            app.coord_at(column, row).map(|coord| coord.to_square(Player::White))
+        };
+
+        // Cells are 7 columns wide and 3 rows high, so e2 spans columns 32 to 38 and rows 20 to 22
+        assert_eq!(square_at(&app, 32, 20), Some(SQ::E2));
+        assert_eq!(square_at(&app, 38, 22), Some(SQ::E2));
+        assert_eq!(square_at(&app, 39, 22), Some(SQ::F2));
+        assert_eq!(square_at(&app, 38, 23), Some(SQ::E1));
+
+        // Just outside the edges and corners the nearest square is taken
+        assert_eq!(square_at(&app, 3, 12), Some(SQ::A5));
+        assert_eq!(square_at(&app, 2, 26), Some(SQ::A1));
+        assert_eq!(square_at(&app, 61, 1), Some(SQ::H8));
+        assert_eq!(square_at(&app, 35, 26), Some(SQ::E1));
+
+        // Further away the position does not belong to the board
+        assert_eq!(square_at(&app, 1, 12), None);
+        assert_eq!(square_at(&app, 35, 27), None);
+        assert_eq!(square_at(&app, 62, 0), None);
+    }
+
+    #[test]
+    fn test_opening_explorer() {
+        let mut app = App::new();
+        app.toggle_opening_explorer();
+        app.move_explorer_selection(1);
+        assert_eq!(app.explorer_moves()[1].san, "d4");
+        app.play_explorer_move();
+        assert_eq!(app.move_history, ["d4"]);
+        assert_eq!(app.opening_explorer, Some(0));
+
+        app.move_explorer_selection(5);
+        assert_eq!(app.opening_explorer, Some(1));
+        app.play_explorer_move();
+        assert_eq!(app.move_history, ["d4", "Nf6"]);
+
+        app.apply_san("h4").unwrap();
+        assert!(app.explorer_moves().is_empty());
+        app.play_explorer_move();
+        assert_eq!(app.move_history.len(), 3);
+        app.toggle_opening_explorer();
+        assert_eq!(app.opening_explorer, None);
+    }
+
+    #[test]
+    fn test_practice_opening() {
+        let mut app = App::new();
+        app.practice_opening("C60");
+        assert_eq!(app.opening.unwrap().name, "Ruy Lopez");
+        assert_eq!(app.move_history, ["e4", "e5", "Nf3", "Nc6", "Bb5"]);
+        assert_eq!(app.main_player, Player::Black);
+        assert_eq!(app.engine_player, Some(Player::White));
+
+        app.practice_opening("Dutch");
+        assert_eq!(app.log.last().unwrap().kind, LogKind::Error);
+        assert_eq!(app.move_history.len(), 5);
+
+        app.reset();
+        assert!(app.opening.is_none());
+    }
+
+    #[test]
+    fn test_command_line() {
+        use ratatui::crossterm::event::KeyModifiers;
+
+        let mut app = App::new();
+        app.open_command_line();
+        "depth 2".chars().for_each(|char| app.push_command_char(char));
+        app.submit_command();
+        assert!(app.command_input.is_none());
+        assert_eq!(app.engine_depth, 2);
+
+        app.open_command_line();
+        "flop".chars().for_each(|char| app.push_command_char(char));
+        app.pop_command_char();
+        app.push_command_char('x');
+        app.submit_command();
+        assert_eq!(app.log.last().unwrap().kind, LogKind::Error);
+
+        app.open_command_line();
+        "fen 4k3/8/8/8/8/8/8/4K3 b - - 0 1".chars().for_each(|char| app.push_command_char(char));
+        app.submit_command();
+        assert_eq!(app.board.turn(), Player::Black);
+
+        // Ctrl+C still quits instead of being typed
+        app.open_command_line();
+        let key = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        app.handle_events(Ok(Event::Key(key))).unwrap();
+        assert_eq!(app.command_input.as_deref(), Some(""));
+        assert!(!app.is_running());
     }
 
-    /// Update the promotion piece.
-    pub fn set_promotion_piece(&mut self, piece_type: PieceType) {
-        self.promotion_piece = piece_type;
+    #[test]
+    fn test_select_empty_square() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(4, 4);
+        app.update_selected_coord();
+        assert!(!app.selected_coord.active);
+        assert!(app.moves_from_selected_coord.is_empty());
     }
 }
@@ -0,0 +1,65 @@
+use ratatui::{ backend::TestBackend, layout::Size, Terminal };
+use color_eyre::Result;
+use std::time::{ Duration, Instant };
+
+use crate::{ ui, App };
+
+/// Size of the off-screen terminal rendered to.
+pub const BENCH_SIZE: Size = Size::new(160, 48);
+
+/// Result of a render benchmark.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BenchResult {
+    /// Number of rendered frames.
+    pub frames: u32,
+    /// Total time spent rendering.
+    pub elapsed: Duration,
+}
+
+impl BenchResult {
+    /// Gets the number of frames rendered per second.
+    pub fn frames_per_second(&self) -> f64 {
+        (self.frames as f64) / self.elapsed.as_secs_f64()
+    }
+
+    /// Gets the average time spent on one frame.
+    pub fn average_frame_time(&self) -> Duration {
+        self.elapsed / self.frames.max(1)
+    }
+}
+
+/// Renders the user interface to an off-screen buffer a number of times.
+///
+/// # Arguments
+///
+/// * `frames` - The number of frames to render.
+///
+/// # Returns
+///
+/// The number of frames and the time it took to render them.
+pub fn bench_render(frames: u32) -> Result<BenchResult> {
+    let mut terminal = Terminal::new(TestBackend::new(BENCH_SIZE.width, BENCH_SIZE.height))?;
+    let mut app = App::new();
+    app.terminal_size = BENCH_SIZE;
+    app.update_terminal_too_small();
+
+    let start = Instant::now();
+    for _ in 0..frames {
+        terminal.draw(|frame| ui::render(&mut app, frame))?;
+    }
+
+    Ok(BenchResult { frames, elapsed: start.elapsed() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bench_render() {
+        let result = bench_render(3).unwrap();
+        assert_eq!(result.frames, 3);
+        assert!(result.frames_per_second() > 0.0);
+        assert!(result.average_frame_time() <= result.elapsed);
+    }
+}
@@ -0,0 +1,76 @@
+#[cfg(feature = "clipboard")]
+use std::io::{ stdout, Write };
+
+use crate::error::{ AppError, AppResult };
+
+/// Characters of the base64 alphabet.
+#[cfg(feature = "clipboard")]
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes as base64 with padding.
+///
+/// # Arguments
+///
+/// * `bytes` - The bytes to encode.
+///
+/// # Returns
+///
+/// The base64 encoded bytes.
+#[cfg(feature = "clipboard")]
+pub fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, byte)| group | ((*byte as u32) << (16 - index * 8)));
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(BASE64_CHARS[((group >> (18 - index * 6)) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Copies text to the clipboard of the terminal with an OSC 52 escape sequence.
+///
+/// Works over SSH as well, but terminals which don't support the sequence silently ignore it.
+///
+/// # Arguments
+///
+/// * `text` - The text to copy.
+#[cfg(feature = "clipboard")]
+pub fn copy_to_clipboard(text: &str) -> AppResult<()> {
+    let mut stdout = stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+        .and_then(|()| stdout.flush())
+        .map_err(AppError::Clipboard)
+}
+
+/// Stands in for copying to the clipboard when the `clipboard` feature is disabled.
+///
+/// # Arguments
+///
+/// * `_text` - The text that would be copied.
+#[cfg(not(feature = "clipboard"))]
+pub fn copy_to_clipboard(_text: &str) -> AppResult<()> {
+    Err(AppError::FeatureDisabled("clipboard"))
+}
+
+#[cfg(all(test, feature = "clipboard"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"e4"), "ZTQ=");
+        assert_eq!(base64_encode(b"e2e"), "ZTJl");
+        assert_eq!(base64_encode(b"e2e4 e7e5"), "ZTJlNCBlN2U1");
+        assert_eq!(base64_encode(b"g1f3"), "ZzFmMw==");
+    }
+}
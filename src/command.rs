@@ -0,0 +1,103 @@
+use pleco::Player;
+use std::path::PathBuf;
+
+/// Command entered in command mode.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    /// Load a position from a FEN.
+    Fen(String),
+    /// Show the board from the other player's perspective.
+    Flip,
+    /// Replace the position with its color-mirrored counterpart.
+    Mirror,
+    /// Reset the game.
+    Reset,
+    /// Set the search depth of the engine.
+    Depth(u16),
+    /// Save the game to a file.
+    Save(PathBuf),
+    /// Continue the game of a PGN file.
+    Pgn(PathBuf),
+    /// Play an opening and continue against the engine.
+    Opening(String),
+    /// Copy the FEN of the position, with another side to move if given.
+    CopyFen(Option<Player>),
+}
+
+/// Names of all commands, for error messages.
+const COMMANDS: &str =
+    "fen <fen>, flip, mirror, reset, depth <n>, save <file>, pgn <file>, opening <name or ECO>, \
+     copyfen [white|black]";
+
+/// Parses a command entered in command mode.
+///
+/// # Arguments
+///
+/// * `input` - The input without the leading `:`.
+///
+/// # Returns
+///
+/// The parsed command, or a message describing why the input is invalid.
+pub fn parse_command(input: &str) -> Result<Command, String> {
+    let input = input.trim();
+    let (name, argument) = input
+        .split_once(' ')
+        .map_or((input, ""), |(name, argument)| (name, argument.trim()));
+
+    match (name, argument) {
+        ("fen", "") => Err(String::from("Usage: fen <fen>")),
+        ("fen", fen) => Ok(Command::Fen(fen.to_string())),
+        ("flip", "") => Ok(Command::Flip),
+        ("mirror", "") => Ok(Command::Mirror),
+        ("reset", "") => Ok(Command::Reset),
+        ("depth", depth) =>
+            match depth.parse() {
+                Ok(depth @ 1..) => Ok(Command::Depth(depth)),
+                _ => Err(String::from("Usage: depth <n>, with n of at least 1")),
+            }
+        ("save", "") => Err(String::from("Usage: save <file>")),
+        ("save", file) => Ok(Command::Save(PathBuf::from(file))),
+        ("pgn", "") => Err(String::from("Usage: pgn <file>")),
+        ("pgn", file) => Ok(Command::Pgn(PathBuf::from(file))),
+        ("opening", "") => Err(String::from("Usage: opening <name or ECO>")),
+        ("opening", opening) => Ok(Command::Opening(opening.to_string())),
+        ("copyfen", "") => Ok(Command::CopyFen(None)),
+        ("copyfen", "white") => Ok(Command::CopyFen(Some(Player::White))),
+        ("copyfen", "black") => Ok(Command::CopyFen(Some(Player::Black))),
+        ("copyfen", _) => Err(String::from("Usage: copyfen [white|black]")),
+        ("flip" | "mirror" | "reset", _) => Err(format!("{} takes no arguments", name)),
+        _ => Err(format!("Unknown command '{}', expected one of: {}", name, COMMANDS)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(
+            parse_command("fen 4k3/8/8/8/8/8/8/4K3 w - - 0 1"),
+            Ok(Command::Fen(String::from("4k3/8/8/8/8/8/8/4K3 w - - 0 1")))
+        );
+        assert_eq!(parse_command(" flip "), Ok(Command::Flip));
+        assert_eq!(parse_command("mirror"), Ok(Command::Mirror));
+        assert_eq!(parse_command("reset"), Ok(Command::Reset));
+        assert_eq!(parse_command("depth 4"), Ok(Command::Depth(4)));
+        assert_eq!(parse_command("save game.json"), Ok(Command::Save(PathBuf::from("game.json"))));
+        assert_eq!(parse_command("pgn game.pgn"), Ok(Command::Pgn(PathBuf::from("game.pgn"))));
+        assert_eq!(parse_command("opening ruy lopez"), Ok(Command::Opening(String::from("ruy lopez"))));
+        assert_eq!(parse_command("copyfen"), Ok(Command::CopyFen(None)));
+        assert_eq!(parse_command("copyfen black"), Ok(Command::CopyFen(Some(Player::Black))));
+    }
+
+    #[test]
+    fn test_parse_invalid_command() {
+        assert!(parse_command("depth 0").is_err());
+        assert!(parse_command("depth deep").is_err());
+        assert!(parse_command("fen").is_err());
+        assert!(parse_command("copyfen red").is_err());
+        assert_eq!(parse_command("flip now"), Err(String::from("flip takes no arguments")));
+        assert!(parse_command("undo").unwrap_err().starts_with("Unknown command 'undo'"));
+    }
+}
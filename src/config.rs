@@ -0,0 +1,302 @@
+use ratatui::style::{ palette::tailwind::{ GREEN, RED }, Color };
+use serde::{ Deserialize, Serialize };
+use std::{ collections::BTreeMap, fs, path::{ Path, PathBuf }, time::{ Duration, SystemTime } };
+use color_eyre::Result;
+
+/// Minimum time the engine takes for a move unless configured otherwise, so its replies don't feel instant.
+pub const DEFAULT_MIN_THINK_MS: u64 = 300;
+
+/// Application configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Whether to hide the board after each move until the next player is ready.
+    pub handoff: bool,
+    /// Whether to flash the target square of an illegal move.
+    pub flash_illegal_moves: bool,
+    /// Whether to ask for confirmation before moves which hang material or allow mate in one.
+    pub training_wheels: bool,
+    /// Whether to always promote to a queen instead of choosing the piece from a menu.
+    pub auto_queen: bool,
+    /// Whether to play the move of a selected piece right away if it has only one.
+    pub auto_play_only_move: bool,
+    /// Whether to keep the moved piece selected, ready for its next move once its side is to move again.
+    pub keep_selection: bool,
+    /// Whether to evaluate each move and mark mistakes and blunders.
+    pub annotate_moves: bool,
+    /// Whether to draw both sides with filled glyphs, colored by side.
+    pub colored_pieces: bool,
+    /// Whether to mark the cursor, selection and targets with borders and dots in addition to colors.
+    pub highlight_patterns: bool,
+    /// Whether to show learning aids, like the path of a check to the king.
+    pub learning_aids: bool,
+    /// Whether to faintly show the moves of the piece under the cursor while nothing is selected.
+    pub hover_moves: bool,
+    /// Whether to tint the squares a typed count moves the cursor to in each direction.
+    pub count_targets: bool,
+    /// Whether to tint the rank and file of the cursor like a crosshair.
+    pub crosshair: bool,
+    /// Whether to draw thin grid lines between the squares instead of telling them apart by color only.
+    pub grid_lines: bool,
+    /// Whether to group identical captured pieces with a count.
+    pub group_captured: bool,
+    /// Whether to show the board paused at launch, accepting moves only after the first key.
+    pub start_paused: bool,
+    /// Whether to show a menu choosing how to start playing at launch.
+    pub start_menu: bool,
+    /// Whether to animate changes on the board, like fading out captured pieces.
+    pub animations: bool,
+    /// Whether to skip the periodic tick and only redraw on input.
+    pub event_driven: bool,
+    /// Whether to reload the configuration file on the next event whenever it changes.
+    pub watch_config: bool,
+    /// Notation moves are shown in.
+    pub notation: Notation,
+    /// Sides the log and info panels are shown at.
+    pub panel_order: PanelOrder,
+    /// How the info panel shows the FEN of the position.
+    pub fen_display: FenDisplay,
+    /// Which squares are labeled with their file and rank.
+    pub coordinate_labels: CoordinateLabels,
+    /// Maximum width of the interface, centered with empty margins on wider terminals.
+    pub max_width: Option<u16>,
+    /// Minimum milliseconds between cursor movements, limiting how fast held keys move the cursor, if at all.
+    pub cursor_repeat_ms: Option<u64>,
+    /// Minimum milliseconds the engine takes for a move in a running game, `DEFAULT_MIN_THINK_MS` if unset.
+    pub min_think_ms: Option<u64>,
+    /// Seconds without input after which the interface is dimmed, if at all.
+    pub idle_dim_seconds: Option<u64>,
+    /// Label of the white player instead of "White".
+    pub white_label: Option<String>,
+    /// Label of the black player instead of "Black".
+    pub black_label: Option<String>,
+    /// Highlight colors while white is to move.
+    pub white_highlights: Highlights,
+    /// Highlight colors while black is to move.
+    pub black_highlights: Highlights,
+    /// When games of the engine against itself end by resignation or agreed draw.
+    pub self_play: SelfPlay,
+    /// Keys typed instead of the default keys they are bound to, e.g. `h` for `a`.
+    pub key_bindings: BTreeMap<char, char>,
+}
+
+impl Config {
+    /// Gets the minimum time the engine takes for a move, zero if disabled.
+    pub fn min_think_time(&self) -> Duration {
+        Duration::from_millis(self.min_think_ms.unwrap_or(DEFAULT_MIN_THINK_MS))
+    }
+}
+
+/// Highlight colors of the cursor and the selection.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Highlights {
+    /// Color of the cursor.
+    pub cursor: Color,
+    /// Color of the selected square.
+    pub selection: Color,
+}
+
+impl Default for Highlights {
+    fn default() -> Self {
+        Self { cursor: RED.c500, selection: GREEN.c500 }
+    }
+}
+
+/// Evaluation thresholds ending games of the engine against itself early, like human players would.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SelfPlay {
+    /// Evaluation in centipawns below which the player to move is hopelessly lost.
+    pub resign_threshold: i32,
+    /// Number of consecutive moves a player has to be hopelessly lost for before resigning.
+    pub resign_moves: u8,
+    /// Evaluation in centipawns around zero within which a simplified position is drawn.
+    pub draw_threshold: i32,
+    /// Most pieces left on the board, kings included, for a position to count as simplified.
+    pub draw_pieces: u8,
+}
+
+impl Default for SelfPlay {
+    fn default() -> Self {
+        Self { resign_threshold: 900, resign_moves: 3, draw_threshold: 20, draw_pieces: 6 }
+    }
+}
+
+/// Notation moves are shown in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Notation {
+    /// Standard algebraic notation, e.g. `Nf3` or `O-O`.
+    #[default]
+    San,
+    /// Coordinate notation of the start and target squares, e.g. `g1f3` or `e1g1`.
+    Coordinate,
+}
+
+impl Notation {
+    /// Gets the other notation.
+    pub fn toggled(self) -> Self {
+        match self {
+            Notation::San => Notation::Coordinate,
+            Notation::Coordinate => Notation::San,
+        }
+    }
+}
+
+/// Sides the log and info panels are shown at, around the board.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanelOrder {
+    /// The log left and the info panel right of the board.
+    #[default]
+    LogLeft,
+    /// The info panel left and the log right of the board.
+    InfoLeft,
+}
+
+/// How the info panel shows the FEN of the position.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FenDisplay {
+    /// The FEN is not shown.
+    #[default]
+    Hidden,
+    /// The FEN as one line.
+    Raw,
+    /// Each field of the FEN on its own labeled line.
+    Split,
+}
+
+/// Which squares of the board are labeled with their file and rank.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoordinateLabels {
+    /// Every square.
+    #[default]
+    Full,
+    /// The squares at the bottom and left edges in the column and row of the cursor.
+    Cursor,
+    /// No squares.
+    Hidden,
+}
+
+impl CoordinateLabels {
+    /// Gets the next mode, from full over cursor to hidden labels.
+    pub fn next(self) -> Self {
+        match self {
+            CoordinateLabels::Full => CoordinateLabels::Cursor,
+            CoordinateLabels::Cursor => CoordinateLabels::Hidden,
+            CoordinateLabels::Hidden => CoordinateLabels::Full,
+        }
+    }
+}
+
+/// Gets the directory the configuration and saves are stored in.
+///
+/// # Returns
+///
+/// The `tui-chess` directory inside the config directory, if the config directory is known.
+pub fn get_config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("tui-chess"))
+}
+
+/// Reads the configuration file.
+///
+/// # Arguments
+///
+/// * `dir` - The config directory.
+///
+/// # Returns
+///
+/// The configuration, or the default configuration if there is no configuration file.
+pub fn read_config(dir: &Path) -> Result<Config> {
+    let path = dir.join("config.json");
+
+    if !path.exists() {
+        return Ok(Config::default());
+    }
+    Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+}
+
+/// Gets when the configuration file was last modified.
+///
+/// # Arguments
+///
+/// * `dir` - The config directory.
+///
+/// # Returns
+///
+/// The modification time, or `None` if there is no configuration file.
+pub fn config_modified(dir: &Path) -> Option<SystemTime> {
+    fs::metadata(dir.join("config.json")).and_then(|metadata| metadata.modified()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tui-chess-config-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_read_missing_config() {
+        assert_eq!(read_config(&test_dir("missing")).unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_read_config() {
+        let dir = test_dir("read");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.json"), r#"{ "handoff": true }"#).unwrap();
+        assert!(read_config(&dir).unwrap().handoff);
+    }
+
+    #[test]
+    fn test_read_highlights() {
+        let dir = test_dir("highlights");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.json"), r#"{ "black_highlights": { "cursor": "blue" } }"#).unwrap();
+
+        let config = read_config(&dir).unwrap();
+        assert_eq!(config.white_highlights, Highlights::default());
+        assert_eq!(config.black_highlights.cursor, Color::Blue);
+        assert_eq!(config.black_highlights.selection, GREEN.c500);
+    }
+
+    #[test]
+    fn test_read_panel_order() {
+        let dir = test_dir("panel_order");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.json"), r#"{ "panel_order": "info_left" }"#).unwrap();
+        assert_eq!(read_config(&dir).unwrap().panel_order, PanelOrder::InfoLeft);
+    }
+
+    #[test]
+    fn test_read_fen_display() {
+        let dir = test_dir("fen_display");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.json"), r#"{ "fen_display": "split" }"#).unwrap();
+        assert_eq!(read_config(&dir).unwrap().fen_display, FenDisplay::Split);
+    }
+
+    #[test]
+    fn test_config_modified() {
+        let dir = test_dir("modified");
+        assert_eq!(config_modified(&dir), None);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.json"), "{}").unwrap();
+        assert!(config_modified(&dir).is_some());
+    }
+
+    #[test]
+    fn test_read_invalid_config() {
+        let dir = test_dir("invalid");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("config.json"), "{ handoff }").unwrap();
+        assert!(read_config(&dir).is_err());
+    }
+}
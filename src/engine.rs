@@ -0,0 +1,551 @@
+use pleco::{ bots::AlphaBetaSearcher, tools::{ eval::Eval, Searcher }, BitMove, Board, PieceType, Player };
+use std::{
+    sync::{ atomic::{ AtomicBool, AtomicU16, Ordering }, Arc, Mutex },
+    time::{ Duration, Instant },
+};
+
+use crate::{ tablebase::tablebase_move, utils::{ move_to_san, piece_value } };
+
+/// Depth the engine searches to.
+pub const ENGINE_DEPTH: u16 = 3;
+
+/// Depth of the search evaluating played moves.
+pub const EVAL_DEPTH: u16 = 2;
+
+/// Deepest depth the analysis can be stepped to, as each step takes longer than the one before.
+pub const MAX_ANALYSIS_DEPTH: u16 = 6;
+
+/// Highest skill level of the engine, at which it plays at full strength.
+pub const MAX_SKILL: u8 = 20;
+
+/// Lowest and highest rating mapped onto the skill levels.
+const SKILL_ELO_RANGE: (u16, u16) = (800, 2800);
+
+/// Score of a checkmate in centipawns.
+const MATE_SCORE: i32 = 100_000;
+
+/// Evaluation drop in centipawns from which a move counts as a mistake.
+const MISTAKE_DROP: i32 = 100;
+
+/// Evaluation drop in centipawns from which a move counts as a blunder.
+const BLUNDER_DROP: i32 = 200;
+
+/// Evaluation of a played move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MoveEval {
+    /// Evaluation after the move in centipawns, positive if white is better.
+    pub eval: i32,
+    /// Change of the evaluation in centipawns, negative if the move worsened the position of its player.
+    pub delta: i32,
+}
+
+impl MoveEval {
+    /// Gets the marker of the move, like `??` for blunders.
+    pub fn marker(&self) -> &'static str {
+        match self.delta {
+            delta if delta <= -BLUNDER_DROP => "??",
+            delta if delta <= -MISTAKE_DROP => "?",
+            _ => "",
+        }
+    }
+}
+
+/// Effort the engine spent on its last move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EngineStats {
+    /// Deepest finished search depth in plies.
+    pub depth: u16,
+    /// Time spent searching.
+    pub elapsed: Duration,
+}
+
+impl EngineStats {
+    /// Gets a short summary, like `depth 3, 1.2s`.
+    pub fn summary(&self) -> String {
+        format!("depth {}, {:.1}s", self.depth, self.elapsed.as_secs_f64())
+    }
+}
+
+/// Analysis of a position to a fixed depth.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Analysis {
+    /// Search depth in plies.
+    pub depth: u16,
+    /// Evaluation in centipawns, positive if white is better.
+    pub eval: i32,
+    /// Best line of play found, in standard algebraic notation.
+    pub pv: Vec<String>,
+}
+
+impl Analysis {
+    /// Gets a short summary, like `depth 3: +0.35 e4 e5 Nf3`.
+    pub fn summary(&self) -> String {
+        format!("depth {}: {} {}", self.depth, format_eval(self.eval), self.pv.join(" "))
+    }
+}
+
+/// Searches the best move for the player to move.
+///
+/// # Arguments
+///
+/// * `board` - The board to search.
+/// * `depth` - The search depth in plies.
+///
+/// # Returns
+///
+/// The best move found, or `None` if the game is over.
+pub fn engine_move(board: &Board, depth: u16) -> Option<BitMove> {
+    if board.generate_moves().is_empty() {
+        return None;
+    }
+    // Basic mating endings are played perfectly instead of searched
+    if let Some(bit_move) = tablebase_move(board) {
+        return Some(bit_move);
+    }
+    Some(AlphaBetaSearcher::best_move(board.shallow_clone(), depth))
+}
+
+/// Converts a rating to the skill level playing at about that strength.
+///
+/// # Arguments
+///
+/// * `elo` - The rating, clamped to the range of 800 to 2800.
+///
+/// # Returns
+///
+/// The skill level, one level per 100 rating points.
+pub fn skill_from_elo(elo: u16) -> u8 {
+    let (lowest, highest) = SKILL_ELO_RANGE;
+    ((elo.clamp(lowest, highest) - lowest) / 100) as u8
+}
+
+/// Limits the search depth to the skill level of the engine.
+///
+/// # Arguments
+///
+/// * `depth` - The search depth at full strength.
+/// * `skill` - The skill level, up to `MAX_SKILL`.
+///
+/// # Returns
+///
+/// The search depth, one ply deeper for every four levels.
+pub fn skill_depth(depth: u16, skill: u8) -> u16 {
+    depth.min(1 + (skill as u16) / 4)
+}
+
+/// Occasionally replaces the best move with a random one, more often the lower the skill level.
+///
+/// # Arguments
+///
+/// * `best` - The best move found by the search.
+/// * `board` - The board the move is played on.
+/// * `skill` - The skill level, up to `MAX_SKILL`.
+/// * `rng` - The random number generator picking the move.
+///
+/// # Returns
+///
+/// The best move, or at skill level 0 in half of the cases another legal move.
+pub fn weaken_move(best: BitMove, board: &Board, skill: u8, rng: &mut fastrand::Rng) -> BitMove {
+    let chance = (MAX_SKILL - skill.min(MAX_SKILL)) as f64 / (2 * MAX_SKILL) as f64;
+    let others: Vec<BitMove> = board
+        .generate_moves()
+        .iter()
+        .copied()
+        .filter(|bit_move| *bit_move != best)
+        .collect();
+    if others.is_empty() || rng.f64() >= chance {
+        return best;
+    }
+    others[rng.usize(..others.len())]
+}
+
+/// Engine search running on a background thread.
+///
+/// The search deepens one ply at a time,
+/// so it can be stopped to play the best move of the last finished depth.
+#[derive(Debug)]
+pub struct EngineSearch {
+    /// Identifier telling the results of different searches apart.
+    pub id: u64,
+    /// Whether the search was asked to stop.
+    stop: Arc<AtomicBool>,
+    /// Best move of the deepest finished search.
+    best_move: Arc<Mutex<Option<BitMove>>>,
+    /// Deepest finished search depth.
+    depth: Arc<AtomicU16>,
+    /// When the search started.
+    started: Instant,
+}
+
+impl EngineSearch {
+    /// Starts searching the best move for the player to move.
+    ///
+    /// # Arguments
+    ///
+    /// * `id` - The identifier of the search.
+    /// * `board` - The board to search.
+    /// * `depth` - The search depth in plies.
+    /// * `on_done` - Called with the best move once the search finished without being stopped.
+    ///
+    /// # Returns
+    ///
+    /// The running search.
+    pub fn start(id: u64, board: &Board, depth: u16, on_done: impl FnOnce(BitMove) + Send + 'static) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let best_move = Arc::new(Mutex::new(None));
+        let reached_depth = Arc::new(AtomicU16::new(0));
+        let board = board.parallel_clone();
+
+        {
+            let stop = Arc::clone(&stop);
+            let best_move = Arc::clone(&best_move);
+            let reached_depth = Arc::clone(&reached_depth);
+            std::thread::spawn(move || {
+                if let Some(bit_move) = tablebase_move(&board) {
+                    *best_move.lock().unwrap() = Some(bit_move);
+                    reached_depth.store(depth, Ordering::Relaxed);
+                    on_done(bit_move);
+                    return;
+                }
+                for depth in 1..=depth.max(1) {
+                    // Only checked between depths, as a running depth can't be interrupted
+                    let bit_move = AlphaBetaSearcher::best_move(board.shallow_clone(), depth);
+                    if stop.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    *best_move.lock().unwrap() = Some(bit_move);
+                    reached_depth.store(depth, Ordering::Relaxed);
+                }
+                if let Some(bit_move) = *best_move.lock().unwrap() {
+                    on_done(bit_move);
+                }
+            });
+        }
+
+        EngineSearch { id, stop, best_move, depth: reached_depth, started: Instant::now() }
+    }
+
+    /// Gets the best move found so far.
+    pub fn best_move(&self) -> Option<BitMove> {
+        *self.best_move.lock().unwrap()
+    }
+
+    /// Gets the time since the search started.
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed()
+    }
+
+    /// Gets the depth reached and the time spent so far.
+    pub fn stats(&self) -> EngineStats {
+        EngineStats { depth: self.depth.load(Ordering::Relaxed), elapsed: self.started.elapsed() }
+    }
+
+    /// Stops the search without reporting its result.
+    ///
+    /// The searcher of pleco can't be interrupted, so the thread keeps running until the depth it is
+    /// searching finished, which can take seconds at high depths. Its result is dropped either way.
+    pub fn stop(&self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Evaluates a position with a shallow search.
+///
+/// # Arguments
+///
+/// * `board` - The board to evaluate.
+/// * `depth` - The search depth in plies.
+///
+/// # Returns
+///
+/// The evaluation in centipawns, positive if white is better.
+pub fn evaluate(board: &Board, depth: u16) -> i32 {
+    let score = negamax(&mut board.shallow_clone(), depth, -MATE_SCORE, MATE_SCORE, &mut Vec::new());
+    if board.turn() == Player::White { score } else { -score }
+}
+
+/// Analyses a position, finding its evaluation and the best line of play.
+///
+/// # Arguments
+///
+/// * `board` - The board to analyse.
+/// * `depth` - The search depth in plies.
+///
+/// # Returns
+///
+/// The evaluation and principal variation at the depth.
+pub fn analyse(board: &Board, depth: u16) -> Analysis {
+    let mut line = Vec::new();
+    let score = negamax(&mut board.shallow_clone(), depth, -MATE_SCORE, MATE_SCORE, &mut line);
+
+    let mut pv_board = board.shallow_clone();
+    let pv = line
+        .into_iter()
+        .map(|bit_move| {
+            let san = move_to_san(bit_move, &pv_board);
+            pv_board.apply_move(bit_move);
+            san
+        })
+        .collect();
+    Analysis { depth, eval: if board.turn() == Player::White { score } else { -score }, pv }
+}
+
+/// Evaluates a move by comparing the position before and after it.
+///
+/// # Arguments
+///
+/// * `bit_move` - The move to evaluate.
+/// * `board` - The board before the move.
+/// * `depth` - The search depth in plies.
+///
+/// # Returns
+///
+/// The evaluation after the move and its change for the moving player.
+pub fn evaluate_move(bit_move: BitMove, board: &Board, depth: u16) -> MoveEval {
+    let before = evaluate(board, depth);
+    let mut after_board = board.shallow_clone();
+    after_board.apply_move(bit_move);
+    let eval = evaluate(&after_board, depth);
+
+    let delta = if board.turn() == Player::White { eval - before } else { before - eval };
+    MoveEval { eval, delta }
+}
+
+/// Formats an evaluation in pawns, or as a mate.
+///
+/// # Arguments
+///
+/// * `eval` - The evaluation in centipawns.
+///
+/// # Returns
+///
+/// The evaluation like `+1.50` or `-M`.
+pub fn format_eval(eval: i32) -> String {
+    if eval.abs() >= MATE_SCORE - 100 {
+        String::from(if eval > 0 { "+M" } else { "-M" })
+    } else {
+        format!("{:+.2}", (eval as f64) / 100.0)
+    }
+}
+
+/// Searches a position with alpha-beta pruning.
+///
+/// # Arguments
+///
+/// * `line` - Filled with the best line of play found from the position.
+///
+/// # Returns
+///
+/// The score for the player to move, preferring quicker mates.
+fn negamax(board: &mut Board, depth: u16, mut alpha: i32, beta: i32, line: &mut Vec<BitMove>) -> i32 {
+    line.clear();
+    let moves = board.generate_moves();
+    if moves.is_empty() {
+        return if board.in_check() { -MATE_SCORE + (board.depth() as i32) } else { 0 };
+    }
+    if depth == 0 {
+        return Eval::eval_low(board);
+    }
+
+    let mut reply_line = Vec::new();
+    for bit_move in moves {
+        board.apply_move(bit_move);
+        let score = -negamax(board, depth - 1, -beta, -alpha, &mut reply_line);
+        board.undo_move();
+
+        if score >= beta {
+            return beta;
+        }
+        if score > alpha {
+            alpha = score;
+            line.clear();
+            line.push(bit_move);
+            line.extend_from_slice(&reply_line);
+        }
+    }
+    alpha
+}
+
+/// Checks whether a move blunders, by looking at every reply of the opponent.
+///
+/// A move blunders if the opponent can mate in one or capture a piece for a material gain.
+///
+/// # Arguments
+///
+/// * `bit_move` - The move to check.
+/// * `board` - The board before the move.
+///
+/// # Returns
+///
+/// A warning naming the refuting reply, or `None` if the move is safe.
+pub fn blunder_warning(bit_move: BitMove, board: &Board) -> Option<String> {
+    let mut board = board.shallow_clone();
+    board.apply_move(bit_move);
+
+    let mut material_loss = None;
+    for reply in board.generate_moves() {
+        let san = move_to_san(reply, &board);
+        if san.ends_with('#') {
+            return Some(format!("{} is mate", san));
+        }
+        if reply.is_capture() && material_loss.is_none() && capture_gain(reply, &board) > 0 {
+            material_loss = Some(format!("{} wins material", san));
+        }
+    }
+    material_loss
+}
+
+/// Estimates the material gained by a capture.
+///
+/// The capturing piece is assumed to be lost if the square is defended.
+///
+/// # Arguments
+///
+/// * `capture` - The capturing move.
+/// * `board` - The board before the capture.
+///
+/// # Returns
+///
+/// The estimated gain in centipawns.
+fn capture_gain(capture: BitMove, board: &Board) -> i32 {
+    let captured = if capture.is_en_passant() {
+        PieceType::P
+    } else {
+        board.piece_at_sq(capture.get_dest()).type_of()
+    };
+
+    let mut board = board.shallow_clone();
+    let capturer = board.piece_at_sq(capture.get_src()).type_of();
+    board.apply_move(capture);
+    let defended = board
+        .generate_moves()
+        .iter()
+        .any(|recapture| recapture.get_dest() == capture.get_dest());
+
+    piece_value(captured) - (if defended { piece_value(capturer) } else { 0 })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_engine_move_is_legal() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+        let bit_move = engine_move(&board, 2).unwrap();
+        assert!(board.generate_moves().contains(&bit_move));
+    }
+
+    #[test]
+    fn test_engine_finds_mate() {
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        assert_eq!(engine_move(&board, 2).unwrap().to_string(), "a1a8");
+    }
+
+    #[test]
+    fn test_blunder_warning_mate() {
+        let board = Board::from_fen("rnbqkbnr/pppp1ppp/8/4p3/8/5P2/PPPPP1PP/RNBQKBNR w KQkq - 0 2").unwrap();
+        let blunder = board.generate_moves().iter().find(|bit_move| bit_move.to_string() == "g2g4").copied();
+        assert_eq!(blunder_warning(blunder.unwrap(), &board).as_deref(), Some("Qh4# is mate"));
+    }
+
+    #[test]
+    fn test_blunder_warning_hanging_piece() {
+        let board = Board::from_fen("4k3/3p4/8/8/Q7/8/8/4K3 w - - 0 1").unwrap();
+        let moves = board.generate_moves();
+        let hanging = moves.iter().find(|bit_move| bit_move.to_string() == "a4c6").unwrap();
+        let safe = moves.iter().find(|bit_move| bit_move.to_string() == "a4a5").unwrap();
+
+        assert_eq!(blunder_warning(*hanging, &board).as_deref(), Some("dxc6 wins material"));
+        assert_eq!(blunder_warning(*safe, &board), None);
+    }
+
+    #[test]
+    fn test_skill_limits() {
+        assert_eq!(skill_from_elo(400), 0);
+        assert_eq!(skill_from_elo(1550), 7);
+        assert_eq!(skill_from_elo(3500), MAX_SKILL);
+        assert_eq!(skill_depth(ENGINE_DEPTH, 0), 1);
+        assert_eq!(skill_depth(ENGINE_DEPTH, 5), 2);
+        assert_eq!(skill_depth(ENGINE_DEPTH, MAX_SKILL), ENGINE_DEPTH);
+    }
+
+    #[test]
+    fn test_weaken_move() {
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let best = engine_move(&board, 2).unwrap();
+        let mut rng = fastrand::Rng::with_seed(3);
+
+        let weak_moves: Vec<BitMove> = (0..10).map(|_| weaken_move(best, &board, 0, &mut rng)).collect();
+        assert!(weak_moves.iter().any(|bit_move| *bit_move != best));
+        assert!(weak_moves.iter().all(|bit_move| board.generate_moves().contains(bit_move)));
+        assert!((0..10).all(|_| weaken_move(best, &board, MAX_SKILL, &mut rng) == best));
+    }
+
+    #[test]
+    fn test_engine_search_reports_move() {
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let search = EngineSearch::start(7, &board, 2, move |bit_move| sender.send(bit_move).unwrap());
+
+        assert_eq!(receiver.recv().unwrap().to_string(), "a1a8");
+        assert_eq!(search.best_move().unwrap().to_string(), "a1a8");
+        assert_eq!(search.stats().depth, 2);
+    }
+
+    #[test]
+    fn test_engine_stats_summary() {
+        let stats = EngineStats { depth: 8, elapsed: Duration::from_millis(1240) };
+        assert_eq!(stats.summary(), "depth 8, 1.2s");
+    }
+
+    #[test]
+    fn test_evaluate() {
+        // Neither side is half a pawn ahead at the start
+        assert!(evaluate(&Board::default(), 1).abs() < 50);
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/3QP3/4K3 b - - 0 1").unwrap();
+        assert!(evaluate(&board, 1) > 500);
+
+        let mated = Board::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 1 1").unwrap();
+        assert_eq!(format_eval(evaluate(&mated, 1)), "+M");
+    }
+
+    #[test]
+    fn test_evaluate_move_flags_blunder() {
+        let board = Board::from_fen("4k3/3p4/8/8/Q7/8/8/4K3 w - - 0 1").unwrap();
+        let moves = board.generate_moves();
+        let hanging = moves.iter().find(|bit_move| bit_move.to_string() == "a4c6").unwrap();
+        let safe = moves.iter().find(|bit_move| bit_move.to_string() == "a4a5").unwrap();
+
+        let blunder = evaluate_move(*hanging, &board, EVAL_DEPTH);
+        assert!(blunder.delta <= -BLUNDER_DROP);
+        assert_eq!(blunder.marker(), "??");
+        assert_eq!(evaluate_move(*safe, &board, EVAL_DEPTH).marker(), "");
+    }
+
+    #[test]
+    fn test_analyse() {
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/8/R5K1 w - - 0 1").unwrap();
+        let analysis = analyse(&board, 1);
+        assert_eq!(analysis.pv, ["Ra8#"]);
+        assert_eq!(format_eval(analysis.eval), "+M");
+
+        let analysis = analyse(&Board::default(), 3);
+        assert_eq!(analysis.pv.len(), 3);
+        assert!(analysis.summary().starts_with("depth 3: "));
+    }
+
+    #[test]
+    fn test_format_eval() {
+        assert_eq!(format_eval(150), "+1.50");
+        assert_eq!(format_eval(-25), "-0.25");
+        assert_eq!(format_eval(-MATE_SCORE + 3), "-M");
+    }
+
+    #[test]
+    fn test_no_engine_move_when_game_over() {
+        let board = Board::from_fen("R5k1/5ppp/8/8/8/8/8/6K1 b - - 1 1").unwrap();
+        assert_eq!(engine_move(&board, 2), None);
+    }
+}
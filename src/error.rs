@@ -0,0 +1,122 @@
+use std::{ fmt::{ Display, Formatter }, io };
+
+use crate::utils::{ FenError, LogKind };
+
+/// Error of an action in the game, shown in the log instead of ending the application.
+#[derive(Debug)]
+pub enum AppError {
+    /// A FEN could not be parsed into a position.
+    Fen(FenError),
+    /// Reading or writing a file or the terminal failed.
+    Io(io::Error),
+    /// Copying to the clipboard of the terminal failed.
+    Clipboard(io::Error),
+    /// The engine could not provide a move.
+    Engine(String),
+    /// A move is not legal in the current position.
+    IllegalMove(String),
+    /// A move was entered after the game ended.
+    GameOver,
+    /// An action needs a Cargo feature the application was built without.
+    FeatureDisabled(&'static str),
+}
+
+/// Result of an action in the game.
+pub type AppResult<T> = std::result::Result<T, AppError>;
+
+impl AppError {
+    /// Gets the kind of log entry the error is shown as.
+    ///
+    /// # Returns
+    ///
+    /// `LogKind::Warning` for rejected input, `LogKind::Error` for failures of the application.
+    pub fn log_kind(&self) -> LogKind {
+        match self {
+            AppError::Engine(_) | AppError::IllegalMove(_) | AppError::GameOver | AppError::FeatureDisabled(_) =>
+                LogKind::Warning,
+            AppError::Fen(_) | AppError::Io(_) | AppError::Clipboard(_) => LogKind::Error,
+        }
+    }
+}
+
+impl Display for AppError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AppError::Fen(error) => write!(f, "Invalid FEN: {}", error),
+            AppError::Io(error) => write!(f, "IO error: {}", error),
+            AppError::Clipboard(error) => write!(f, "Copying to the clipboard failed: {}", error),
+            AppError::Engine(message) => write!(f, "Engine: {}", message),
+            AppError::IllegalMove(text) => write!(f, "Illegal move: {}", text),
+            AppError::GameOver => write!(f, "The game is over"),
+            AppError::FeatureDisabled(feature) => write!(f, "{} feature not enabled", feature),
+        }
+    }
+}
+
+impl std::error::Error for AppError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AppError::Fen(error) => Some(error),
+            AppError::Io(error) | AppError::Clipboard(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<FenError> for AppError {
+    fn from(error: FenError) -> Self {
+        AppError::Fen(error)
+    }
+}
+
+impl From<io::Error> for AppError {
+    fn from(error: io::Error) -> Self {
+        AppError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ event::Event, App };
+    use color_eyre::eyre::WrapErr;
+    use ratatui::crossterm::event::{ KeyCode, KeyEvent };
+
+    #[test]
+    fn test_error_variants() {
+        let mut app = App::new();
+        assert!(
+            matches!(
+                app.load_fen("4k3/8/8/8/8/8/8/4K3 x - - 0 1"),
+                Err(AppError::Fen(FenError::SideToMove(side))) if side == "x"
+            )
+        );
+        assert!(matches!(app.apply_san("Ke2"), Err(AppError::IllegalMove(san)) if san == "Ke2"));
+
+        app.load_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap();
+        assert!(matches!(app.apply_san("Kxg7"), Err(AppError::GameOver)));
+        assert_eq!(AppError::FeatureDisabled("clipboard").to_string(), "clipboard feature not enabled");
+    }
+
+    #[test]
+    fn test_errors_are_logged_by_severity() {
+        let mut app = App::new();
+        app.handle_events(Ok(Event::Paste(String::from("Ke3")))).unwrap();
+        assert_eq!(app.log.last().unwrap().message, "Illegal move: Ke3");
+        assert_eq!(app.log.last().unwrap().kind, LogKind::Warning);
+
+        app.handle_events(Ok(Event::Paste(String::from("8/8/8 w - - 0 1")))).unwrap();
+        assert_eq!(app.log.last().unwrap().kind, LogKind::Error);
+
+        // Without the clipboard feature the copy keys still work, but only explain why nothing was copied
+        if cfg!(not(feature = "clipboard")) {
+            app.handle_events(Ok(Event::Key(KeyEvent::from(KeyCode::Char('y'))))).unwrap();
+            assert_eq!(app.log.last().unwrap().message, "clipboard feature not enabled");
+            assert_eq!(app.log.last().unwrap().kind, LogKind::Warning);
+        }
+
+        // Context added on the way up still reaches the loop as the same error
+        let report = Err::<(), _>(AppError::GameOver).wrap_err("Handling key event failed").unwrap_err();
+        assert!(matches!(report.downcast_ref::<AppError>(), Some(AppError::GameOver)));
+    }
+}
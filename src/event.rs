@@ -1,11 +1,14 @@
 use crossterm::event::{ Event as CrosstermEvent, KeyEvent, MouseEvent };
 use futures::{ FutureExt, StreamExt };
+use pleco::BitMove;
 use std::time::Duration;
 use color_eyre::Result;
-use tokio::sync::mpsc;
+use tokio::{ sync::mpsc, time::Interval };
+
+use crate::{ engine::Analysis, error::AppError };
 
 /// Terminal events.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Debug)]
 pub enum Event {
     /// Terminal tick.
     Tick,
@@ -15,6 +18,16 @@ pub enum Event {
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// Terminal focus gained/lost.
+    Focus(bool),
+    /// Pasted text.
+    Paste(String),
+    /// Termination requested by a signal.
+    Shutdown,
+    /// Move found by the engine search with the given identifier.
+    EngineMove(u64, BitMove),
+    /// Analysis finished in the background with the given identifier.
+    Analysis(u64, Analysis),
 }
 
 /// Terminal event handler.
@@ -31,29 +44,38 @@ pub struct EventHandler {
 
 impl EventHandler {
     /// Constructs a new instance of [`EventHandler`].
-    pub fn new(tick_rate: u64) -> Self {
-        let tick_rate = Duration::from_millis(tick_rate);
+    ///
+    /// No tick events are emitted if `tick_rate` is `None`.
+    pub fn new(tick_rate: Option<u64>) -> Self {
         let (sender, receiver) = mpsc::unbounded_channel();
 
         let handler = {
             let sender = sender.clone();
             tokio::spawn(async move {
                 let mut reader = crossterm::event::EventStream::new();
-                let mut tick = tokio::time::interval(tick_rate);
+                let mut tick = tick_rate.map(|tick_rate| {
+                    tokio::time::interval(Duration::from_millis(tick_rate))
+                });
+                // Listens for the whole loop, so signals between two events aren't missed, and stays
+                // pending once it completed
+                let mut shutdown = std::pin::pin!(shutdown_signal().fuse());
 
                 loop {
-                    let tick_delay = tick.tick();
+                    let tick_delay = next_tick(&mut tick);
                     let crossterm_event = reader.next().fuse();
 
                     tokio::select! {
                         _ = sender.closed() => { break }
+                        _ = &mut shutdown => { sender.send(Event::Shutdown).unwrap() }
                         _ = tick_delay => { sender.send(Event::Tick).unwrap() }
                         Some(Ok(event)) = crossterm_event => {
                           match event {
                             CrosstermEvent::Key(key) => { sender.send(Event::Key(key)).unwrap() },
                             CrosstermEvent::Mouse(mouse) => { sender.send(Event::Mouse(mouse)).unwrap() },
                             CrosstermEvent::Resize(x, y) => { sender.send(Event::Resize(x, y)).unwrap() },
-                            CrosstermEvent::FocusLost | CrosstermEvent::FocusGained | CrosstermEvent::Paste(_) => {},
+                            CrosstermEvent::FocusGained => { sender.send(Event::Focus(true)).unwrap() },
+                            CrosstermEvent::FocusLost => { sender.send(Event::Focus(false)).unwrap() },
+                            CrosstermEvent::Paste(text) => { sender.send(Event::Paste(text)).unwrap() },
                           }
                         }
                     }
@@ -67,6 +89,11 @@ impl EventHandler {
         }
     }
 
+    /// Gets a sender for emitting events from other tasks.
+    pub fn sender(&self) -> mpsc::UnboundedSender<Event> {
+        self.sender.clone()
+    }
+
     /// Receive the next event from the handler thread.
     ///
     /// This function will always block the current thread if
@@ -74,10 +101,81 @@ impl EventHandler {
     pub async fn next(&mut self) -> Result<Event> {
         self.receiver
             .recv().await
-            .ok_or(
-                Box::new(
-                    std::io::Error::new(std::io::ErrorKind::Other, "This is an IO error")
-                ).into()
-            )
+            .ok_or_else(|| {
+                let error = std::io::Error::new(std::io::ErrorKind::BrokenPipe, "The event channel closed");
+                AppError::Io(error).into()
+            })
+    }
+}
+
+/// Waits for the next tick of the interval.
+///
+/// # Arguments
+///
+/// * `tick` - The tick interval, or `None` if ticks are disabled.
+async fn next_tick(tick: &mut Option<Interval>) {
+    match tick {
+        Some(tick) => {
+            tick.tick().await;
+        }
+        None => std::future::pending().await,
+    }
+}
+
+/// Waits for a signal asking the process to terminate.
+///
+/// Completes on SIGINT, and on Unix also on SIGTERM.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{ signal, SignalKind };
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut terminate) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn count_ticks(tick_rate: Option<u64>, duration: Duration) -> usize {
+        let mut tick = tick_rate.map(|tick_rate| tokio::time::interval(Duration::from_millis(tick_rate)));
+        let mut count = 0;
+        let _ = tokio::time::timeout(duration, async {
+            loop {
+                next_tick(&mut tick).await;
+                count += 1;
+            }
+        }).await;
+        count
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_through_channel() {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let mut events = EventHandler { sender: sender.clone(), receiver, handler: tokio::spawn(async {}) };
+
+        sender.send(Event::Shutdown).unwrap();
+        assert!(matches!(events.next().await.unwrap(), Event::Shutdown));
+    }
+
+    #[tokio::test]
+    async fn test_ticks_disabled() {
+        assert!(count_ticks(Some(10), Duration::from_millis(200)).await >= 5);
+        assert_eq!(count_ticks(None, Duration::from_millis(200)).await, 0);
     }
 }
@@ -1,35 +1,226 @@
-use ratatui::crossterm::event::{ KeyCode, KeyEvent, KeyModifiers };
+use ratatui::crossterm::event::{ KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind };
+use ratatui::layout::Size;
 use color_eyre::Result;
-use pleco::PieceType;
 
-use crate::{ utils::CoordEvent, App };
+use crate::{ save::SlotAction, tabs::Tabs, utils::CoordEvent, App };
+
+/// Number of lines scrolled by PageUp and PageDown.
+const PAGE_SCROLL: i16 = 5;
 
 pub fn handle_key_event(key_event: KeyEvent, app: &mut App) -> Result<()> {
+    // A typed count only applies to the cursor movement right after it
+    if !keeps_motion_count(key_event) {
+        app.motion_count = None;
+    }
     match (key_event.modifiers, key_event.code) {
+        // The first key only starts a game launched paused
+        _ if app.paused => app.end_pause(),
+        // Typing into the command line
+        (_, KeyCode::Esc) if app.command_input.is_some() => app.close_command_line(),
+        (_, KeyCode::Enter) if app.command_input.is_some() => app.submit_command(),
+        (_, KeyCode::Backspace) if app.command_input.is_some() => app.pop_command_char(),
+        (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) if app.command_input.is_some() =>
+            app.quit(),
+        (_, KeyCode::Char(char)) if app.command_input.is_some() => app.push_command_char(char),
+        _ if app.command_input.is_some() => {}
+        // Choosing how to start playing
+        (_, KeyCode::Up | KeyCode::Char('w')) if app.start_menu.is_some() => app.move_start_selection(-1),
+        (_, KeyCode::Down | KeyCode::Char('s')) if app.start_menu.is_some() => app.move_start_selection(1),
+        (_, KeyCode::Left | KeyCode::Char('a')) if app.start_menu.is_some() => app.change_start_skill(-1),
+        (_, KeyCode::Right | KeyCode::Char('d')) if app.start_menu.is_some() => app.change_start_skill(1),
+        (_, KeyCode::Enter | KeyCode::Char(' ')) if app.start_menu.is_some() => app.confirm_start_choice(),
+        | (_, KeyCode::Esc | KeyCode::Char('q'))
+        | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) if app.start_menu.is_some() =>
+            app.quit(),
+        _ if app.start_menu.is_some() => {}
+        // Cancel a move waiting for confirmation instead of quitting
+        (_, KeyCode::Esc) if app.pending_move.is_some() => app.cancel_pending_move(),
+        // Hand the board over to the next player
+        (_, KeyCode::Enter | KeyCode::Char(' ')) if app.handoff => app.end_handoff(),
+        // Choosing a promotion piece on the board
+        (_, KeyCode::Up | KeyCode::Char('w')) if app.promotion_menu.is_some() =>
+            app.move_promotion_selection(-1),
+        (_, KeyCode::Down | KeyCode::Char('s')) if app.promotion_menu.is_some() =>
+            app.move_promotion_selection(1),
+        (_, KeyCode::Char(index @ '1'..='4')) if app.promotion_menu.is_some() =>
+            app.select_promotion(((index as u8) - b'1') as usize),
+        (_, KeyCode::Enter | KeyCode::Char(' ')) if app.promotion_menu.is_some() => app.confirm_promotion(),
+        (_, KeyCode::Esc) if app.promotion_menu.is_some() => app.cancel_promotion(),
+        // Choosing a continuation in the opening explorer
+        (_, KeyCode::Up | KeyCode::Char('w')) if app.opening_explorer.is_some() =>
+            app.move_explorer_selection(-1),
+        (_, KeyCode::Down | KeyCode::Char('s')) if app.opening_explorer.is_some() =>
+            app.move_explorer_selection(1),
+        (_, KeyCode::Enter | KeyCode::Char(' ')) if app.opening_explorer.is_some() =>
+            app.play_explorer_move(),
+        (_, KeyCode::Esc | KeyCode::Char('O')) if app.opening_explorer.is_some() =>
+            app.toggle_opening_explorer(),
+        // Choosing a save slot
+        (_, KeyCode::Esc) if app.slot_action.is_some() => app.close_slot_prompt(),
+        (_, KeyCode::Char(slot @ '1'..='9')) if app.slot_action.is_some() => app.select_slot((slot as u8) - b'0'),
+        // Choosing a practice scenario
+        (_, KeyCode::Esc) if app.scenario_prompt => app.close_scenario_prompt(),
+        (_, KeyCode::Char(number @ '1'..='9')) if app.scenario_prompt =>
+            app.select_scenario(((number as u8) - b'0') as usize),
+        // Summary of the finished game
+        (_, KeyCode::Esc | KeyCode::Enter) if app.game_summary => app.close_game_summary(),
+        (_, KeyCode::Char('p')) if app.game_summary => app.copy_pgn()?,
+        (_, KeyCode::Char('f')) if app.game_summary => app.copy_fen()?,
+        // Stepping through the plies of the game
+        (_, KeyCode::Esc) if app.viewed_ply.is_some() => app.view_current_position(),
+        (_, KeyCode::Left) if app.viewed_ply.is_some() => app.step_viewed_ply(-1),
+        (_, KeyCode::Right) if app.viewed_ply.is_some() => app.step_viewed_ply(1),
+        (KeyModifiers::SHIFT, KeyCode::Left) => app.step_viewed_ply(-1),
+        (KeyModifiers::SHIFT, KeyCode::Right) => app.step_viewed_ply(1),
         // Universal commands
         (_, KeyCode::Char('r')) => app.reset(),
         | (_, KeyCode::Esc | KeyCode::Char('q'))
         | (KeyModifiers::CONTROL, KeyCode::Char('c') | KeyCode::Char('C')) => app.quit(),
-        // Block all non-universal key events while block_inputs is true
-        _ if app.block_inputs => {}
+        // Zoom of the board
+        (_, KeyCode::Char('+') | KeyCode::Char('=')) => app.zoom_in(),
+        (_, KeyCode::Char('-')) => app.zoom_out(),
+        // Save slots
+        (_, KeyCode::Char('S')) => app.open_slot_prompt(SlotAction::Save),
+        (_, KeyCode::Char('L')) => app.open_slot_prompt(SlotAction::Load),
+        // Command mode
+        (_, KeyCode::Char(':')) => app.open_command_line(),
+        // Coordinate training replaces selecting pieces
+        (_, KeyCode::Char('T')) => app.toggle_coordinate_training(),
+        (_, KeyCode::Enter | KeyCode::Char(' ')) if app.coordinate_training.is_some() =>
+            app.answer_coordinate_training(),
+        // Overlays on the board
+        (_, KeyCode::Backspace) => app.clear_annotations(),
+        // Notation of the moves
+        (_, KeyCode::Char('n')) => app.toggle_notation(),
+        (_, KeyCode::Char('l')) => app.cycle_coordinate_labels(),
+        // Practice scenarios
+        (_, KeyCode::Char('p')) => app.open_scenario_prompt(),
+        // Internal state for development
+        (_, KeyCode::F(12)) => app.toggle_debug_overlay(),
+        // Showing only the board
+        (_, KeyCode::Char('F')) => app.toggle_focus_board(),
+        // Focus and scrolling of the panels
+        (_, KeyCode::Tab) => app.cycle_panel_focus(),
+        (_, KeyCode::PageUp) => app.scroll_focused_panel(-PAGE_SCROLL),
+        (_, KeyCode::PageDown) => app.scroll_focused_panel(PAGE_SCROLL),
+        // Block all non-universal key events while block_inputs is true or the board is hidden
+        _ if app.block_inputs || app.handoff => {}
         // Movement of the cursor
-        (_, KeyCode::Up | KeyCode::Char('w')) => app.cursor_coord.handle_event(CoordEvent::UP),
-        (_, KeyCode::Down | KeyCode::Char('s')) => app.cursor_coord.handle_event(CoordEvent::DOWN),
-        (_, KeyCode::Left | KeyCode::Char('a')) => app.cursor_coord.handle_event(CoordEvent::LEFT),
-        (_, KeyCode::Right | KeyCode::Char('d')) => app.cursor_coord.handle_event(CoordEvent::RIGHT),
+        (_, KeyCode::Up | KeyCode::Char('w')) => app.move_cursor(CoordEvent::UP),
+        (_, KeyCode::Down | KeyCode::Char('s')) => app.move_cursor(CoordEvent::DOWN),
+        (_, KeyCode::Left | KeyCode::Char('a')) => app.move_cursor(CoordEvent::LEFT),
+        (_, KeyCode::Right | KeyCode::Char('d')) => app.move_cursor(CoordEvent::RIGHT),
+        (_, KeyCode::Char(digit @ '0'..='9')) => app.push_motion_digit(digit),
+        (_, KeyCode::Char(']')) => app.cycle_movable_pieces(true),
+        (_, KeyCode::Char('[')) => app.cycle_movable_pieces(false),
         // Selection of the cursor and moves
         (_, KeyCode::Enter | KeyCode::Char(' ')) => app.update_selected_coord(),
-        // Promotion piece
-        (_, KeyCode::Char('1')) => app.set_promotion_piece(PieceType::Q),
-        (_, KeyCode::Char('2')) => app.set_promotion_piece(PieceType::R),
-        (_, KeyCode::Char('3')) => app.set_promotion_piece(PieceType::B),
-        (_, KeyCode::Char('4')) => app.set_promotion_piece(PieceType::N),
+        (_, KeyCode::Char('c')) => app.toggle_confirm_moves(),
+        (_, KeyCode::Char('k')) => app.toggle_keep_selection(),
+        (_, KeyCode::Char('M')) => app.toggle_mark(),
+        (_, KeyCode::Char('v')) => app.toggle_move_preview(),
+        (_, KeyCode::Char('A')) => app.toggle_move_arrows(),
+        (_, KeyCode::Char('m')) => app.toggle_material_bar(),
+        (_, KeyCode::Char('o')) => app.rotate_board(),
+        (_, KeyCode::Char('G')) => app.log_legal_moves(),
+        (_, KeyCode::Char('y')) => app.copy_move_list()?,
+        (_, KeyCode::Char('B')) => app.snapshot_board(),
+        (_, KeyCode::Char('z')) => app.play_random_move(),
+        (_, KeyCode::Char('i')) => app.deepen_analysis(),
+        (_, KeyCode::Char('O')) => app.toggle_opening_explorer(),
+        // Engine opponent
+        (_, KeyCode::Char('e')) => app.toggle_engine(),
+        (_, KeyCode::Char('x')) => app.swap_engine_side(),
+        (_, KeyCode::Char('D')) => app.toggle_self_play(),
+        (_, KeyCode::Char('f')) => app.force_engine_move()?,
         _ => {}
     }
     Ok(())
 }
 
+/// Checks if a key types a count or moves the cursor, keeping the typed count for the movement.
+fn keeps_motion_count(key_event: KeyEvent) -> bool {
+    matches!(
+        key_event.code,
+        KeyCode::Up | KeyCode::Down | KeyCode::Left | KeyCode::Right |
+            KeyCode::Char('0'..='9' | 'w' | 'a' | 's' | 'd')
+    )
+}
+
+/// Handles the keys switching, opening and closing tabs.
+///
+/// # Returns
+///
+/// `true` if the key was a tab key, so the active game must not handle it.
+pub fn handle_tab_key_event(key_event: KeyEvent, tabs: &mut Tabs) -> bool {
+    match (key_event.modifiers, key_event.code) {
+        (KeyModifiers::CONTROL, KeyCode::Char('t')) => tabs.open_tab(),
+        (KeyModifiers::CONTROL, KeyCode::Char('w')) => tabs.close_tab(),
+        (_, KeyCode::BackTab) => tabs.next_tab(),
+        (KeyModifiers::ALT, KeyCode::Char(number @ '1'..='9')) =>
+            tabs.select_tab(((number as u8) - b'1') as usize),
+        _ => {
+            return false;
+        }
+    }
+    true
+}
+
+pub fn handle_mouse_event(mouse_event: MouseEvent, app: &mut App) -> Result<()> {
+    // The board isn't drawn while the terminal is too small, so clicks would land on its last known squares
+    if app.paused || app.terminal_too_small {
+        return Ok(());
+    }
+    match mouse_event.kind {
+        // Highlight the hovered square without moving the cursor
+        MouseEventKind::Moved => app.update_hover_coord(mouse_event.column, mouse_event.row),
+        // Jump to a ply of the timeline, or choose a square like with the keyboard
+        MouseEventKind::Down(MouseButton::Left) => {
+            app.click_timeline(mouse_event.column, mouse_event.row);
+            app.start_drag(mouse_event.column, mouse_event.row);
+        }
+        // Drag the picked up piece to its target
+        MouseEventKind::Drag(MouseButton::Left) => {
+            app.update_hover_coord(mouse_event.column, mouse_event.row);
+            app.update_drag(mouse_event.column, mouse_event.row);
+        }
+        MouseEventKind::Up(MouseButton::Left) => app.end_drag(mouse_event.column, mouse_event.row),
+        // Scroll the focused panel
+        MouseEventKind::ScrollUp => app.scroll_focused_panel(-1),
+        MouseEventKind::ScrollDown => app.scroll_focused_panel(1),
+        _ => {}
+    }
+    Ok(())
+}
+
+pub fn handle_paste_event(text: &str, app: &mut App) -> Result<()> {
+    // Paste into the command line while typing a command, otherwise treat the text as a FEN or move
+    if app.command_input.is_some() {
+        text.chars().filter(|char| !char.is_control()).for_each(|char| app.push_command_char(char));
+        return Ok(());
+    }
+
+    let prompt_open = app.slot_action.is_some() || app.scenario_prompt || app.promotion_menu.is_some();
+    if !prompt_open && !app.handoff && !app.paused {
+        app.paste_input(text)?;
+    }
+    Ok(())
+}
+
 pub fn handle_resize_event(app: &mut App, width: u16, height: u16) -> Result<()> {
-    app.terminal_too_small = width < 106 || height < 24;
+    // The interface never gets wider than the maximum width, so the board has to fit in it
+    app.terminal_size = Size::new(width.min(app.config.max_width.unwrap_or(width)), height);
+    app.update_terminal_too_small();
+    // A drag can't end on the hidden board, the dragged piece stays selected for the keyboard instead
+    if app.terminal_too_small {
+        app.drag_position = None;
+        app.hover_coord = None;
+    }
+    Ok(())
+}
+
+pub fn handle_focus_event(app: &mut App, focused: bool) -> Result<()> {
+    // Nothing depends on the focus while no clock is running
+    app.focused = focused;
     Ok(())
 }
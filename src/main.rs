@@ -1,8 +1,25 @@
-use color_eyre::Result;
+use crossterm::{
+    event::{
+        DisableBracketedPaste,
+        DisableFocusChange,
+        DisableMouseCapture,
+        EnableBracketedPaste,
+        EnableFocusChange,
+        EnableMouseCapture,
+    },
+    execute,
+};
+use color_eyre::{ eyre::eyre, Result };
+use std::io::stdout;
+
+use engine::{ skill_from_elo, MAX_SKILL };
 
 /// Application.
 mod app;
-pub use app::App;
+pub use app::{ Adjudication, App, CaptureAnimation, Panel, PromotionMenu, PROMOTION_PIECES };
+
+/// Games open in tabs.
+pub mod tabs;
 
 /// Terminal events handler.
 pub mod event;
@@ -16,6 +33,45 @@ pub mod tui;
 /// Event handler.
 pub mod handler;
 
+/// Saving and loading of games.
+pub mod save;
+
+/// Application configuration.
+pub mod config;
+
+/// Practice scenarios for special moves.
+pub mod scenarios;
+
+/// Computer opponent.
+pub mod engine;
+
+/// Parsing of PGN games.
+pub mod pgn;
+
+/// Commands of the command mode.
+pub mod command;
+
+/// Rendering benchmark.
+pub mod bench;
+
+/// Errors of actions in the game.
+pub mod error;
+
+/// Copying to the clipboard of the terminal.
+pub mod clipboard;
+
+/// Coordinate training.
+pub mod training;
+
+/// Table of named openings.
+pub mod openings;
+
+/// Perfect play of basic mating endings.
+pub mod tablebase;
+
+/// Menu choosing how to start playing.
+pub mod start_menu;
+
 // Utils methods and types.
 pub mod utils;
 
@@ -26,9 +82,75 @@ pub mod widgets;
 async fn main() -> Result<()> {
     color_eyre::install()?;
 
+    // Hidden mode for profiling rendering without a terminal
+    let mut args: Vec<String> = std::env::args().collect();
+    if args.get(1).is_some_and(|arg| arg == "--bench-render") {
+        let frames = args.get(2).map_or(Ok(1000), |frames| frames.parse())?;
+        let result = bench::bench_render(frames)?;
+        println!(
+            "Rendered {} frames in {:.2?}: {:.1} frames/sec, {:.2?} per frame",
+            result.frames,
+            result.elapsed,
+            result.frames_per_second(),
+            result.average_frame_time()
+        );
+        return Ok(());
+    }
+
+    // Limit the strength of the engine by a skill level or a rating, before the terminal is taken over
+    let skill = match (take_option(&mut args, "--skill")?, take_option(&mut args, "--elo")?) {
+        (Some(skill), _) => Some(skill.parse::<u8>()?.min(MAX_SKILL)),
+        (None, Some(elo)) => Some(skill_from_elo(elo.parse()?)),
+        (None, None) => None,
+    };
+
     let terminal = ratatui::init();
-    let app_result = App::default().run(terminal).await;
+    execute!(stdout(), EnableFocusChange, EnableMouseCapture, EnableBracketedPaste)?;
+    let mut app = App::default();
+    app.load_config();
+    app.paused = app.config.start_paused;
+    app.engine_skill = skill;
+    // Allow the debug overlay for development, wherever the flag is given
+    if let Some(index) = args.iter().position(|arg| arg == "--debug") {
+        args.remove(index);
+        app.debug_allowed = true;
+    }
+    // Continue a game from a PGN file given as the first argument, or from the environment without one
+    if let Some(path) = args.get(1) {
+        let loaded = std::fs::read_to_string(path).map_err(Into::into).and_then(|pgn| app.load_pgn(&pgn));
+        if let Err(error) = loaded {
+            app.log(&format!("Loading {} failed: {}", path, error));
+        }
+    } else {
+        app.load_env_position();
+        if app.config.start_menu && app.played_moves.is_empty() {
+            app.open_start_menu();
+        }
+    }
+    let app_result = tabs::Tabs::new(app).run(terminal).await;
 
+    execute!(stdout(), DisableBracketedPaste, DisableMouseCapture, DisableFocusChange)?;
     ratatui::restore();
     Ok(app_result?)
 }
+
+/// Removes an option and its value from the arguments.
+///
+/// # Arguments
+///
+/// * `args` - The command line arguments.
+/// * `name` - The name of the option, like `--skill`.
+///
+/// # Returns
+///
+/// The value of the option if given, or an error if the value is missing.
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>> {
+    let Some(index) = args.iter().position(|arg| arg == name) else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        return Err(eyre!("Missing value for {}", name));
+    }
+    args.remove(index);
+    Ok(Some(args.remove(index)))
+}
@@ -0,0 +1,135 @@
+/// Named chess opening.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Opening {
+    /// ECO code of the opening.
+    pub eco: &'static str,
+    /// Name of the opening.
+    pub name: &'static str,
+    /// Moves of the opening from the start position, in SAN.
+    pub moves: &'static [&'static str],
+}
+
+/// Continuation of the played moves found in the table of openings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookMove {
+    /// The move in SAN.
+    pub san: &'static str,
+    /// Name of the opening the move completes, or else of the first opening continuing with it.
+    pub name: &'static str,
+    /// Number of openings continuing with the move.
+    pub count: usize,
+}
+
+/// Built-in openings.
+pub const OPENINGS: [Opening; 12] = [
+    Opening { eco: "B01", name: "Scandinavian Defense", moves: &["e4", "d5"] },
+    Opening { eco: "B07", name: "Pirc Defense", moves: &["e4", "d6", "d4", "Nf6", "Nc3", "g6"] },
+    Opening { eco: "B10", name: "Caro-Kann Defense", moves: &["e4", "c6", "d4", "d5"] },
+    Opening { eco: "B20", name: "Sicilian Defense", moves: &["e4", "c5"] },
+    Opening { eco: "C00", name: "French Defense", moves: &["e4", "e6", "d4", "d5"] },
+    Opening { eco: "C42", name: "Petrov's Defense", moves: &["e4", "e5", "Nf3", "Nf6"] },
+    Opening { eco: "C50", name: "Italian Game", moves: &["e4", "e5", "Nf3", "Nc6", "Bc4"] },
+    Opening { eco: "C60", name: "Ruy Lopez", moves: &["e4", "e5", "Nf3", "Nc6", "Bb5"] },
+    Opening { eco: "D06", name: "Queen's Gambit", moves: &["d4", "d5", "c4"] },
+    Opening { eco: "E60", name: "King's Indian Defense", moves: &["d4", "Nf6", "c4", "g6"] },
+    Opening { eco: "A10", name: "English Opening", moves: &["c4"] },
+    Opening { eco: "A04", name: "Reti Opening", moves: &["Nf3"] },
+];
+
+/// Finds an opening by its ECO code or name.
+///
+/// # Arguments
+///
+/// * `query` - The ECO code, or the name or a part of it, ignoring case.
+///
+/// # Returns
+///
+/// The first matching opening, or `None` if there is none.
+pub fn find_opening(query: &str) -> Option<Opening> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return None;
+    }
+
+    OPENINGS.iter()
+        .find(|opening| opening.eco.to_lowercase() == query)
+        .or_else(|| OPENINGS.iter().find(|opening| opening.name.to_lowercase().contains(&query)))
+        .copied()
+}
+
+/// Gets the continuations of the played moves from the table of openings.
+///
+/// # Arguments
+///
+/// * `played` - The moves played from the start position, in SAN.
+///
+/// # Returns
+///
+/// The next moves of all openings starting with the played moves, most common first.
+pub fn book_moves(played: &[String]) -> Vec<BookMove> {
+    let mut book_moves: Vec<BookMove> = Vec::new();
+    // Played moves may carry check markers, which the table leaves out
+    let follows_played = |opening: &&Opening| {
+        opening.moves.len() > played.len() &&
+            opening.moves.iter().zip(played).all(|(san, played)| *san == played.trim_end_matches(['+', '#']))
+    };
+
+    for opening in OPENINGS.iter().filter(follows_played) {
+        let san = opening.moves[played.len()];
+        let completes = opening.moves.len() == played.len() + 1;
+        match book_moves.iter_mut().find(|book_move| book_move.san == san) {
+            Some(book_move) => {
+                book_move.count += 1;
+                if completes {
+                    book_move.name = opening.name;
+                }
+            }
+            None => book_moves.push(BookMove { san, name: opening.name, count: 1 }),
+        }
+    }
+    book_moves.sort_by_key(|book_move| std::cmp::Reverse(book_move.count));
+    book_moves
+}
+
+#[cfg(test)]
+mod tests {
+    use pleco::Board;
+
+    use super::*;
+    use crate::utils::san_to_move;
+
+    #[test]
+    fn test_opening_moves_are_legal() {
+        for opening in OPENINGS {
+            let mut board = Board::start_pos();
+            for san in opening.moves {
+                let bit_move = san_to_move(san, &board);
+                assert!(bit_move.is_some(), "{} is illegal in the {}", san, opening.name);
+                board.apply_move(bit_move.unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_book_moves() {
+        let first_moves = book_moves(&[]);
+        assert_eq!(first_moves[0], BookMove { san: "e4", name: "Scandinavian Defense", count: 8 });
+        assert_eq!(first_moves.len(), 4);
+
+        let played = ["e4", "e5", "Nf3"].map(String::from);
+        let replies = book_moves(&played);
+        assert_eq!(replies[0], BookMove { san: "Nc6", name: "Italian Game", count: 2 });
+        assert_eq!(replies[1], BookMove { san: "Nf6", name: "Petrov's Defense", count: 1 });
+
+        assert!(book_moves(&[String::from("c4")]).is_empty());
+    }
+
+    #[test]
+    fn test_find_opening() {
+        assert_eq!(find_opening("c60").unwrap().name, "Ruy Lopez");
+        assert_eq!(find_opening("sicilian").unwrap().eco, "B20");
+        assert_eq!(find_opening("queen's gambit").unwrap().eco, "D06");
+        assert_eq!(find_opening("Dutch"), None);
+        assert_eq!(find_opening(" "), None);
+    }
+}
@@ -0,0 +1,142 @@
+use color_eyre::{ eyre::eyre, Result };
+use pleco::{ Board, Player };
+
+use crate::utils::is_game_over;
+
+/// Mainline of a PGN game.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Pgn {
+    /// FEN of the starting position, if given by a `FEN` tag.
+    pub fen: Option<String>,
+    /// Mainline moves in standard algebraic notation.
+    pub moves: Vec<String>,
+}
+
+/// Parses the mainline of a PGN game, skipping comments, NAGs and variations.
+///
+/// # Arguments
+///
+/// * `pgn` - The PGN text.
+///
+/// # Returns
+///
+/// The starting position and mainline moves of the game.
+pub fn parse_pgn(pgn: &str) -> Result<Pgn> {
+    let mut game = Pgn::default();
+    let mut tokens = Vec::new();
+    let mut token = String::new();
+    let mut variation_depth = 0usize;
+    let mut chars = pgn.chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '{' => {
+                if !chars.by_ref().any(|char| char == '}') {
+                    return Err(eyre!("Unterminated comment"));
+                }
+            }
+            ';' => {
+                chars.by_ref().find(|char| *char == '\n');
+            }
+            '[' if variation_depth == 0 => {
+                let tag: String = chars.by_ref().take_while(|char| *char != ']').collect();
+                if let Some(fen) = tag.trim().strip_prefix("FEN") {
+                    game.fen = Some(fen.trim().trim_matches('"').to_string());
+                }
+            }
+            '(' => variation_depth += 1,
+            ')' => {
+                variation_depth = variation_depth.checked_sub(1).ok_or(eyre!("Unmatched ')'"))?;
+            }
+            _ if variation_depth > 0 => {}
+            _ if char.is_whitespace() => tokens.push(std::mem::take(&mut token)),
+            _ => token.push(char),
+        }
+    }
+    tokens.push(token);
+
+    if variation_depth > 0 {
+        return Err(eyre!("Unterminated variation"));
+    }
+
+    for token in tokens {
+        if matches!(token.as_str(), "1-0" | "0-1" | "1/2-1/2" | "*") || token.starts_with('$') {
+            continue;
+        }
+
+        // Strip move numbers like `12.` or `12...`, which may be attached to the move
+        let token = match token.find(|char: char| !char.is_ascii_digit()) {
+            Some(index) if token[index..].starts_with('.') => token[index..].trim_start_matches('.'),
+            _ => &token,
+        };
+        if !token.is_empty() {
+            game.moves.push(token.to_string());
+        }
+    }
+    Ok(game)
+}
+
+/// Gets the PGN result of a game.
+///
+/// # Arguments
+///
+/// * `board` - The board of the final position.
+///
+/// # Returns
+///
+/// `1-0` or `0-1` for the winner of a checkmate, `1/2-1/2` for a draw and `*` for a game still going on.
+pub fn result_token(board: &Board) -> &'static str {
+    if board.checkmate() {
+        match board.turn() {
+            Player::White => "0-1",
+            Player::Black => "1-0",
+        }
+    } else if is_game_over(board) {
+        "1/2-1/2"
+    } else {
+        "*"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pgn_with_annotations() {
+        let pgn = r#"[Event "Casual game"]
+[White "Anderssen"]
+[Black "Dufresne"]
+[Result "1/2-1/2"]
+
+1. e4 {Best by test} e5 2. Nf3 (2. f4 exf4 (2... d5) 3. Nf3) Nc6 $1
+3. Bb5 a6 ; The Morphy Defence
+4.Ba4 Nf6 5. 0-0!? Be7 1/2-1/2"#;
+
+        let game = parse_pgn(pgn).unwrap();
+        assert_eq!(game.fen, None);
+        assert_eq!(game.moves, ["e4", "e5", "Nf3", "Nc6", "Bb5", "a6", "Ba4", "Nf6", "0-0!?", "Be7"]);
+    }
+
+    #[test]
+    fn test_result_token() {
+        assert_eq!(result_token(&Board::default()), "*");
+        assert_eq!(result_token(&Board::from_fen("7k/6Q1/6K1/8/8/8/8/8 b - - 0 1").unwrap()), "1-0");
+        assert_eq!(result_token(&Board::from_fen("7k/8/6QK/8/8/8/8/8 b - - 0 1").unwrap()), "1/2-1/2");
+    }
+
+    #[test]
+    fn test_parse_pgn_with_fen() {
+        let pgn = "[FEN \"4k3/8/8/8/8/8/4P3/4K3 b - - 0 1\"]\n\n1... Kd7 2. e4 *";
+        let game = parse_pgn(pgn).unwrap();
+        assert_eq!(game.fen.as_deref(), Some("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1"));
+        assert_eq!(game.moves, ["Kd7", "e4"]);
+    }
+
+    #[test]
+    fn test_parse_invalid_pgn() {
+        assert!(parse_pgn("1. e4 {unterminated").is_err());
+        assert!(parse_pgn("1. e4 (1. d4").is_err());
+        assert!(parse_pgn("1. e4 )").is_err());
+    }
+}
@@ -0,0 +1,216 @@
+use pleco::Player;
+use serde::{ Deserialize, Serialize };
+use std::{ collections::BTreeMap, fs, io::Write, path::{ Path, PathBuf } };
+use color_eyre::Result;
+
+use crate::config::get_config_dir;
+
+/// Number of available save slots.
+pub const SLOT_COUNT: u8 = 9;
+
+/// State of a saved game.
+///
+/// Saves which only contain `fen` are restored from that position without history.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SaveState {
+    /// FEN of the saved position.
+    pub fen: String,
+    /// FEN of the position the game started from, if not the standard start.
+    pub start_fen: Option<String>,
+    /// Moves played from the starting position, in SAN.
+    pub moves: Vec<String>,
+    /// Player whose perspective the board is shown from.
+    pub main_player: SavedPlayer,
+    /// Player controlled by the engine, if playing against it.
+    pub engine_player: Option<SavedPlayer>,
+    /// Marks drawn on the board in coordinate notation, by the ply of the position they annotate.
+    pub annotations: BTreeMap<usize, Vec<String>>,
+}
+
+/// Player as stored in a save.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SavedPlayer {
+    /// The white player.
+    #[default]
+    White,
+    /// The black player.
+    Black,
+}
+
+impl From<Player> for SavedPlayer {
+    fn from(player: Player) -> Self {
+        match player {
+            Player::White => SavedPlayer::White,
+            Player::Black => SavedPlayer::Black,
+        }
+    }
+}
+
+impl From<SavedPlayer> for Player {
+    fn from(player: SavedPlayer) -> Self {
+        match player {
+            SavedPlayer::White => Player::White,
+            SavedPlayer::Black => Player::Black,
+        }
+    }
+}
+
+/// Action to perform on the chosen save slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SlotAction {
+    /// Save the current game to the slot.
+    Save,
+    /// Load the game from the slot.
+    Load,
+}
+
+/// Gets the directory the save slots are stored in.
+///
+/// # Returns
+///
+/// The save directory, if the config directory is known.
+pub fn get_save_dir() -> Option<PathBuf> {
+    get_config_dir()
+}
+
+/// Gets the path of a save slot.
+///
+/// # Arguments
+///
+/// * `dir` - The save directory.
+/// * `slot` - The slot number.
+///
+/// # Returns
+///
+/// The path of the save slot file.
+pub fn slot_path(dir: &Path, slot: u8) -> PathBuf {
+    dir.join(format!("slot{}.json", slot))
+}
+
+/// Writes a save state to a save slot.
+///
+/// # Arguments
+///
+/// * `dir` - The save directory.
+/// * `slot` - The slot number.
+/// * `state` - The state to save.
+pub fn save_slot(dir: &Path, slot: u8, state: &SaveState) -> Result<()> {
+    fs::create_dir_all(dir)?;
+    save_file(&slot_path(dir, slot), state)
+}
+
+/// Writes a save state to a file.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file.
+/// * `state` - The state to save.
+pub fn save_file(path: &Path, state: &SaveState) -> Result<()> {
+    fs::write(path, serde_json::to_string_pretty(state)?)?;
+    Ok(())
+}
+
+/// Appends a snapshot of a position to the snapshots file, keeping earlier ones.
+///
+/// # Arguments
+///
+/// * `dir` - The save directory.
+/// * `snapshot` - The text of the snapshot.
+///
+/// # Returns
+///
+/// The path of the snapshots file.
+pub fn append_snapshot(dir: &Path, snapshot: &str) -> Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = dir.join("snapshots.txt");
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+    writeln!(file, "{}\n", snapshot)?;
+    Ok(path)
+}
+
+/// Reads the save state of a save slot.
+///
+/// # Arguments
+///
+/// * `dir` - The save directory.
+/// * `slot` - The slot number.
+///
+/// # Returns
+///
+/// The saved state, or `None` if the slot is empty.
+pub fn load_slot(dir: &Path, slot: u8) -> Result<Option<SaveState>> {
+    let path = slot_path(dir, slot);
+
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_str(&fs::read_to_string(path)?)?))
+}
+
+/// Gets all occupied save slots.
+///
+/// # Arguments
+///
+/// * `dir` - The save directory.
+///
+/// # Returns
+///
+/// The numbers of all occupied slots.
+pub fn occupied_slots(dir: &Path) -> Vec<u8> {
+    (1..=SLOT_COUNT).filter(|slot| slot_path(dir, *slot).exists()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tui-chess-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_save_and_load_slot() {
+        let dir = test_dir("save-and-load");
+        let state = SaveState {
+            fen: String::from("4k3/8/8/8/8/8/8/4K3 w - - 0 1"),
+            engine_player: Some(SavedPlayer::Black),
+            ..Default::default()
+        };
+
+        save_slot(&dir, 3, &state).unwrap();
+        assert_eq!(load_slot(&dir, 3).unwrap(), Some(state));
+        assert_eq!(occupied_slots(&dir), vec![3]);
+    }
+
+    #[test]
+    fn test_append_snapshot() {
+        let dir = test_dir("snapshots");
+        append_snapshot(&dir, "first").unwrap();
+        let path = append_snapshot(&dir, "second").unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "first\n\nsecond\n\n");
+    }
+
+    #[test]
+    fn test_load_fen_only_slot() {
+        let dir = test_dir("fen-only");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(slot_path(&dir, 1), r#"{ "fen": "4k3/8/8/8/8/8/8/4K3 w - - 0 1" }"#).unwrap();
+
+        let state = load_slot(&dir, 1).unwrap().unwrap();
+        assert_eq!(state.fen, "4k3/8/8/8/8/8/8/4K3 w - - 0 1");
+        assert!(state.moves.is_empty());
+        assert!(state.annotations.is_empty());
+        assert_eq!(state.main_player, SavedPlayer::White);
+    }
+
+    #[test]
+    fn test_load_empty_slot() {
+        let dir = test_dir("empty");
+        assert_eq!(load_slot(&dir, 1).unwrap(), None);
+        assert!(occupied_slots(&dir).is_empty());
+    }
+}
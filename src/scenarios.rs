@@ -0,0 +1,60 @@
+/// Practice scenario for a special move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scenario {
+    /// Name shown in the scenario menu.
+    pub name: &'static str,
+    /// FEN of the starting position.
+    pub fen: &'static str,
+    /// Goal shown in the Info panel.
+    pub goal: &'static str,
+}
+
+/// Built-in practice scenarios.
+pub const SCENARIOS: [Scenario; 4] = [
+    Scenario {
+        name: "Castle kingside",
+        fen: "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R w KQkq - 0 1",
+        goal: "Castle kingside: move the king two squares towards the h1 rook.",
+    },
+    Scenario {
+        name: "Castle queenside",
+        fen: "r3k2r/pppppppp/8/8/8/8/PPPPPPPP/R3K2R b KQkq - 0 1",
+        goal: "Castle queenside: move the king two squares towards the a8 rook.",
+    },
+    Scenario {
+        name: "En passant (White)",
+        fen: "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+        goal: "Capture the f5 pawn en passant with the e5 pawn.",
+    },
+    Scenario {
+        name: "En passant (Black)",
+        fen: "rnbqkbnr/ppp1pppp/8/8/2Pp4/8/PP1PPPPP/RNBQKBNR b KQkq c3 0 2",
+        goal: "Capture the c4 pawn en passant with the d4 pawn.",
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use pleco::{ BitMove, Board };
+
+    use super::*;
+
+    #[test]
+    fn test_scenarios_offer_special_move() {
+        let checks: [fn(&BitMove) -> bool; 4] = [
+            |bit_move| bit_move.is_king_castle(),
+            |bit_move| bit_move.is_queen_castle(),
+            |bit_move| bit_move.is_en_passant(),
+            |bit_move| bit_move.is_en_passant(),
+        ];
+
+        for (scenario, check) in SCENARIOS.iter().zip(checks) {
+            let board = Board::from_fen(scenario.fen).unwrap();
+            assert!(
+                board.generate_moves().iter().any(check),
+                "{} does not offer the expected move",
+                scenario.name
+            );
+        }
+    }
+}
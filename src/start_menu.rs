@@ -0,0 +1,134 @@
+use crate::engine::MAX_SKILL;
+
+/// Way to start playing, chosen in the start menu.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StartChoice {
+    /// Two players taking turns at the same keyboard.
+    TwoPlayers,
+    /// Play white against the engine.
+    EngineAsWhite,
+    /// Play black against the engine.
+    EngineAsBlack,
+    /// Strength of the engine, changed with left and right.
+    Strength,
+    /// Type a FEN to start from.
+    LoadFen,
+    /// Type the path of a PGN file to continue.
+    LoadPgn,
+    /// Continue a game from a save slot.
+    LoadSave,
+    /// Play one of the practice scenarios.
+    Practice,
+}
+
+/// Entries of the start menu, in the order they are listed.
+pub const START_CHOICES: [StartChoice; 8] = [
+    StartChoice::TwoPlayers,
+    StartChoice::EngineAsWhite,
+    StartChoice::EngineAsBlack,
+    StartChoice::Strength,
+    StartChoice::LoadFen,
+    StartChoice::LoadPgn,
+    StartChoice::LoadSave,
+    StartChoice::Practice,
+];
+
+/// State of the start menu shown before the first game.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StartMenu {
+    /// Index of the highlighted entry.
+    pub selected: usize,
+    /// Skill level of the engine in games against it, full strength at `MAX_SKILL`.
+    pub skill: u8,
+}
+
+impl StartMenu {
+    /// Creates a new `StartMenu` instance with the first entry highlighted.
+    ///
+    /// # Arguments
+    ///
+    /// * `skill` - The skill level of the engine, if limited.
+    ///
+    /// # Returns
+    ///
+    /// A new `StartMenu` instance.
+    pub fn new(skill: Option<u8>) -> Self {
+        StartMenu { selected: 0, skill: skill.unwrap_or(MAX_SKILL).min(MAX_SKILL) }
+    }
+
+    /// Gets the highlighted entry.
+    pub fn choice(&self) -> StartChoice {
+        START_CHOICES[self.selected]
+    }
+
+    /// Moves the highlight, wrapping around at both ends.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The number of entries to move by, negative values move up.
+    pub fn move_selection(&mut self, offset: isize) {
+        self.selected = (self.selected as isize + offset).rem_euclid(START_CHOICES.len() as isize) as usize;
+    }
+
+    /// Raises or lowers the skill level of the engine while its strength is highlighted.
+    ///
+    /// # Arguments
+    ///
+    /// * `offset` - The number of levels to change by, negative values weaken the engine.
+    pub fn change_skill(&mut self, offset: i8) {
+        if self.choice() == StartChoice::Strength {
+            self.skill = self.skill.saturating_add_signed(offset).min(MAX_SKILL);
+        }
+    }
+
+    /// Gets the skill level to limit the engine to.
+    ///
+    /// # Returns
+    ///
+    /// The skill level, or `None` at full strength.
+    pub fn engine_skill(&self) -> Option<u8> {
+        (self.skill < MAX_SKILL).then_some(self.skill)
+    }
+
+    /// Gets the text of an entry.
+    pub fn label(&self, choice: StartChoice) -> String {
+        match choice {
+            StartChoice::TwoPlayers => String::from("Two players"),
+            StartChoice::EngineAsWhite => String::from("White against the engine"),
+            StartChoice::EngineAsBlack => String::from("Black against the engine"),
+            StartChoice::Strength if self.skill == MAX_SKILL => String::from("Engine skill: < full >"),
+            StartChoice::Strength => format!("Engine skill: < {} >", self.skill),
+            StartChoice::LoadFen => String::from("Load a FEN"),
+            StartChoice::LoadPgn => String::from("Load a PGN file"),
+            StartChoice::LoadSave => String::from("Load a save slot"),
+            StartChoice::Practice => String::from("Practice scenario"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_start_menu() {
+        let mut menu = StartMenu::new(None);
+        menu.move_selection(-1);
+        assert_eq!(menu.choice(), StartChoice::Practice);
+        menu.move_selection(4);
+        assert_eq!(menu.choice(), StartChoice::Strength);
+
+        assert_eq!(menu.label(StartChoice::Strength), "Engine skill: < full >");
+        assert_eq!(menu.engine_skill(), None);
+        menu.change_skill(1);
+        assert_eq!(menu.skill, MAX_SKILL);
+        menu.change_skill(-5);
+        assert_eq!(menu.label(StartChoice::Strength), "Engine skill: < 15 >");
+        assert_eq!(menu.engine_skill(), Some(15));
+
+        // The skill only changes while it is highlighted
+        menu.move_selection(1);
+        menu.change_skill(-1);
+        assert_eq!(menu.skill, 15);
+    }
+}
@@ -0,0 +1,395 @@
+use pleco::{ BitMove, Board, PieceType, Player, SQ };
+use std::{ collections::VecDeque, sync::OnceLock };
+
+/// Number of positions of an ending with two kings and one piece, for both players to move.
+const POSITION_COUNT: usize = 64 * 64 * 64 * 2;
+
+/// Distance to mate of positions which are drawn or not legal.
+const UNKNOWN: u8 = u8::MAX;
+
+/// Directions a rook slides in, as file and rank steps.
+const ROOK_DIRECTIONS: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+/// Directions a queen slides in, as file and rank steps.
+const QUEEN_DIRECTIONS: [(i8, i8); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+/// Distances to mate of the K+Q vs K ending.
+static QUEEN_TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// Distances to mate of the K+R vs K ending.
+static ROOK_TABLE: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// Position of an ending with two kings and one piece.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Ending {
+    /// Square of the king of the side with the piece.
+    strong_king: u8,
+    /// Square of the lone king.
+    weak_king: u8,
+    /// Square of the piece.
+    piece: u8,
+    /// Whether the side with the piece is to move.
+    strong_to_move: bool,
+}
+
+impl Ending {
+    /// Gets the index of the position in a table.
+    fn index(self) -> usize {
+        let squares = ((self.strong_king as usize) * 64 + self.weak_king as usize) * 64 + self.piece as usize;
+        squares * 2 + self.strong_to_move as usize
+    }
+
+    /// Gets the mirrored or rotated position with the lowest index, which stands for all of them in a table.
+    ///
+    /// Without pawns or castling, all eight symmetries of the board keep the distance to mate.
+    fn canonical(self) -> Self {
+        (0..8)
+            .map(|symmetry| Ending {
+                strong_king: transform(self.strong_king, symmetry),
+                weak_king: transform(self.weak_king, symmetry),
+                piece: transform(self.piece, symmetry),
+                strong_to_move: self.strong_to_move,
+            })
+            .min_by_key(|position| position.index())
+            .unwrap()
+    }
+
+    /// Gets the position at an index of a table.
+    fn from_index(index: usize) -> Self {
+        Ending {
+            strong_king: (index / (64 * 64 * 2)) as u8,
+            weak_king: (index / (64 * 2) % 64) as u8,
+            piece: (index / 2 % 64) as u8,
+            strong_to_move: index % 2 == 1,
+        }
+    }
+
+    /// Checks if the position can occur, with the kings apart and the player not to move out of check.
+    fn is_legal(self, directions: &[(i8, i8)]) -> bool {
+        let distinct = self.strong_king != self.weak_king
+            && self.strong_king != self.piece
+            && self.weak_king != self.piece;
+        distinct
+            && !kings_touch(self.strong_king, self.weak_king)
+            && !(self.strong_to_move && self.weak_king_in_check(directions))
+    }
+
+    /// Checks if the piece attacks the lone king.
+    fn weak_king_in_check(self, directions: &[(i8, i8)]) -> bool {
+        directions.iter().any(|direction| {
+            let mut current = self.piece;
+            while let Some(next) = step(current, *direction).filter(|next| *next != self.strong_king) {
+                if next == self.weak_king {
+                    return true;
+                }
+                current = next;
+            }
+            false
+        })
+    }
+
+    /// Gets the positions after each move of the player to move.
+    ///
+    /// # Returns
+    ///
+    /// The positions, and whether the lone king can take the piece, drawing the game.
+    fn successors(self, directions: &[(i8, i8)]) -> (Vec<Ending>, bool) {
+        let mut positions = Vec::new();
+        let mut takes_piece = false;
+
+        if self.strong_to_move {
+            for square in king_steps(self.strong_king) {
+                positions.push(Ending { strong_king: square, strong_to_move: false, ..self });
+            }
+            for square in slides(self.piece, directions, &[self.strong_king, self.weak_king]) {
+                positions.push(Ending { piece: square, strong_to_move: false, ..self });
+            }
+        } else {
+            for square in king_steps(self.weak_king) {
+                if square == self.piece {
+                    takes_piece |= !kings_touch(square, self.strong_king);
+                } else {
+                    positions.push(Ending { weak_king: square, strong_to_move: true, ..self });
+                }
+            }
+        }
+        positions.retain(|position| position.is_legal(directions));
+        (positions, takes_piece)
+    }
+
+    /// Gets the positions the last move could have been played from.
+    fn predecessors(self, directions: &[(i8, i8)]) -> Vec<Ending> {
+        let mut positions = Vec::new();
+
+        if self.strong_to_move {
+            for square in king_steps(self.weak_king) {
+                positions.push(Ending { weak_king: square, strong_to_move: false, ..self });
+            }
+        } else {
+            for square in king_steps(self.strong_king) {
+                positions.push(Ending { strong_king: square, strong_to_move: true, ..self });
+            }
+            for square in slides(self.piece, directions, &[self.strong_king, self.weak_king]) {
+                positions.push(Ending { piece: square, strong_to_move: true, ..self });
+            }
+        }
+        positions.retain(|position| position.is_legal(directions));
+        positions
+    }
+}
+
+/// Checks if two kings stand on neighboring squares.
+fn kings_touch(a: u8, b: u8) -> bool {
+    (a % 8).abs_diff(b % 8) <= 1 && (a / 8).abs_diff(b / 8) <= 1
+}
+
+/// Mirrors a square along the files, the ranks and the diagonal, as chosen by the bits of a symmetry.
+fn transform(square: u8, symmetry: u8) -> u8 {
+    let (mut file, mut rank) = (square % 8, square / 8);
+    if symmetry & 1 != 0 {
+        file = 7 - file;
+    }
+    if symmetry & 2 != 0 {
+        rank = 7 - rank;
+    }
+    if symmetry & 4 != 0 {
+        (file, rank) = (rank, file);
+    }
+    rank * 8 + file
+}
+
+/// Gets the squares a king can step to from a square.
+fn king_steps(square: u8) -> impl Iterator<Item = u8> {
+    QUEEN_DIRECTIONS.iter().filter_map(move |direction| step(square, *direction))
+}
+
+/// Gets the square one step in a direction away, if it is on the board.
+fn step(square: u8, (file_step, rank_step): (i8, i8)) -> Option<u8> {
+    let file = (square % 8) as i8 + file_step;
+    let rank = (square / 8) as i8 + rank_step;
+    ((0..8).contains(&file) && (0..8).contains(&rank)).then_some((rank * 8 + file) as u8)
+}
+
+/// Gets the squares a piece slides to, stopping in front of blocking pieces.
+fn slides(square: u8, directions: &[(i8, i8)], blockers: &[u8]) -> Vec<u8> {
+    let mut squares = Vec::new();
+    for direction in directions {
+        let mut current = square;
+        while let Some(next) = step(current, *direction).filter(|next| !blockers.contains(next)) {
+            squares.push(next);
+            current = next;
+        }
+    }
+    squares
+}
+
+/// Computes the distances to mate in plies of all positions of an ending by retrograde analysis.
+///
+/// Starting from the checkmates, positions of the strong side are won once any move reaches a lost position,
+/// and positions of the lone king are lost once all of its moves reach won positions.
+/// Only canonical positions are filled in, with their moves counted once per canonical successor.
+fn build_table(directions: &[(i8, i8)]) -> Vec<u8> {
+    let mut distances = vec![UNKNOWN; POSITION_COUNT];
+    // Canonical successors of the lone king not yet known to lose
+    let mut escapes = vec![0u8; POSITION_COUNT];
+    let mut queue = VecDeque::new();
+
+    for (index, escape_count) in escapes.iter_mut().enumerate() {
+        let position = Ending::from_index(index);
+        // Canonical positions have the strong king in the a1-d1-d4 triangle
+        let (file, rank) = (position.strong_king % 8, position.strong_king / 8);
+        if position.strong_to_move || file > 3 || rank > file || position.canonical() != position {
+            continue;
+        }
+        if !position.is_legal(directions) {
+            continue;
+        }
+        let (successors, takes_piece) = position.successors(directions);
+        let mut successors: Vec<usize> = successors
+            .iter()
+            .map(|successor| successor.canonical().index())
+            .collect();
+        successors.sort_unstable();
+        successors.dedup();
+        // Taking the piece escapes for good, so the position is never lost
+        *escape_count = if takes_piece { UNKNOWN } else { successors.len() as u8 };
+        if successors.is_empty() && !takes_piece && position.weak_king_in_check(directions) {
+            distances[index] = 0;
+            queue.push_back(position);
+        }
+    }
+
+    while let Some(position) = queue.pop_front() {
+        let distance = distances[position.index()] + 1;
+        let mut predecessors: Vec<Ending> = position
+            .predecessors(directions)
+            .iter()
+            .map(|predecessor| predecessor.canonical())
+            .collect();
+        predecessors.sort_unstable_by_key(|predecessor| predecessor.index());
+        predecessors.dedup();
+        for predecessor in predecessors {
+            let index = predecessor.index();
+            if distances[index] != UNKNOWN {
+                continue;
+            }
+            if !predecessor.strong_to_move {
+                if escapes[index] == UNKNOWN {
+                    continue;
+                }
+                escapes[index] -= 1;
+                if escapes[index] > 0 {
+                    continue;
+                }
+            }
+            distances[index] = distance;
+            queue.push_back(predecessor);
+        }
+    }
+    distances
+}
+
+/// Reads a position of a supported ending from a board.
+///
+/// # Returns
+///
+/// The position, and the table of its ending.
+fn read_ending(board: &Board) -> Option<(Ending, &'static [u8])> {
+    if board.count_all_pieces() != 3 {
+        return None;
+    }
+    let strong = [Player::White, Player::Black]
+        .into_iter()
+        .find(|player| board.count_pieces_player(*player) == 2)?;
+    let (table, directions) = if board.count_piece(strong, PieceType::Q) == 1 {
+        (&QUEEN_TABLE, &QUEEN_DIRECTIONS[..])
+    } else if board.count_piece(strong, PieceType::R) == 1 {
+        (&ROOK_TABLE, &ROOK_DIRECTIONS[..])
+    } else {
+        return None;
+    };
+
+    let mut ending = Ending { strong_king: 0, weak_king: 0, piece: 0, strong_to_move: board.turn() == strong };
+    for square in 0..64 {
+        let piece = board.piece_at_sq(SQ(square));
+        match (piece.player(), piece.type_of()) {
+            (Some(player), PieceType::K) if player == strong => ending.strong_king = square,
+            (Some(_), PieceType::K) => ending.weak_king = square,
+            (Some(_), _) => ending.piece = square,
+            (None, _) => (),
+        }
+    }
+    Some((ending, table.get_or_init(|| build_table(directions))))
+}
+
+/// Gets the distance to mate of a position of a supported ending.
+///
+/// # Arguments
+///
+/// * `board` - The board to look up.
+///
+/// # Returns
+///
+/// The number of plies until the lone king is mated with perfect play,
+/// or `None` if the ending isn't K+Q vs K or K+R vs K or the position is drawn.
+pub fn distance_to_mate(board: &Board) -> Option<u8> {
+    let (ending, table) = read_ending(board)?;
+    Some(table[ending.canonical().index()]).filter(|distance| *distance != UNKNOWN)
+}
+
+/// Gets the perfect move of a supported ending.
+///
+/// The side with the piece mates as quickly as possible,
+/// and the lone king delays the mate as long as possible, taking the piece if it can.
+///
+/// # Arguments
+///
+/// * `board` - The board to find the move on.
+///
+/// # Returns
+///
+/// The move, or `None` if the ending isn't K+Q vs K or K+R vs K or the position is drawn.
+pub fn tablebase_move(board: &Board) -> Option<BitMove> {
+    let (ending, _) = read_ending(board)?;
+    distance_to_mate(board)?;
+
+    let moves = board.generate_moves();
+    let distance_after = |bit_move: &BitMove| {
+        let mut next = board.shallow_clone();
+        next.apply_move(*bit_move);
+        distance_to_mate(&next)
+    };
+    if ending.strong_to_move {
+        moves
+            .iter()
+            .filter_map(|bit_move| Some((distance_after(bit_move)?, *bit_move)))
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, bit_move)| bit_move)
+    } else {
+        // Drawn positions after the move, like after taking the piece, are the best defense
+        moves.iter().max_by_key(|bit_move| distance_after(bit_move).map_or(u16::MAX, u16::from)).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Plays the tablebase moves of both sides until the game ends.
+    ///
+    /// # Returns
+    ///
+    /// The number of plies played, if the game ended in checkmate.
+    fn play_out(fen: &str) -> Option<usize> {
+        let mut board = Board::from_fen(fen).unwrap();
+        for ply in 0..100 {
+            if board.checkmate() {
+                return Some(ply);
+            }
+            board.apply_move(tablebase_move(&board)?);
+        }
+        None
+    }
+
+    #[test]
+    fn test_mate_in_one() {
+        let board = Board::from_fen("k7/8/1K6/8/8/8/8/6Q1 w - - 0 1").unwrap();
+        assert_eq!(distance_to_mate(&board), Some(1));
+
+        let mut board = board;
+        board.apply_move(tablebase_move(&board).unwrap());
+        assert!(board.checkmate());
+    }
+
+    #[test]
+    fn test_queen_ending() {
+        let fen = "8/8/8/4k3/8/8/8/4K2Q w - - 0 1";
+        let distance = distance_to_mate(&Board::from_fen(fen).unwrap()).unwrap() as usize;
+        // The longest K+Q vs K mate takes 10 moves
+        assert!(distance <= 19);
+        assert_eq!(play_out(fen), Some(distance));
+    }
+
+    #[test]
+    fn test_rook_ending() {
+        let fen = "8/8/3k4/8/8/8/8/R3K3 b - - 0 1";
+        let distance = distance_to_mate(&Board::from_fen(fen).unwrap()).unwrap() as usize;
+        // The longest K+R vs K mate takes 16 moves
+        assert!(distance <= 32);
+        assert_eq!(play_out(fen), Some(distance));
+
+        // Black with the rook mates the same way
+        assert_eq!(play_out("r3k3/8/8/8/8/3K4/8/8 w - - 0 1"), Some(distance));
+    }
+
+    #[test]
+    fn test_unsupported_positions() {
+        // The lone king takes the undefended queen
+        let board = Board::from_fen("8/8/8/8/8/8/1Qk5/7K b - - 0 1").unwrap();
+        assert_eq!(distance_to_mate(&board), None);
+        assert_eq!(tablebase_move(&board), None);
+
+        let board = Board::from_fen("8/8/8/4k3/8/8/8/4KB2 w - - 0 1").unwrap();
+        assert_eq!(tablebase_move(&board), None);
+    }
+}
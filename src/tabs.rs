@@ -0,0 +1,218 @@
+use ratatui::{ crossterm::event::KeyEventKind, layout::Size, DefaultTerminal };
+use color_eyre::{ eyre::WrapErr, Result };
+
+use crate::{
+    event::{ Event, EventHandler },
+    handler::{ handle_resize_event, handle_tab_key_event },
+    tui::Tui,
+    App,
+};
+
+/// Games open at the same time, each in its own tab.
+#[derive(Debug)]
+pub struct Tabs {
+    /// The open games.
+    pub games: Vec<App>,
+    /// The index of the game shown and receiving input.
+    pub active: usize,
+    /// The last known size of the terminal.
+    terminal_size: Size,
+}
+
+impl Tabs {
+    /// Constructs a new instance of [`Tabs`] with a single game.
+    pub fn new(game: App) -> Self {
+        Self { games: vec![game], active: 0, terminal_size: Default::default() }
+    }
+
+    /// Gets the game of the active tab.
+    pub fn active_game(&self) -> &App {
+        &self.games[self.active]
+    }
+
+    /// Gets the game of the active tab mutably.
+    pub fn active_game_mut(&mut self) -> &mut App {
+        &mut self.games[self.active]
+    }
+
+    /// Gets the number of lines taken by the tab bar, which is only shown with several tabs.
+    pub fn tab_bar_height(&self) -> u16 {
+        if self.games.len() > 1 { 1 } else { 0 }
+    }
+
+    /// Gets the titles of the tabs, numbered and showing the last move of their game.
+    pub fn titles(&self) -> Vec<String> {
+        self.games
+            .iter()
+            .enumerate()
+            .map(|(index, game)| {
+                format!("{} {}", index + 1, game.move_history.last().map_or("Start", String::as_str))
+            })
+            .collect()
+    }
+
+    /// runs the application's main loop until the user quits
+    pub async fn run(&mut self, terminal: DefaultTerminal) -> Result<()> {
+        let size = terminal.size()?;
+        self.resize(size.width, size.height)?;
+
+        // Without the periodic tick, a slow one is still needed to dim the interface once idle
+        let config = &self.active_game().config;
+        let tick_rate = if config.event_driven { config.idle_dim_seconds.map(|_| 1000) } else { Some(250) };
+        let events = EventHandler::new(tick_rate);
+        for game in &mut self.games {
+            game.set_event_sender(events.sender());
+        }
+        let mut tui = Tui::new(terminal, events);
+
+        while self.games.iter().all(App::is_running) {
+            tui.draw(self)?;
+            self.handle_events(tui.events.next().await).wrap_err("Error handling events")?;
+        }
+        Ok(())
+    }
+
+    /// Routes an event to the active game, or to all games if it concerns each of them.
+    fn handle_events(&mut self, event: Result<Event>) -> Result<()> {
+        let event = event?;
+        // Tab keys work in every game, whatever it is waiting for, except while typing a command
+        if let Event::Key(key_event) = event {
+            let typing = self.active_game().command_input.is_some();
+            if key_event.kind == KeyEventKind::Press && !typing && handle_tab_key_event(key_event, self) {
+                return Ok(());
+            }
+        }
+
+        match event {
+            Event::Resize(width, height) => self.resize(width, height),
+            // Background results and timers belong to whichever game started them
+            event @ (
+                Event::Tick | Event::Shutdown | Event::Focus(_) | Event::EngineMove(..) | Event::Analysis(..)
+            ) =>
+                self.games.iter_mut().try_for_each(|game| game.handle_events(Ok(event.clone()))),
+            event => self.active_game_mut().handle_events(Ok(event)),
+        }
+    }
+
+    /// Updates the size of all games, leaving room for the tab bar.
+    ///
+    /// # Arguments
+    ///
+    /// * `width` - The width of the terminal.
+    /// * `height` - The height of the terminal.
+    pub fn resize(&mut self, width: u16, height: u16) -> Result<()> {
+        self.terminal_size = Size::new(width, height);
+        let height = height.saturating_sub(self.tab_bar_height());
+        self.games.iter_mut().try_for_each(|game| handle_resize_event(game, width, height))
+    }
+
+    /// Opens a new game in a tab after the open ones and switches to it.
+    pub fn open_tab(&mut self) {
+        let game = self.active_game().spawn_game();
+        self.games.push(game);
+        self.active = self.games.len() - 1;
+        self.refresh_size();
+    }
+
+    /// Closes the active tab, unless it is the last one.
+    pub fn close_tab(&mut self) {
+        if self.games.len() == 1 {
+            self.active_game_mut().log("Can't close the last tab");
+            return;
+        }
+
+        let mut game = self.games.remove(self.active);
+        game.cancel_engine_search();
+        self.active = self.active.min(self.games.len() - 1);
+        self.refresh_size();
+    }
+
+    /// Switches to the next tab, wrapping around after the last one.
+    pub fn next_tab(&mut self) {
+        self.active = (self.active + 1) % self.games.len();
+    }
+
+    /// Switches to the tab at an index, if it is open.
+    pub fn select_tab(&mut self, index: usize) {
+        if index < self.games.len() {
+            self.active = index;
+        }
+    }
+
+    /// Applies the last terminal size again, as the tab bar may have appeared or disappeared.
+    fn refresh_size(&mut self) {
+        let _ = self.resize(self.terminal_size.width, self.terminal_size.height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::san_to_move;
+
+    fn play(game: &mut App, san: &str) {
+        let bit_move = san_to_move(san, &game.board).unwrap();
+        game.handle_move(bit_move);
+    }
+
+    #[test]
+    fn test_open_and_close_tabs() {
+        let mut tabs = Tabs::new(App::new());
+        tabs.resize(106, 25).unwrap();
+        assert_eq!(tabs.tab_bar_height(), 0);
+        assert_eq!(tabs.active_game().terminal_size, Size::new(106, 25));
+
+        tabs.open_tab();
+        assert_eq!(tabs.active, 1);
+        assert_eq!(tabs.tab_bar_height(), 1);
+        assert_eq!(tabs.games[0].terminal_size, Size::new(106, 24));
+
+        tabs.close_tab();
+        assert_eq!(tabs.active, 0);
+        assert_eq!(tabs.active_game().terminal_size, Size::new(106, 25));
+
+        tabs.close_tab();
+        assert_eq!(tabs.games.len(), 1);
+    }
+
+    #[test]
+    fn test_games_are_independent() {
+        let mut tabs = Tabs::new(App::new());
+        play(tabs.active_game_mut(), "e4");
+        tabs.open_tab();
+        play(tabs.active_game_mut(), "d4");
+
+        assert_eq!(tabs.titles(), vec!["1 e4", "2 d4"]);
+        tabs.next_tab();
+        assert_eq!(tabs.active, 0);
+        assert_eq!(tabs.active_game().move_history, vec!["e4"]);
+
+        tabs.select_tab(5);
+        assert_eq!(tabs.active, 0);
+        tabs.select_tab(1);
+        assert_eq!(tabs.active_game().move_history, vec!["d4"]);
+    }
+
+    #[test]
+    fn test_shutdown_stops_all_games() {
+        let mut tabs = Tabs::new(App::new());
+        tabs.open_tab();
+        tabs.handle_events(Ok(Event::Shutdown)).unwrap();
+        assert!(tabs.games.iter().all(|game| !game.is_running()));
+    }
+
+    #[test]
+    fn test_tab_keys_ignored_while_typing() {
+        use ratatui::crossterm::event::{ KeyCode, KeyEvent, KeyModifiers };
+
+        let mut tabs = Tabs::new(App::new());
+        tabs.active_game_mut().open_command_line();
+        let key = KeyEvent::new(KeyCode::Char('t'), KeyModifiers::CONTROL);
+        tabs.handle_events(Ok(Event::Key(key))).unwrap();
+        assert_eq!(tabs.games.len(), 1);
+
+        tabs.active_game_mut().close_command_line();
+        tabs.handle_events(Ok(Event::Key(key))).unwrap();
+        assert_eq!(tabs.games.len(), 2);
+    }
+}
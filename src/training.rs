@@ -0,0 +1,100 @@
+use pleco::SQ;
+use std::time::{ Duration, Instant };
+
+/// State of the coordinate training, which asks for the cursor to be moved onto named squares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoordinateTraining {
+    /// Square to move the cursor to.
+    pub target: SQ,
+    /// When the current target was named.
+    pub started: Instant,
+    /// Number of correctly found squares.
+    pub correct: u32,
+    /// Number of answered targets.
+    pub attempts: u32,
+    /// Time taken to find the last correctly found square.
+    pub last_time: Option<Duration>,
+}
+
+impl CoordinateTraining {
+    /// Creates a new `CoordinateTraining` instance with a random target.
+    ///
+    /// # Returns
+    ///
+    /// A new `CoordinateTraining` instance without any answers.
+    pub fn new() -> Self {
+        CoordinateTraining {
+            target: random_square(None),
+            started: Instant::now(),
+            correct: 0,
+            attempts: 0,
+            last_time: None,
+        }
+    }
+
+    /// Scores an answer and names a new target if it was correct.
+    ///
+    /// # Arguments
+    ///
+    /// * `square` - The square the cursor was on.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the answer was correct.
+    pub fn answer(&mut self, square: SQ) -> bool {
+        self.attempts += 1;
+        if square != self.target {
+            return false;
+        }
+
+        self.correct += 1;
+        self.last_time = Some(self.started.elapsed());
+        self.target = random_square(Some(self.target));
+        self.started = Instant::now();
+        true
+    }
+}
+
+impl Default for CoordinateTraining {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Picks a random square.
+///
+/// # Arguments
+///
+/// * `previous` - The square not to pick again, if any.
+///
+/// # Returns
+///
+/// A random square other than `previous`.
+fn random_square(previous: Option<SQ>) -> SQ {
+    loop {
+        let square = SQ(fastrand::u8(0..64));
+        if Some(square) != previous {
+            return square;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer() {
+        let mut training = CoordinateTraining::new();
+        let target = training.target;
+
+        assert!(!training.answer(SQ(target.0 ^ 1)));
+        assert_eq!((training.correct, training.attempts), (0, 1));
+        assert_eq!(training.target, target);
+
+        assert!(training.answer(target));
+        assert_eq!((training.correct, training.attempts), (1, 2));
+        assert!(training.last_time.is_some());
+        assert_ne!(training.target, target);
+    }
+}
@@ -1,7 +1,7 @@
 use ratatui::{ backend::Backend, Terminal };
 use color_eyre::Result;
 
-use crate::{ app::App, event::EventHandler, ui };
+use crate::{ event::EventHandler, tabs::Tabs, ui };
 
 /// Representation of a terminal user interface.
 ///
@@ -24,9 +24,9 @@ impl<B: Backend> Tui<B> {
     /// [`Draw`] the terminal interface by [`rendering`] the widgets.
     ///
     /// [`Draw`]: ratatui::Terminal::draw
-    /// [`rendering`]: crate::ui:render
-    pub fn draw(&mut self, app: &mut App) -> Result<()> {
-        self.terminal.draw(|frame| ui::render(app, frame))?;
+    /// [`rendering`]: crate::ui::render_tabs
+    pub fn draw(&mut self, tabs: &mut Tabs) -> Result<()> {
+        self.terminal.draw(|frame| ui::render_tabs(tabs, frame))?;
         Ok(())
     }
 }
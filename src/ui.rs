@@ -1,20 +1,188 @@
-use ratatui::{ layout::{ Constraint, Direction, Layout }, Frame };
+use ratatui::{
+    layout::{ Constraint, Direction, Layout, Rect },
+    style::{ Color, Style, Stylize },
+    symbols::border,
+    widgets::{ Block, Paragraph, Tabs as TabBar },
+    Frame,
+};
 
-use crate::{ widgets::{ Board, Info, Log, TerminalTooSmall }, App };
+use crate::{
+    config::PanelOrder,
+    save::SLOT_COUNT,
+    scenarios::SCENARIOS,
+    tabs::Tabs,
+    utils::{ capped_area, centered_rect, get_board_size },
+    widgets::{
+        Board,
+        DebugOverlay,
+        GameSummary,
+        Handoff,
+        Info,
+        Log,
+        OpeningExplorer,
+        Paused,
+        SaveSlots,
+        Scenarios,
+        StartScreen,
+        TerminalTooSmall,
+        Timeline,
+        DEBUG_OVERLAY_HEIGHT,
+        DEBUG_OVERLAY_WIDTH,
+        GAME_SUMMARY_HEIGHT,
+        GAME_SUMMARY_WIDTH,
+        OPENING_EXPLORER_WIDTH,
+        PAUSED_HEIGHT,
+        PAUSED_WIDTH,
+        START_SCREEN_HEIGHT,
+        START_SCREEN_WIDTH,
+    },
+    App,
+    Panel,
+};
+
+/// Renders the active game, with a tab bar above it if several games are open
+pub fn render_tabs(tabs: &mut Tabs, frame: &mut Frame) {
+    let [tab_bar_area, game_area] = Layout::vertical([
+        Constraint::Length(tabs.tab_bar_height()),
+        Constraint::Fill(1),
+    ]).areas(frame.area());
+
+    if tabs.tab_bar_height() > 0 {
+        let tab_bar = TabBar::new(tabs.titles()).select(tabs.active).highlight_style(Color::Yellow);
+        frame.render_widget(tab_bar, tab_bar_area);
+    }
+    render_game(tabs.active_game_mut(), frame, game_area);
+}
 
 /// Renders the user interface
 pub fn render(app: &mut App, frame: &mut Frame) {
+    let area = frame.area();
+    render_game(app, frame, area);
+}
+
+/// Renders the user interface of a game in an area of the frame
+fn render_game(app: &mut App, frame: &mut Frame, area: Rect) {
+    let area = capped_area(area, app.config.max_width);
+
     if app.terminal_too_small {
-        frame.render_widget(TerminalTooSmall::default(), frame.area());
+        let (min_width, min_height) = app.min_terminal_size();
+        frame.render_widget(TerminalTooSmall { min_width, min_height }, area);
+        return;
+    }
+
+    if app.handoff {
+        frame.render_widget(Handoff { player: app.board.turn() }, area);
         return;
     }
 
+    // The menu takes the place of the board until a way to start is chosen
+    if let Some(menu) = app.start_menu {
+        let menu_area = centered_rect(START_SCREEN_WIDTH, START_SCREEN_HEIGHT, area);
+        frame.render_widget(StartScreen { menu }, menu_area);
+        return;
+    }
+
+    // Reserve the bottom line for the command line while in command mode
+    let [main_area, command_area] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(if app.command_input.is_some() { 1 } else { 0 }),
+    ]).areas(area);
+
+    if let Some(input) = &app.command_input {
+        frame.render_widget(Paragraph::new(format!(":{}", input)), command_area);
+        frame.set_cursor_position((command_area.x + 1 + (input.chars().count() as u16), command_area.y));
+    }
+
+    let board_area = if app.focus_board {
+        render_focused_board(app, frame, main_area)
+    } else {
+        render_panels(app, frame, main_area)
+    };
+
+    if let Some(action) = app.slot_action {
+        frame.render_widget(
+            SaveSlots { action, occupied_slots: app.occupied_slots.clone() },
+            centered_rect(24, (SLOT_COUNT as u16) + 2, board_area)
+        );
+    }
+
+    if app.scenario_prompt {
+        frame.render_widget(Scenarios {}, centered_rect(26, (SCENARIOS.len() as u16) + 2, board_area));
+    }
+
+    if let Some(selected) = app.opening_explorer {
+        let explorer = OpeningExplorer { moves: app.explorer_moves(), selected };
+        let explorer_area = centered_rect(OPENING_EXPLORER_WIDTH, explorer.height(), board_area);
+        frame.render_widget(explorer, explorer_area);
+    }
+
+    if app.game_summary {
+        let summary_area = centered_rect(GAME_SUMMARY_WIDTH, GAME_SUMMARY_HEIGHT, board_area);
+        frame.render_widget(GameSummary { app }, summary_area);
+    }
+
+    if app.paused {
+        frame.render_widget(Paused {}, centered_rect(PAUSED_WIDTH, PAUSED_HEIGHT, board_area));
+    }
+
+    if app.debug_overlay {
+        let overlay_area = Rect {
+            x: area.right().saturating_sub(DEBUG_OVERLAY_WIDTH),
+            y: area.y,
+            width: DEBUG_OVERLAY_WIDTH.min(area.width),
+            height: DEBUG_OVERLAY_HEIGHT.min(area.height),
+        };
+        frame.render_widget(DebugOverlay { app }, overlay_area);
+    }
+
+    if app.dimmed {
+        frame.buffer_mut().set_style(area, Style::new().dim());
+    }
+}
+
+/// Renders only the board with a status line below it, returning the area of the board
+fn render_focused_board(app: &mut App, frame: &mut Frame, area: Rect) -> Rect {
+    let [board_area, status_area] = Layout::vertical([Constraint::Fill(1), Constraint::Length(1)])
+        .areas(area);
+
+    app.timeline_area = Rect::default();
+    frame.render_widget(Paragraph::new(app.status_line()).bold().centered(), status_area);
+    frame.render_widget(Board { app }, board_area);
+    board_area
+}
+
+/// Renders the log, board and info panels with the timeline below them, returning the area of the board
+fn render_panels(app: &mut App, frame: &mut Frame, main_area: Rect) -> Rect {
+    // Show the timeline below the panels once moves were played, if it doesn't cut off the board
+    let (_, board_height) = get_board_size(app.zoom, app.config.grid_lines);
+    let has_room = main_area.height > board_height;
+    let [panels_area, timeline_area] = Layout::vertical([
+        Constraint::Fill(1),
+        Constraint::Length(if !app.played_moves.is_empty() && has_room { 1 } else { 0 }),
+    ]).areas(main_area);
+
     let layout = Layout::default()
         .direction(Direction::Horizontal)
         .constraints([Constraint::Ratio(4, 17), Constraint::Ratio(9, 17), Constraint::Ratio(4, 17)].as_ref())
-        .split(frame.area());
+        .split(panels_area);
 
-    frame.render_widget(Log { log: app.log.clone() }, layout[0]);
+    app.timeline_area = timeline_area;
+    let plies = app.played_moves.len();
+    frame.render_widget(Timeline { plies, current: app.viewed_ply.unwrap_or(plies) }, timeline_area);
+
+    let (log_area, info_area) = match app.config.panel_order {
+        PanelOrder::LogLeft => (layout[0], layout[2]),
+        PanelOrder::InfoLeft => (layout[2], layout[0]),
+    };
+    frame.render_widget(
+        Log { log: app.log.clone(), scroll: app.log_scroll, focused: app.focused_panel == Panel::Log },
+        log_area
+    );
+    if app.focused_panel == Panel::Board {
+        // The board has no border of its own, so outline its panel behind the squares
+        frame.render_widget(Block::bordered().border_set(border::ROUNDED).border_style(Color::Yellow), layout[1]);
+    }
     frame.render_widget(Board { app }, layout[1]);
-    frame.render_widget(Info { app }, layout[2]);
+    frame.render_widget(Info { app }, info_area);
+    layout[1]
 }
@@ -1,7 +1,20 @@
-use ratatui::style::{ palette::tailwind::{ BLUE, GRAY, GREEN, ORANGE, RED, WHITE, YELLOW }, Color };
-use pleco::{ BitMove, Player, SQ };
+use ratatui::style::{
+    palette::tailwind::{ AMBER, BLUE, GRAY, ORANGE, PURPLE, RED, SLATE, WHITE, YELLOW },
+    Color,
+};
+use pleco::{ Player, SQ };
 
-use super::{ dest_in_moves, get_file, get_rank, Coord };
+use super::{
+    captured_square,
+    castling_king_square,
+    castling_rook_squares,
+    check_path,
+    dest_in_moves,
+    get_file,
+    get_rank,
+    Coord,
+};
+use crate::App;
 
 /// Cell.
 #[derive(Debug, Clone, PartialEq)]
@@ -10,6 +23,19 @@ pub struct Cell {
     pub color: Color,
     /// Square associated with the cell.
     pub square: SQ,
+    /// Highlights of the cell, for drawing them without relying on color.
+    pub marks: CellMarks,
+}
+
+/// Highlights applying to a cell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CellMarks {
+    /// Whether the cursor is on the cell.
+    pub cursor: bool,
+    /// Whether the cell holds the selected piece.
+    pub selected: bool,
+    /// Whether the selected piece can move to the cell.
+    pub target: bool,
 }
 
 impl Cell {
@@ -17,43 +43,241 @@ impl Cell {
     ///
     /// # Arguments
     ///
-    /// * `moves_from_selected_coord` - The moves from the selected coordinate.
-    /// * `selected_coord` - The selected coordinate.
-    /// * `cursor_coord` - The cursor coordinate.
-    /// * `main_player` - The current player.
+    /// * `app` - The application state.
     /// * `row` - The row of the cell.
     /// * `col` - The column of the cell.
     ///
     /// # Returns
     ///
     /// A new `Cell` instance.
-    pub fn new(
-        moves_from_selected_coord: &Vec<BitMove>,
-        selected_coord: Coord,
-        cursor_coord: Coord,
-        main_player: Player,
-        row: usize,
-        col: usize
-    ) -> Self {
-        let square = SQ::make(get_file(col as u8), get_rank(row as u8, main_player));
-        let mut color = if (row + col) % 2 == 0 { WHITE } else { GRAY.c700 };
+    pub fn new(app: &App, row: usize, col: usize) -> Self {
+        let selected_coord = app.selected_coord;
+        let cursor_coord = app.cursor_coord;
+        let highlights = match app.board.turn() {
+            Player::White => app.config.white_highlights,
+            Player::Black => app.config.black_highlights,
+        };
+        // Dim the cursor while input can't move a piece
+        let cursor_color = if app.board.turn() == app.controllable_player() {
+            highlights.cursor
+        } else {
+            SLATE.c500
+        };
+
+        let square = SQ::make(get_file(col as u8), get_rank(row as u8, app.main_player));
+        let base_color = if (row + col) % 2 == 0 { WHITE } else { GRAY.c700 };
+        let mut color = base_color;
+        let marks = CellMarks {
+            cursor: cursor_coord.active && cursor_coord.is_on(row as i8, col as i8),
+            selected: selected_coord.active && selected_coord.is_on(row as i8, col as i8),
+            target: selected_coord.active && dest_in_moves(square, &app.moves_from_selected_coord),
+        };
 
         if selected_coord.active {
             if selected_coord.is_on(row as i8, col as i8) {
-                color = if cursor_coord.is_on(row as i8, col as i8) { YELLOW.c500 } else { GREEN.c500 };
-            } else if dest_in_moves(square, moves_from_selected_coord) {
                 color = if cursor_coord.is_on(row as i8, col as i8) {
+                    YELLOW.c500
+                } else {
+                    highlights.selection
+                };
+            } else if dest_in_moves(square, &app.moves_from_selected_coord) {
+                color = if app.pending_move.is_some_and(|pending_move| pending_move.get_dest() == square) {
+                    PURPLE.c500
+                } else if cursor_coord.is_on(row as i8, col as i8) {
                     ORANGE.c500
                 } else {
                     if (row + col) % 2 == 0 { BLUE.c400 } else { BLUE.c500 }
                 };
             } else if cursor_coord.is_on(row as i8, col as i8) {
-                color = RED.c500;
+                color = cursor_color;
             }
         } else if cursor_coord.is_on(row as i8, col as i8) && cursor_coord.active {
-            color = RED.c500;
+            color = cursor_color;
+        } else if app.config.hover_moves && dest_in_moves(square, &app.hover_moves()) {
+            // Dimmer than the targets of a selected piece, as nothing is selected yet
+            color = if (row + col) % 2 == 0 { BLUE.c100 } else { BLUE.c300 };
+        } else if app.config.count_targets
+            && app.count_targets().contains(&Coord::new(col as i8, row as i8, true))
+        {
+            color = AMBER.c300;
+        }
+
+        // Mark the last move, including the pawn taken en passant behind its destination
+        // and the king and rook landing apart from the encoded rook square when castling
+        if color == base_color {
+            if let Some(last_move) = app.displayed_board().last_move() {
+                let rook_squares = castling_rook_squares(last_move);
+                let moved_rook = rook_squares.is_some_and(|(src, dest)| src == square || dest == square);
+                let castled_king = castling_king_square(last_move) == Some(square);
+                let moved = last_move.get_src() == square || last_move.get_dest() == square;
+                if moved || moved_rook || castled_king {
+                    color = if (row + col) % 2 == 0 { YELLOW.c200 } else { YELLOW.c700 };
+                } else if captured_square(last_move) == Some(square) {
+                    color = if (row + col) % 2 == 0 { RED.c200 } else { RED.c400 };
+                }
+            }
+        }
+
+        // Show where a check comes from and the squares it passes through
+        let on_check_path = || check_path(app.displayed_board()).contains(&square);
+        if color == base_color && app.config.learning_aids && on_check_path() {
+            color = if (row + col) % 2 == 0 { AMBER.c200 } else { AMBER.c600 };
+        }
+
+        // Lightly tint the hovered square if no other highlight applies
+        let hovered = app.hover_coord.is_some_and(|hover_coord| hover_coord.is_on(row as i8, col as i8));
+        if color == base_color && hovered {
+            color = if (row + col) % 2 == 0 { SLATE.c300 } else { GRAY.c500 };
         }
 
-        Cell { color, square }
+        // Faintly tint the rank and file of the cursor, below all other highlights
+        let in_crosshair = cursor_coord.row == row as i8 || cursor_coord.col == col as i8;
+        if color == base_color && app.config.crosshair && cursor_coord.active && in_crosshair {
+            color = if (row + col) % 2 == 0 { GRAY.c200 } else { GRAY.c600 };
+        }
+
+        if app.illegal_flash.is_some_and(|flash_coord| flash_coord.is_on(row as i8, col as i8)) {
+            color = RED.c700;
+        }
+
+        Cell { color, square, marks }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::san_to_move;
+    use super::*;
+
+    #[test]
+    fn test_hover_moves() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(7, 6);
+        assert!(app.hover_moves().is_empty());
+
+        app.config.hover_moves = true;
+        assert_eq!(app.hover_moves().len(), 2);
+        assert_eq!(Cell::new(&app, 5, 5).color, BLUE.c100);
+        assert_eq!(Cell::new(&app, 5, 7).color, BLUE.c100);
+        assert_eq!(Cell::new(&app, 4, 5).color, GRAY.c700);
+
+        // Leaving the knight clears the preview
+        app.cursor_coord.move_to(5, 6);
+        assert!(app.hover_moves().is_empty());
+        assert_eq!(Cell::new(&app, 5, 5).color, WHITE);
+    }
+
+    #[test]
+    fn test_count_targets() {
+        let mut app = App::new();
+        app.config.count_targets = true;
+        app.cursor_coord.move_to(4, 4);
+        assert_eq!(Cell::new(&app, 4, 6).color, WHITE);
+
+        app.push_motion_digit('2');
+        assert_eq!(Cell::new(&app, 4, 6).color, AMBER.c300);
+        assert_eq!(Cell::new(&app, 2, 4).color, AMBER.c300);
+        assert_eq!(Cell::new(&app, 4, 5).color, GRAY.c700);
+    }
+
+    #[test]
+    fn test_cursor_color_per_player() {
+        let mut app = App::new();
+        app.config.black_highlights.cursor = Color::Blue;
+        assert_eq!(Cell::new(&app, 7, 0).color, app.config.white_highlights.cursor);
+
+        app.load_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1").unwrap();
+        app.main_player = Player::Black;
+        assert_eq!(Cell::new(&app, 7, 0).color, Color::Blue);
+    }
+
+    #[test]
+    fn test_en_passant_captured_square_highlighted() {
+        let mut app = App::new();
+        app.load_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        app.cursor_coord.active = false;
+        app.handle_move(san_to_move("exd6", &app.board).unwrap());
+
+        // e5 and d6 mark the move, d5 the captured pawn
+        assert_eq!(Cell::new(&app, 3, 4).color, YELLOW.c700);
+        assert_eq!(Cell::new(&app, 2, 3).color, YELLOW.c700);
+        assert_eq!(Cell::new(&app, 3, 3).color, RED.c200);
+        assert_eq!(Cell::new(&app, 4, 3).color, GRAY.c700);
+    }
+
+    #[test]
+    fn test_castling_squares_highlighted() {
+        let mut app = App::new();
+        app.load_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        app.handle_move(san_to_move("O-O", &app.board).unwrap());
+        app.cursor_coord.active = false;
+        assert_eq!(app.log.last().unwrap().message, "1. O-O");
+
+        // e1 and g1 of the king, h1 and f1 of the rook
+        assert_eq!(Cell::new(&app, 7, 4).color, YELLOW.c700);
+        assert_eq!(Cell::new(&app, 7, 6).color, YELLOW.c700);
+        assert_eq!(Cell::new(&app, 7, 7).color, YELLOW.c200);
+        assert_eq!(Cell::new(&app, 7, 5).color, YELLOW.c200);
+        assert_eq!(Cell::new(&app, 7, 0).color, GRAY.c700);
+
+        // e8 and c8 of the king, a8 and d8 of the rook
+        app.handle_move(san_to_move("O-O-O", &app.board).unwrap());
+        assert_eq!(app.log.last().unwrap().message, "1... O-O-O");
+        assert_eq!(Cell::new(&app, 0, 4).color, YELLOW.c200);
+        assert_eq!(Cell::new(&app, 0, 2).color, YELLOW.c200);
+        assert_eq!(Cell::new(&app, 0, 0).color, YELLOW.c200);
+        assert_eq!(Cell::new(&app, 0, 3).color, YELLOW.c700);
+        assert_eq!(Cell::new(&app, 0, 7).color, GRAY.c700);
+    }
+
+    #[test]
+    fn test_marks() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(4, 4);
+
+        assert_eq!(Cell::new(&app, 6, 4).marks, CellMarks { selected: true, ..Default::default() });
+        assert_eq!(Cell::new(&app, 4, 4).marks, CellMarks { cursor: true, selected: false, target: true });
+        assert_eq!(Cell::new(&app, 5, 4).marks, CellMarks { target: true, ..Default::default() });
+        assert_eq!(Cell::new(&app, 5, 5).marks, CellMarks::default());
+    }
+
+    #[test]
+    fn test_check_path_tinted() {
+        let mut app = App::new();
+        app.load_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+        app.cursor_coord.move_to(0, 0);
+        assert_eq!(Cell::new(&app, 7, 5).color, WHITE);
+
+        app.config.learning_aids = true;
+        assert_eq!(Cell::new(&app, 7, 5).color, AMBER.c200);
+        assert_eq!(Cell::new(&app, 7, 6).color, AMBER.c600);
+        assert_eq!(Cell::new(&app, 6, 5).color, GRAY.c700);
+    }
+
+    #[test]
+    fn test_crosshair() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(4, 2);
+        assert_eq!(Cell::new(&app, 4, 5).color, GRAY.c700);
+
+        app.config.crosshair = true;
+        assert_eq!(Cell::new(&app, 4, 5).color, GRAY.c600);
+        assert_eq!(Cell::new(&app, 0, 2).color, GRAY.c200);
+        assert_eq!(Cell::new(&app, 3, 3).color, WHITE);
+        assert_eq!(Cell::new(&app, 4, 2).color, app.config.white_highlights.cursor);
+    }
+
+    #[test]
+    fn test_cursor_dimmed_on_opponent_turn() {
+        let mut app = App::new();
+        assert_eq!(Cell::new(&app, 7, 0).color, app.config.white_highlights.cursor);
+
+        app.load_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1").unwrap();
+        assert_eq!(Cell::new(&app, 7, 0).color, SLATE.c500);
+
+        app.engine_player = Some(Player::White);
+        assert_eq!(Cell::new(&app, 7, 0).color, app.config.black_highlights.cursor);
     }
 }
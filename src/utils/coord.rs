@@ -99,6 +99,26 @@ impl Coord {
         SQ::make(get_file(self.col as u8), get_rank(self.row as u8, main_player))
     }
 
+    /// Creates an active `Coord` from a `pleco::SQ`, the inverse of [`Coord::to_square`].
+    ///
+    /// # Arguments
+    ///
+    /// * `square` - The square to convert.
+    /// * `main_player` - The current player.
+    ///
+    /// # Returns
+    ///
+    /// A `Coord` representing the square from the perspective of `main_player`.
+    pub fn from_square(square: SQ, main_player: Player) -> Self {
+        let file = (square.0 & 7) as i8;
+        let rank = (square.0 >> 3) as i8;
+
+        match main_player {
+            Player::White => Coord::new(file, 7 - rank, true),
+            Player::Black => Coord::new(file, rank, true),
+        }
+    }
+
     /// Checks whether the Coord is on the specified row and column.
     ///
     /// # Arguments
@@ -212,6 +232,24 @@ mod tests {
         assert_eq!(coord.to_square(Player::Black), SQ::make(File::G, Rank::R6));
     }
 
+    #[test]
+    fn test_from_square() {
+        assert_eq!(Coord::from_square(SQ::G3, Player::White), Coord::new(6, 5, true));
+        assert_eq!(Coord::from_square(SQ::G6, Player::Black), Coord::new(6, 5, true));
+    }
+
+    #[test]
+    fn test_from_square_round_trip() {
+        for player in [Player::White, Player::Black] {
+            for row in 0..8 {
+                for col in 0..8 {
+                    let coord = Coord::new(col, row, true);
+                    assert_eq!(Coord::from_square(coord.to_square(player), player), coord);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_is_on() {
         let coord = Coord::new(6, 5, true);
@@ -0,0 +1,84 @@
+use ratatui::style::Color;
+
+/// Full moves without a capture or pawn move at which the fifty-move counter turns yellow.
+const FIFTY_MOVE_NEAR: i16 = 40;
+/// Full moves without a capture or pawn move at which the fifty-move counter turns red.
+const FIFTY_MOVE_IMMINENT: i16 = 48;
+
+/// How close a draw counter is to ending the game.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DrawWarning {
+    /// The draw is still far away.
+    #[default]
+    None,
+    /// The draw is getting close.
+    Near,
+    /// The draw is only a few moves away.
+    Imminent,
+}
+
+impl DrawWarning {
+    /// Gets the color of a counter with this warning level.
+    pub fn color(self) -> Color {
+        match self {
+            DrawWarning::None => Color::White,
+            DrawWarning::Near => Color::Yellow,
+            DrawWarning::Imminent => Color::Red,
+        }
+    }
+}
+
+/// Gets the warning level of the fifty-move rule.
+///
+/// # Arguments
+///
+/// * `half_moves` - The half-moves since the last capture or pawn move.
+///
+/// # Returns
+///
+/// `Near` from 40 moves on and `Imminent` from 48 moves on.
+pub fn fifty_move_warning(half_moves: i16) -> DrawWarning {
+    match half_moves / 2 {
+        FIFTY_MOVE_IMMINENT.. => DrawWarning::Imminent,
+        FIFTY_MOVE_NEAR.. => DrawWarning::Near,
+        _ => DrawWarning::None,
+    }
+}
+
+/// Gets the warning level of the threefold repetition rule.
+///
+/// # Arguments
+///
+/// * `count` - The number of times the current position occurred.
+///
+/// # Returns
+///
+/// `Near` once the position repeats and `Imminent` once it occurred three times.
+pub fn repetition_warning(count: usize) -> DrawWarning {
+    match count {
+        3.. => DrawWarning::Imminent,
+        2 => DrawWarning::Near,
+        _ => DrawWarning::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fifty_move_warning() {
+        assert_eq!(fifty_move_warning(0), DrawWarning::None);
+        assert_eq!(fifty_move_warning(79), DrawWarning::None);
+        assert_eq!(fifty_move_warning(80), DrawWarning::Near);
+        assert_eq!(fifty_move_warning(95), DrawWarning::Near);
+        assert_eq!(fifty_move_warning(96), DrawWarning::Imminent);
+    }
+
+    #[test]
+    fn test_repetition_warning() {
+        assert_eq!(repetition_warning(1), DrawWarning::None);
+        assert_eq!(repetition_warning(2), DrawWarning::Near);
+        assert_eq!(repetition_warning(3), DrawWarning::Imminent);
+    }
+}
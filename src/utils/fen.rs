@@ -0,0 +1,352 @@
+use std::fmt::{ Display, Formatter, Result };
+use pleco::{ Board, Player };
+
+/// Error describing why a FEN is invalid.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FenError {
+    /// The FEN does not have 4 to 6 space separated fields.
+    FieldCount(usize),
+    /// The piece placement does not have 8 ranks.
+    RankCount(usize),
+    /// A rank of the piece placement does not cover 8 squares.
+    RankLength { rank: usize, squares: usize },
+    /// The piece placement contains an unknown character.
+    InvalidPiece(char),
+    /// A player does not have exactly one king.
+    KingCount { player: Player, count: usize },
+    /// A player has more than 8 pawns or 16 pieces.
+    TooManyPieces(Player),
+    /// A pawn is placed on the first or last rank.
+    PawnOnBackRank,
+    /// The side to move is not `w` or `b`.
+    SideToMove(String),
+    /// The castling rights are not `-` or a combination of `KQkq`.
+    Castling(String),
+    /// The en passant square is not `-` or a square on the third or sixth rank.
+    EnPassant(String),
+    /// The halfmove clock is not a number.
+    HalfmoveClock(String),
+    /// The fullmove number is not a positive number.
+    FullmoveNumber(String),
+    /// The fields are well formed, but the position can't be set up.
+    Position(String),
+    /// The king of the player not to move is in check, so the side to move could capture it.
+    WaitingKingInCheck(Player),
+}
+
+impl Display for FenError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            FenError::FieldCount(count) => write!(f, "expected 4 to 6 fields, found {}", count),
+            FenError::RankCount(count) => write!(f, "piece placement: expected 8 ranks, found {}", count),
+            FenError::RankLength { rank, squares } =>
+                write!(f, "piece placement: rank {} covers {} squares instead of 8", rank, squares),
+            FenError::InvalidPiece(piece) => write!(f, "piece placement: unknown piece '{}'", piece),
+            FenError::KingCount { player, count } =>
+                write!(f, "piece placement: {} has {} kings instead of 1", player, count),
+            FenError::TooManyPieces(player) => write!(f, "piece placement: {} has too many pieces", player),
+            FenError::PawnOnBackRank => write!(f, "piece placement: pawn on the first or last rank"),
+            FenError::SideToMove(side) => write!(f, "side to move: expected 'w' or 'b', found '{}'", side),
+            FenError::Castling(castling) => write!(f, "castling: invalid rights '{}'", castling),
+            FenError::EnPassant(square) => write!(f, "en passant: invalid square '{}'", square),
+            FenError::HalfmoveClock(clock) => write!(f, "halfmove clock: invalid number '{}'", clock),
+            FenError::FullmoveNumber(number) => write!(f, "fullmove number: invalid number '{}'", number),
+            FenError::Position(reason) => write!(f, "position: {}", reason),
+            FenError::WaitingKingInCheck(player) =>
+                write!(f, "side to move: {} is in check without being to move", player),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+/// Validates a FEN, naming the offending field if it is invalid.
+///
+/// # Arguments
+///
+/// * `fen` - The FEN to validate.
+///
+/// # Returns
+///
+/// `Ok(())` if the FEN is valid, otherwise the `FenError` describing the problem.
+pub fn validate_fen(fen: &str) -> std::result::Result<(), FenError> {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    if !(4..=6).contains(&fields.len()) {
+        return Err(FenError::FieldCount(fields.len()));
+    }
+
+    validate_placement(fields[0])?;
+
+    if !matches!(fields[1], "w" | "b") {
+        return Err(FenError::SideToMove(fields[1].to_string()));
+    }
+
+    let castling = fields[2];
+    let mut seen = String::new();
+    if castling != "-" {
+        for right in castling.chars() {
+            if !"KQkq".contains(right) || seen.contains(right) {
+                return Err(FenError::Castling(castling.to_string()));
+            }
+            seen.push(right);
+        }
+    }
+
+    let en_passant = fields[3].as_bytes();
+    let en_passant_square =
+        en_passant.len() == 2 &&
+        (b'a'..=b'h').contains(&en_passant[0]) &&
+        matches!(en_passant[1], b'3' | b'6');
+    if fields[3] != "-" && !en_passant_square {
+        return Err(FenError::EnPassant(fields[3].to_string()));
+    }
+
+    if let Some(clock) = fields.get(4) {
+        if clock.parse::<u16>().is_err() {
+            return Err(FenError::HalfmoveClock(clock.to_string()));
+        }
+    }
+    if let Some(number) = fields.get(5) {
+        if number.parse::<u16>().map_or(true, |number| number == 0) {
+            return Err(FenError::FullmoveNumber(number.to_string()));
+        }
+    }
+    Ok(())
+}
+
+/// Validates the piece placement field of a FEN.
+///
+/// # Arguments
+///
+/// * `placement` - The piece placement field.
+///
+/// # Returns
+///
+/// `Ok(())` if the placement is valid, otherwise the `FenError` describing the problem.
+fn validate_placement(placement: &str) -> std::result::Result<(), FenError> {
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != 8 {
+        return Err(FenError::RankCount(ranks.len()));
+    }
+
+    // Counts of kings, pawns and all pieces for white and black
+    let mut counts = [[0usize; 3]; 2];
+
+    for (index, rank) in ranks.iter().enumerate() {
+        let mut squares = 0;
+
+        for piece in rank.chars() {
+            // A count of no empty squares is an invalid piece, counts over 8 overrun the rank
+            if let Some(empty @ 1..) = piece.to_digit(10) {
+                squares += empty as usize;
+                continue;
+            }
+            if !"KQRBNPkqrbnp".contains(piece) {
+                return Err(FenError::InvalidPiece(piece));
+            }
+            squares += 1;
+
+            let player = if piece.is_ascii_uppercase() { 0 } else { 1 };
+            match piece.to_ascii_lowercase() {
+                'k' => counts[player][0] += 1,
+                'p' => {
+                    if index == 0 || index == 7 {
+                        return Err(FenError::PawnOnBackRank);
+                    }
+                    counts[player][1] += 1;
+                }
+                _ => {}
+            }
+            counts[player][2] += 1;
+        }
+
+        if squares != 8 {
+            return Err(FenError::RankLength { rank: 8 - index, squares });
+        }
+    }
+
+    for (player, [kings, pawns, pieces]) in [Player::White, Player::Black].into_iter().zip(counts) {
+        if kings != 1 {
+            return Err(FenError::KingCount { player, count: kings });
+        }
+        if pawns > 8 || pieces > 16 {
+            return Err(FenError::TooManyPieces(player));
+        }
+    }
+    Ok(())
+}
+
+/// Sets up the position of a FEN after validating it.
+///
+/// # Arguments
+///
+/// * `fen` - The FEN to parse.
+///
+/// # Returns
+///
+/// The board of the position, otherwise the `FenError` describing the problem.
+pub fn parse_fen(fen: &str) -> std::result::Result<Board, FenError> {
+    validate_fen(fen)?;
+    Board::from_fen(fen).map_err(|error| FenError::Position(format!("{:?}", error)))
+}
+
+/// Checks that a player could be to move in a position, which needs the king of the other player to be
+/// out of check, as it could be captured otherwise.
+///
+/// # Arguments
+///
+/// * `board` - The board with the piece placement, whichever side is to move on it.
+/// * `player` - The player to move.
+///
+/// # Returns
+///
+/// `Ok(())` if the position is legal with the player to move, otherwise the `FenError` describing
+/// the problem.
+pub fn validate_side_to_move(board: &Board, player: Player) -> std::result::Result<(), FenError> {
+    let waiting = player.other_player();
+    let attackers = board.attackers_to(board.king_sq(waiting), board.occupied());
+    if (attackers & board.get_occupied_player(player)).is_not_empty() {
+        return Err(FenError::WaitingKingInCheck(waiting));
+    }
+    Ok(())
+}
+
+/// Overrides the side to move of a FEN, keeping the piece placement.
+///
+/// # Arguments
+///
+/// * `fen` - The FEN of the position, with at least four fields.
+/// * `player` - The player to move.
+///
+/// # Returns
+///
+/// The FEN with the player to move, without the en passant square if the side changed, as it
+/// would belong to the other side. Shorter FENs are returned unchanged.
+pub fn with_side_to_move(fen: &str, player: Player) -> String {
+    let mut fields: Vec<&str> = fen.split_whitespace().collect();
+    // Without an en passant field, this is no FEN to override
+    if fields.len() < 4 {
+        return fen.to_string();
+    }
+    let side_to_move = if player == Player::White { "w" } else { "b" };
+    if fields[1] != side_to_move {
+        fields[1] = side_to_move;
+        fields[3] = "-";
+    }
+    fields.join(" ")
+}
+
+/// Mirrors the position of a FEN, swapping the colors of the pieces and flipping the ranks.
+///
+/// # Arguments
+///
+/// * `fen` - The FEN of the position, with all six fields.
+///
+/// # Returns
+///
+/// The FEN of the mirrored position, with the other side to move and the castling rights and
+/// en passant square of the swapped colors.
+pub fn mirror_fen(fen: &str) -> String {
+    let fields: Vec<&str> = fen.split_whitespace().collect();
+    let swap_case = |text: &str| -> String {
+        text.chars()
+            .map(|char| {
+                if char.is_ascii_uppercase() { char.to_ascii_lowercase() } else { char.to_ascii_uppercase() }
+            })
+            .collect()
+    };
+
+    let placement = fields[0].split('/').rev().map(swap_case).collect::<Vec<String>>().join("/");
+    let side_to_move = if fields[1] == "w" { "b" } else { "w" };
+    // The rights of white come first
+    let mut castling: Vec<char> = swap_case(fields[2]).chars().collect();
+    castling.sort_by_key(|right| "KQkq-".find(*right));
+    let en_passant = fields[3].replace('3', "_").replace('6', "3").replace('_', "6");
+
+    [placement.as_str(), side_to_move, &castling.iter().collect::<String>(), &en_passant]
+        .into_iter()
+        .chain(fields[4..].iter().copied())
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mirror_fen() {
+        let fen = "r3k2r/8/8/3pP3/8/8/8/R3K1R1 w Qkq d6 0 12";
+        let mirrored = mirror_fen(fen);
+        assert_eq!(mirrored, "r3k1r1/8/8/8/3Pp3/8/8/R3K2R b KQq d3 0 12");
+        assert_eq!(mirror_fen(&mirrored), fen);
+        assert_eq!(mirror_fen("4k3/8/8/8/8/8/8/4K3 b - - 3 40"), "4k3/8/8/8/8/8/8/4K3 w - - 3 40");
+    }
+
+    #[test]
+    fn test_with_side_to_move() {
+        let fen = "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3";
+        let flipped = with_side_to_move(fen, Player::Black);
+        assert_eq!(flipped, "rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR b KQkq - 0 3");
+        assert_eq!(validate_side_to_move(&Board::from_fen(fen).unwrap(), Player::Black), Ok(()));
+        assert_eq!(with_side_to_move(fen, Player::White), fen);
+        assert_eq!(with_side_to_move("4k3/8", Player::Black), "4k3/8");
+
+        // Black to move could capture the checked white king
+        let board = Board::from_fen("4k3/8/8/8/8/8/4r3/4K3 w - - 0 1").unwrap();
+        assert_eq!(validate_side_to_move(&board, Player::White), Ok(()));
+        let error = FenError::WaitingKingInCheck(Player::White);
+        assert_eq!(validate_side_to_move(&board, Player::Black), Err(error));
+    }
+
+    #[test]
+    fn test_validate_valid_fen() {
+        assert_eq!(validate_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1"), Ok(()));
+        assert_eq!(validate_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6"), Ok(()));
+    }
+
+    #[test]
+    fn test_validate_invalid_fields() {
+        assert_eq!(validate_fen("4k3/8/8/8/8/8/8/4K3 w"), Err(FenError::FieldCount(2)));
+        assert_eq!(
+            validate_fen("4k3/8/8/8/8/8/8/4K3 x - - 0 1"),
+            Err(FenError::SideToMove(String::from("x")))
+        );
+        assert_eq!(
+            validate_fen("4k3/8/8/8/8/8/8/4K3 w KK - 0 1"),
+            Err(FenError::Castling(String::from("KK")))
+        );
+        assert_eq!(
+            validate_fen("4k3/8/8/8/8/8/8/4K3 w - e4 0 1"),
+            Err(FenError::EnPassant(String::from("e4")))
+        );
+        assert_eq!(
+            validate_fen("4k3/8/8/8/8/8/8/4K3 w - - x 1"),
+            Err(FenError::HalfmoveClock(String::from("x")))
+        );
+        assert_eq!(
+            validate_fen("4k3/8/8/8/8/8/8/4K3 w - - 0 0"),
+            Err(FenError::FullmoveNumber(String::from("0")))
+        );
+    }
+
+    #[test]
+    fn test_validate_invalid_placement() {
+        assert_eq!(validate_fen("4k3/8/8/8/8/8/4K3 w - - 0 1"), Err(FenError::RankCount(7)));
+        assert_eq!(
+            validate_fen("4k3/8/8/8/8/8/9/4K3 w - - 0 1"),
+            Err(FenError::RankLength { rank: 2, squares: 9 })
+        );
+        assert_eq!(validate_fen("4k3/8/8/8/8/8/4X3/4K3 w - - 0 1"), Err(FenError::InvalidPiece('X')));
+        assert_eq!(validate_fen("4k3/8/8/8/8/8/08/4K3 w - - 0 1"), Err(FenError::InvalidPiece('0')));
+        assert_eq!(
+            validate_fen("8/8/8/8/8/8/8/4K3 w - - 0 1"),
+            Err(FenError::KingCount { player: Player::Black, count: 0 })
+        );
+        assert_eq!(validate_fen("4k3/8/8/8/8/8/8/P3K3 w - - 0 1"), Err(FenError::PawnOnBackRank));
+        assert_eq!(
+            validate_fen("4k3/8/8/8/8/PPPPPPPP/P7/4K3 w - - 0 1"),
+            Err(FenError::TooManyPieces(Player::White))
+        );
+    }
+}
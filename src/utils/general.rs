@@ -1,6 +1,8 @@
-use ratatui::layout::{ Constraint, Direction, Layout, Rect };
-use pleco::{ BitMove, Board, File, Piece, Player, Rank, SQ };
-use std::{ iter::{ once, repeat }, rc::Rc };
+use ratatui::layout::{ Constraint, Direction, Flex, Layout, Rect };
+use pleco::{ BitMove, Board, File, Piece, PieceType, Player, Rank, SQ };
+use std::{ iter::{ once, repeat, repeat_n }, rc::Rc, time::Duration };
+
+use crate::config::Notation;
 
 /// Creates a board layout with specified rows/columns and borders.
 ///
@@ -31,6 +33,40 @@ pub fn create_board_layout(
     ).split(area);
 }
 
+/// Creates a rect of the given size centered in the given area.
+///
+/// # Arguments
+///
+/// * `width` - The width of the rect.
+/// * `height` - The height of the rect.
+/// * `area` - The area to center the rect in.
+///
+/// # Returns
+///
+/// The centered `Rect`, clipped to the area.
+pub fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
+    let [area] = Layout::horizontal([Constraint::Length(width)]).flex(Flex::Center).areas(area);
+    let [area] = Layout::vertical([Constraint::Length(height)]).flex(Flex::Center).areas(area);
+    area
+}
+
+/// Caps the width of an area, centering the capped area horizontally.
+///
+/// # Arguments
+///
+/// * `area` - The area to cap.
+/// * `max_width` - The maximum width, if any.
+///
+/// # Returns
+///
+/// The area, narrowed to `max_width` with equal margins if it is wider.
+pub fn capped_area(area: Rect, max_width: Option<u16>) -> Rect {
+    match max_width {
+        Some(max_width) if area.width > max_width => centered_rect(max_width, area.height, area),
+        _ => area,
+    }
+}
+
 /// Returns all legal moves that can be made from the given square.
 ///
 /// # Arguments
@@ -81,6 +117,29 @@ pub fn dest_in_moves(dest: SQ, moves: &Vec<BitMove>) -> bool {
     moves.iter().any(|move_| move_.get_dest() == dest)
 }
 
+/// Draws a board as a compact ASCII grid, white at the bottom, with ranks and files labeled.
+///
+/// # Arguments
+///
+/// * `board` - The board to draw.
+///
+/// # Returns
+///
+/// The grid, one line per rank, like `8 r n b q k b n r`, followed by the file letters.
+pub fn board_ascii(board: &Board) -> String {
+    let mut lines: Vec<String> = (0..8u8)
+        .rev()
+        .map(|rank| {
+            let squares: Vec<String> = (0..8u8)
+                .map(|file| board.piece_at_sq(SQ(rank * 8 + file)).character().unwrap_or('.').to_string())
+                .collect();
+            format!("{} {}", rank + 1, squares.join(" "))
+        })
+        .collect();
+    lines.push(String::from("  a b c d e f g h"));
+    lines.join("\n")
+}
+
 /// Converts a piece to a character.
 ///
 /// # Arguments
@@ -138,6 +197,175 @@ pub fn square_to_string(square: SQ) -> &'static str {
     }
 }
 
+/// Converts a piece type to its letter in standard algebraic notation.
+///
+/// # Arguments
+///
+/// * `piece_type` - The piece type to convert.
+///
+/// # Returns
+///
+/// The letter of the piece type, or an empty string for pawns.
+pub fn piece_type_to_san(piece_type: PieceType) -> &'static str {
+    match piece_type {
+        PieceType::K => "K",
+        PieceType::Q => "Q",
+        PieceType::R => "R",
+        PieceType::B => "B",
+        PieceType::N => "N",
+        _ => "",
+    }
+}
+
+/// Formats a move in the chosen notation.
+///
+/// # Arguments
+///
+/// * `bit_move` - The move to format.
+/// * `board` - The board before the move is applied.
+/// * `notation` - The notation to use.
+///
+/// # Returns
+///
+/// The move in the notation, e.g. `exd5` or `e4d5`.
+pub fn format_move(bit_move: BitMove, board: &Board, notation: Notation) -> String {
+    match notation {
+        Notation::San => move_to_san(bit_move, board),
+        Notation::Coordinate => bit_move.stringify(),
+    }
+}
+
+/// Gets the move number of the player to move as written on a scoresheet.
+///
+/// # Arguments
+///
+/// * `board` - The board before the move is applied.
+///
+/// # Returns
+///
+/// The full-move number followed by `.` for white or `...` for black, e.g. `1.` or `1...`.
+pub fn move_number(board: &Board) -> String {
+    // The plies played include those before the position a game started from
+    let full_moves = board.moves_played() / 2 + 1;
+    match board.turn() {
+        Player::White => format!("{}.", full_moves),
+        Player::Black => format!("{}...", full_moves),
+    }
+}
+
+/// Converts a move to standard algebraic notation.
+///
+/// # Arguments
+///
+/// * `bit_move` - The move to convert.
+/// * `board` - The board before the move is applied.
+///
+/// # Returns
+///
+/// The move in standard algebraic notation, e.g. `Nbd2`, `exd5` or `O-O+`.
+pub fn move_to_san(bit_move: BitMove, board: &Board) -> String {
+    let src = bit_move.get_src();
+    let dest = bit_move.get_dest();
+    let piece_type = board.piece_at_sq(src).type_of();
+
+    let mut san = if bit_move.is_king_castle() {
+        String::from("O-O")
+    } else if bit_move.is_queen_castle() {
+        String::from("O-O-O")
+    } else {
+        let mut san = String::from(piece_type_to_san(piece_type));
+
+        if piece_type == PieceType::P {
+            if bit_move.is_capture() {
+                san.push_str(&src.to_string()[..1]);
+            }
+        } else {
+            // Other pieces of the same type which can reach the same destination
+            let ambiguous: Vec<SQ> = board
+                .generate_moves()
+                .into_iter()
+                .filter(|move_| {
+                    move_.get_dest() == dest &&
+                        move_.get_src() != src &&
+                        board.piece_at_sq(move_.get_src()).type_of() == piece_type
+                })
+                .map(|move_| move_.get_src())
+                .collect();
+
+            if !ambiguous.is_empty() {
+                if ambiguous.iter().all(|square| square.file() != src.file()) {
+                    san.push_str(&src.to_string()[..1]);
+                } else if ambiguous.iter().all(|square| square.rank() != src.rank()) {
+                    san.push_str(&src.to_string()[1..]);
+                } else {
+                    san.push_str(&src.to_string());
+                }
+            }
+        }
+
+        if bit_move.is_capture() {
+            san.push('x');
+        }
+        san.push_str(&dest.to_string());
+
+        if bit_move.is_promo() {
+            san.push('=');
+            san.push_str(piece_type_to_san(bit_move.promo_piece()));
+        }
+        san
+    };
+
+    let mut board_after = board.shallow_clone();
+    board_after.apply_move(bit_move);
+    if board_after.checkmate() {
+        san.push('#');
+    } else if board_after.in_check() {
+        san.push('+');
+    }
+    san
+}
+
+/// Finds the legal move matching a move in standard algebraic notation.
+///
+/// Check, mate and annotation suffixes are ignored and castling may be written with zeros.
+///
+/// # Arguments
+///
+/// * `san` - The move in standard algebraic notation.
+/// * `board` - The board the move is played on.
+///
+/// # Returns
+///
+/// The matching legal move, or `None` if there is none.
+pub fn san_to_move(san: &str, board: &Board) -> Option<BitMove> {
+    let normalize = |san: &str| san.trim_end_matches(['+', '#', '!', '?']).replace('0', "O");
+    let san = normalize(san);
+
+    board
+        .generate_moves()
+        .into_iter()
+        .find(|bit_move| normalize(&move_to_san(*bit_move, board)) == san)
+}
+
+/// Gets the legal move matching a move in coordinate notation, such as `e7e8q`.
+///
+/// # Arguments
+///
+/// * `uci` - The move in coordinate notation.
+/// * `board` - The board the move is played on.
+///
+/// # Returns
+///
+/// The matching legal move, or `None` if there is none.
+pub fn uci_to_move(uci: &str, board: &Board) -> Option<BitMove> {
+    let uci = uci.to_lowercase();
+
+    board
+        .generate_moves()
+        .into_iter()
+        .find(|bit_move| bit_move.stringify() == uci)
+}
+
 /// Gets the file from a column.
 ///
 /// # Arguments
@@ -200,20 +428,329 @@ pub fn get_rank(row: u8, main_player: Player) -> Rank {
     }
 }
 
-/// Gets the current player based on the number of moves played.
+/// Gets the size of a board cell for the given zoom.
 ///
 /// # Arguments
 ///
-/// * `moves_played` - The number of moves played.
+/// * `zoom` - The number of rows per cell.
 ///
 /// # Returns
 ///
-/// The current player based on the number of moves played.
-pub fn get_current_player(moves_played: u16) -> Player {
-    match moves_played % 2 {
-        0 => Player::Black,
-        1 => Player::White,
-        _ => unreachable!(),
+/// A tuple of the width and height of a cell.
+pub fn get_cell_size(zoom: u16) -> (u16, u16) {
+    // Terminal cells are roughly twice as high as they are wide
+    (zoom * 2 + 1, zoom)
+}
+
+/// Gets the distance from one board cell to the next for the given zoom.
+///
+/// # Arguments
+///
+/// * `zoom` - The number of rows per cell.
+/// * `grid_lines` - Whether grid lines are drawn between the cells.
+///
+/// # Returns
+///
+/// A tuple of the width and height of a cell including the grid line right and below it.
+pub fn get_cell_pitch(zoom: u16, grid_lines: bool) -> (u16, u16) {
+    let (cell_width, cell_height) = get_cell_size(zoom);
+    (cell_width + (grid_lines as u16), cell_height + (grid_lines as u16))
+}
+
+/// Gets the size of the whole board for the given zoom.
+///
+/// # Arguments
+///
+/// * `zoom` - The number of rows per cell.
+/// * `grid_lines` - Whether grid lines are drawn between and around the cells.
+///
+/// # Returns
+///
+/// A tuple of the width and height of the board.
+pub fn get_board_size(zoom: u16, grid_lines: bool) -> (u16, u16) {
+    let (pitch_width, pitch_height) = get_cell_pitch(zoom, grid_lines);
+    (pitch_width * 8 + (grid_lines as u16), pitch_height * 8 + (grid_lines as u16))
+}
+
+/// Gets the minimum terminal size needed to display the application.
+///
+/// # Arguments
+///
+/// * `zoom` - The number of rows per cell.
+/// * `grid_lines` - Whether grid lines are drawn between and around the cells.
+///
+/// # Returns
+///
+/// A tuple of the minimum width and height of the terminal.
+pub fn get_min_terminal_size(zoom: u16, grid_lines: bool) -> (u16, u16) {
+    let (board_width, board_height) = get_board_size(zoom, grid_lines);
+
+    // The board takes up 9/17 of the terminal width
+    ((board_width * 17).div_ceil(9), board_height)
+}
+
+/// Counts the pieces of each player attacking the given square.
+///
+/// # Arguments
+///
+/// * `square` - The square to check.
+/// * `board` - The board to check.
+///
+/// # Returns
+///
+/// A tuple of the number of white and black attackers.
+pub fn count_attackers(square: SQ, board: &Board) -> (u8, u8) {
+    let attackers = board.attackers_to(square, board.occupied());
+    (
+        (attackers & board.get_occupied_player(Player::White)).count_bits(),
+        (attackers & board.get_occupied_player(Player::Black)).count_bits(),
+    )
+}
+
+/// Calculates the material difference between the players.
+///
+/// # Arguments
+///
+/// * `board` - The board to evaluate.
+///
+/// # Returns
+///
+/// The material of white minus the material of black in centipawns.
+pub fn material_difference(board: &Board) -> i32 {
+    let material = |player: Player| -> i32 {
+        [PieceType::P, PieceType::N, PieceType::B, PieceType::R, PieceType::Q]
+            .iter()
+            .map(|piece_type| (board.count_piece(player, *piece_type) as i32) * piece_value(*piece_type))
+            .sum()
+    };
+    material(Player::White) - material(Player::Black)
+}
+
+/// Gets the material value of a piece type.
+///
+/// # Arguments
+///
+/// * `piece_type` - The piece type to get the value of.
+///
+/// # Returns
+///
+/// The value in centipawns, `0` for kings and empty squares.
+pub fn piece_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::P => 100,
+        PieceType::N | PieceType::B => 300,
+        PieceType::R => 500,
+        PieceType::Q => 900,
+        _ => 0,
+    }
+}
+
+/// Gets the pieces of a player which have been captured, sorted by descending value.
+///
+/// The pieces are derived from the missing material compared to the starting position.
+///
+/// # Arguments
+///
+/// * `player` - The player whose pieces were captured.
+/// * `board` - The board to check.
+///
+/// # Returns
+///
+/// The captured pieces, most valuable first.
+pub fn captured_pieces(player: Player, board: &Board) -> Vec<Piece> {
+    [(PieceType::Q, 1), (PieceType::R, 2), (PieceType::B, 2), (PieceType::N, 2), (PieceType::P, 8)]
+        .iter()
+        .flat_map(|(piece_type, start_count)| {
+            let missing = (*start_count as u8).saturating_sub(board.count_piece(player, *piece_type));
+            repeat_n(Piece::make_lossy(player, *piece_type), missing as usize)
+        })
+        .collect()
+}
+
+/// Formats captured pieces as their glyphs.
+///
+/// # Arguments
+///
+/// * `pieces` - The captured pieces, sorted so identical pieces are adjacent.
+/// * `grouped` - Whether to show identical pieces once with a count, e.g. `♟×3`.
+///
+/// # Returns
+///
+/// The formatted pieces.
+pub fn format_captured(pieces: &[Piece], grouped: bool) -> String {
+    if !grouped {
+        return pieces.iter().map(|piece| piece_to_char(*piece)).collect();
+    }
+
+    pieces
+        .chunk_by(|a, b| a == b)
+        .map(|group| {
+            if group.len() > 1 {
+                format!("{}×{}", piece_to_char(group[0]), group.len())
+            } else {
+                piece_to_char(group[0]).to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Gets the square of the piece captured by a move.
+///
+/// For en passant captures this is the square behind the destination.
+///
+/// # Arguments
+///
+/// * `bit_move` - The move to check.
+///
+/// # Returns
+///
+/// The square of the captured piece, or `None` if the move doesn't capture.
+pub fn captured_square(bit_move: BitMove) -> Option<SQ> {
+    if bit_move.is_en_passant() {
+        Some(SQ::make(bit_move.get_dest().file(), bit_move.get_src().rank()))
+    } else if bit_move.is_capture() {
+        Some(bit_move.get_dest())
+    } else {
+        None
+    }
+}
+
+/// Gets the squares the rook moves between when castling.
+///
+/// # Arguments
+///
+/// * `bit_move` - The move to check.
+///
+/// # Returns
+///
+/// The start and target square of the rook, or `None` if the move doesn't castle.
+pub fn castling_rook_squares(bit_move: BitMove) -> Option<(SQ, SQ)> {
+    let rank = bit_move.get_src().rank();
+    if bit_move.is_king_castle() {
+        Some((SQ::make(File::H, rank), SQ::make(File::F, rank)))
+    } else if bit_move.is_queen_castle() {
+        Some((SQ::make(File::A, rank), SQ::make(File::D, rank)))
+    } else {
+        None
+    }
+}
+
+/// Gets the square the king lands on when castling.
+///
+/// Castling moves are encoded from the king to the rook, so their destination is the square of the rook.
+///
+/// # Arguments
+///
+/// * `bit_move` - The move to check.
+///
+/// # Returns
+///
+/// The target square of the king on the g- or c-file, or `None` if the move doesn't castle.
+pub fn castling_king_square(bit_move: BitMove) -> Option<SQ> {
+    let rank = bit_move.get_src().rank();
+    if bit_move.is_king_castle() {
+        Some(SQ::make(File::G, rank))
+    } else if bit_move.is_queen_castle() {
+        Some(SQ::make(File::C, rank))
+    } else {
+        None
+    }
+}
+
+/// Checks the invariants every applied move has to keep, as a safeguard against bugs in the move handling.
+///
+/// # Arguments
+///
+/// * `bit_move` - The applied move.
+/// * `before` - The board before the move.
+/// * `after` - The board after the move.
+///
+/// # Returns
+///
+/// A description of each violated invariant, empty if the move was applied correctly.
+pub fn move_invariant_violations(bit_move: BitMove, before: &Board, after: &Board) -> Vec<String> {
+    let mut violations = Vec::new();
+    let pieces = after.count_all_pieces();
+    let expected_pieces = before.count_all_pieces() - (bit_move.is_capture() as u8);
+    if pieces != expected_pieces {
+        violations.push(format!("{} pieces on the board instead of {}", pieces, expected_pieces));
+    }
+    if after.turn() == before.turn() {
+        violations.push(format!("{} is to move twice in a row", after.turn()));
+    }
+    let (moves_played, expected_moves_played) = (after.moves_played(), before.moves_played() + 1);
+    if moves_played != expected_moves_played {
+        violations.push(format!("{} moves played instead of {}", moves_played, expected_moves_played));
+    }
+    violations
+}
+
+/// Gets the squares of the pieces giving check and the squares their attacks pass.
+///
+/// # Arguments
+///
+/// * `board` - The board to check.
+///
+/// # Returns
+///
+/// The checking pieces and, for sliding pieces, the squares between them and the king.
+pub fn check_path(board: &Board) -> Vec<SQ> {
+    let king = board.king_sq(board.turn());
+    let mut squares = Vec::new();
+
+    for checker in board.checkers() {
+        squares.push(checker);
+
+        let piece_type = board.piece_at_sq(checker).type_of();
+        if !matches!(piece_type, PieceType::B | PieceType::R | PieceType::Q) {
+            continue;
+        }
+        // Walk from the checker towards the king, one square at a time
+        let file_step = ((king.0 & 7) as i8 - (checker.0 & 7) as i8).signum();
+        let rank_step = ((king.0 >> 3) as i8 - (checker.0 >> 3) as i8).signum();
+        let mut square = checker;
+        loop {
+            square = SQ(((square.0 as i8) + rank_step * 8 + file_step) as u8);
+            if square == king {
+                break;
+            }
+            squares.push(square);
+        }
+    }
+    squares
+}
+
+/// Checks whether neither side can checkmate by any sequence of legal moves.
+///
+/// Covers the dead positions listed by FIDE: king against king, king and bishop or knight against king,
+/// and kings with any number of bishops which all stand on squares of the same color.
+///
+/// # Arguments
+///
+/// * `board` - The board to check.
+///
+/// # Returns
+///
+/// `true` if the position is dead, otherwise `false`.
+pub fn is_dead_position(board: &Board) -> bool {
+    let count = |piece_type| {
+        board.count_piece(Player::White, piece_type) + board.count_piece(Player::Black, piece_type)
+    };
+    if count(PieceType::P) + count(PieceType::R) + count(PieceType::Q) > 0 {
+        return false;
+    }
+
+    let knights = count(PieceType::N);
+    let mut bishop_colors = [Player::White, Player::Black]
+        .into_iter()
+        .flat_map(|player| board.piece_bb(player, PieceType::B))
+        .map(|square| ((square.0 & 7) + (square.0 >> 3)) % 2);
+
+    match knights {
+        0 => bishop_colors.next().is_none_or(|color| bishop_colors.all(|other| other == color)),
+        1 => bishop_colors.next().is_none(),
+        _ => false,
     }
 }
 
@@ -227,7 +764,49 @@ pub fn get_current_player(moves_played: u16) -> Player {
 ///
 /// `true` if the game is over, otherwise `false`.
 pub fn is_game_over(board: &Board) -> bool {
-    board.checkmate() || board.stalemate()
+    board.checkmate() || board.stalemate() || is_dead_position(board)
+}
+
+/// Hint shown with the result of a finished game.
+pub const RESET_HINT: &str = "Press r to reset.";
+
+/// Gets the result of a finished game.
+///
+/// # Arguments
+///
+/// * `board` - The board to check.
+///
+/// # Returns
+///
+/// How the game ended, like `Checkmate!`, or `None` if it is still going on.
+pub fn game_result(board: &Board) -> Option<&'static str> {
+    if board.checkmate() {
+        Some("Checkmate!")
+    } else if board.stalemate() {
+        Some("Stalemate!")
+    } else if is_dead_position(board) {
+        Some("Draw by dead position!")
+    } else {
+        None
+    }
+}
+
+/// Formats a duration like a stopwatch.
+///
+/// # Arguments
+///
+/// * `duration` - The duration to format.
+///
+/// # Returns
+///
+/// The duration as minutes and seconds, like `4:05`, with hours added from an hour on, like `1:02:03`.
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, (seconds / 60) % 60, seconds % 60)
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
 }
 
 #[cfg(test)]
@@ -236,12 +815,43 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_board_ascii() {
+        let board = Board::from_fen("4k3/8/8/8/4P3/8/8/R3K3 b Q - 0 1").unwrap();
+        let expected = [
+            "8 . . . . k . . .",
+            "7 . . . . . . . .",
+            "6 . . . . . . . .",
+            "5 . . . . . . . .",
+            "4 . . . . P . . .",
+            "3 . . . . . . . .",
+            "2 . . . . . . . .",
+            "1 R . . . K . . .",
+            "  a b c d e f g h",
+        ];
+        assert_eq!(board_ascii(&board), expected.join("\n"));
+    }
+
     #[test]
     fn test_create_board_layout() {
         let layout = create_board_layout(Direction::Vertical, 10, 3, 1, Rect::new(0, 0, 10, 10));
         assert_eq!(layout.len(), 5);
     }
 
+    #[test]
+    fn test_centered_rect() {
+        assert_eq!(centered_rect(4, 2, Rect::new(0, 0, 10, 10)), Rect::new(3, 4, 4, 2));
+        assert_eq!(centered_rect(20, 2, Rect::new(0, 0, 10, 10)), Rect::new(0, 4, 10, 2));
+    }
+
+    #[test]
+    fn test_capped_area() {
+        let wide = Rect::new(0, 1, 400, 50);
+        assert_eq!(capped_area(wide, Some(200)), Rect::new(100, 1, 200, 50));
+        assert_eq!(capped_area(wide, None), wide);
+        assert_eq!(capped_area(Rect::new(0, 0, 150, 50), Some(200)), Rect::new(0, 0, 150, 50));
+    }
+
     #[test]
     fn test_moves_from_square() {
         let board = Board::default();
@@ -301,6 +911,81 @@ mod tests {
         assert_eq!(square_to_string(SQ::A8), "8");
     }
 
+    #[test]
+    fn test_move_to_san() {
+        let board = Board::default();
+        assert_eq!(move_to_san(move_to_square(SQ::E4, &moves_from_square(SQ::E2, &board)), &board), "e4");
+        assert_eq!(move_to_san(move_to_square(SQ::F3, &moves_from_square(SQ::G1, &board)), &board), "Nf3");
+    }
+
+    #[test]
+    fn test_format_move() {
+        let board = Board::from_fen("4k3/8/8/3p4/4P3/8/8/4K2R w K - 0 1").unwrap();
+        let capture = move_to_square(SQ::D5, &moves_from_square(SQ::E4, &board));
+        assert_eq!(format_move(capture, &board, Notation::San), "exd5");
+        assert_eq!(format_move(capture, &board, Notation::Coordinate), "e4d5");
+
+        let castle = board.generate_moves().into_iter().find(|bit_move| bit_move.is_castle()).unwrap();
+        assert_eq!(format_move(castle, &board, Notation::San), "O-O");
+        assert_eq!(format_move(castle, &board, Notation::Coordinate), "e1g1");
+    }
+
+    #[test]
+    fn test_move_number() {
+        let mut board = Board::default();
+        assert_eq!(move_number(&board), "1.");
+        board.apply_move(move_to_square(SQ::E4, &moves_from_square(SQ::E2, &board)));
+        assert_eq!(move_number(&board), "1...");
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - - 0 42").unwrap();
+        assert_eq!(move_number(&board), "42...");
+    }
+
+    #[test]
+    fn test_san_to_move() {
+        let board = Board::default();
+        let knight_move = move_to_square(SQ::F3, &moves_from_square(SQ::G1, &board));
+        assert_eq!(san_to_move("Nf3", &board), Some(knight_move));
+        assert_eq!(san_to_move("e5", &board), None);
+
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        assert!(san_to_move("0-0-0", &board).unwrap().is_queen_castle());
+        assert!(san_to_move("O-O!?", &board).unwrap().is_king_castle());
+    }
+
+    #[test]
+    fn test_uci_to_move() {
+        let board = Board::default();
+        let bit_move = uci_to_move("G1F3", &board).unwrap();
+        assert_eq!((bit_move.get_src(), bit_move.get_dest()), (SQ::G1, SQ::F3));
+        assert_eq!(uci_to_move("e2e5", &board), None);
+
+        let board = Board::from_fen("4k3/1P6/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(uci_to_move("b7b8n", &board).unwrap().promo_piece(), PieceType::N);
+    }
+
+    #[test]
+    fn test_move_to_san_file_disambiguation() {
+        let board = Board::from_fen("4k3/8/8/8/8/5N2/8/1N2K3 w - - 0 1").unwrap();
+        assert_eq!(move_to_san(move_to_square(SQ::D2, &moves_from_square(SQ::B1, &board)), &board), "Nbd2");
+        assert_eq!(move_to_san(move_to_square(SQ::D2, &moves_from_square(SQ::F3, &board)), &board), "Nfd2");
+    }
+
+    #[test]
+    fn test_move_to_san_rank_disambiguation() {
+        let board = Board::from_fen("4k3/8/8/R7/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert_eq!(move_to_san(move_to_square(SQ::A3, &moves_from_square(SQ::A1, &board)), &board), "R1a3");
+        assert_eq!(move_to_san(move_to_square(SQ::A3, &moves_from_square(SQ::A5, &board)), &board), "R5a3");
+    }
+
+    #[test]
+    fn test_move_to_san_square_disambiguation() {
+        let board = Board::from_fen("2k5/8/8/8/4Q2Q/8/8/K6Q w - - 0 1").unwrap();
+        assert_eq!(move_to_san(move_to_square(SQ::E1, &moves_from_square(SQ::H4, &board)), &board), "Qh4e1");
+        assert_eq!(move_to_san(move_to_square(SQ::E1, &moves_from_square(SQ::E4, &board)), &board), "Qee1");
+        assert_eq!(move_to_san(move_to_square(SQ::E1, &moves_from_square(SQ::H1, &board)), &board), "Q1e1");
+    }
+
     #[test]
     fn test_get_file() {
         assert_eq!(get_file(0), File::A);
@@ -335,10 +1020,153 @@ mod tests {
     }
 
     #[test]
-    fn test_get_current_player() {
-        assert_eq!(get_current_player(1), Player::White);
-        assert_eq!(get_current_player(2), Player::Black);
-        assert_eq!(get_current_player(3), Player::White);
-        assert_eq!(get_current_player(4), Player::Black);
+    fn test_get_cell_size() {
+        assert_eq!(get_cell_size(3), (7, 3));
+        assert_eq!(get_cell_size(5), (11, 5));
+        assert_eq!(get_cell_size(7), (15, 7));
+    }
+
+    #[test]
+    fn test_get_board_size() {
+        assert_eq!(get_board_size(3, false), (56, 24));
+        assert_eq!(get_board_size(3, true), (65, 33));
+        assert_eq!(get_cell_pitch(3, true), (8, 4));
+    }
+
+    #[test]
+    fn test_get_min_terminal_size() {
+        assert_eq!(get_min_terminal_size(3, false), (106, 24));
+        assert_eq!(get_min_terminal_size(5, false), (167, 40));
+        assert_eq!(get_min_terminal_size(7, false), (227, 56));
+        assert_eq!(get_min_terminal_size(3, true), (123, 33));
+    }
+
+    #[test]
+    fn test_count_attackers() {
+        let board = Board::default();
+        assert_eq!(count_attackers(SQ::F3, &board), (3, 0));
+        assert_eq!(count_attackers(SQ::D6, &board), (0, 2));
+        assert_eq!(count_attackers(SQ::E4, &board), (0, 0));
+    }
+
+    #[test]
+    fn test_captured_pieces() {
+        assert!(captured_pieces(Player::White, &Board::default()).is_empty());
+
+        let board = Board::from_fen("rnb1kbnr/ppp2ppp/8/8/8/8/PPPPP3/RNBQKBNR w KQkq - 0 1").unwrap();
+        assert_eq!(
+            captured_pieces(Player::Black, &board),
+            [Piece::BlackQueen, Piece::BlackPawn, Piece::BlackPawn]
+        );
+        assert_eq!(captured_pieces(Player::White, &board).len(), 3);
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_millis(999)), "0:00");
+        assert_eq!(format_duration(Duration::from_secs(245)), "4:05");
+        assert_eq!(format_duration(Duration::from_secs(3723)), "1:02:03");
+    }
+
+    #[test]
+    fn test_format_captured() {
+        let pieces = [Piece::BlackRook, Piece::BlackPawn, Piece::BlackPawn, Piece::BlackPawn];
+        assert_eq!(format_captured(&pieces, false), "♜♟♟♟");
+        assert_eq!(format_captured(&pieces, true), "♜ ♟×3");
+    }
+
+    #[test]
+    fn test_check_path() {
+        assert!(check_path(&Board::default()).is_empty());
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/b3K2r w - - 0 1").unwrap();
+        assert_eq!(check_path(&board), [SQ::H1, SQ::G1, SQ::F1]);
+
+        let board = Board::from_fen("4k3/8/8/8/1b6/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(check_path(&board), [SQ::B4, SQ::C3, SQ::D2]);
+
+        // Knight checks have no line, double checks mark both checkers
+        let board = Board::from_fen("4k3/8/8/8/8/3n4/8/r3K3 w - - 0 1").unwrap();
+        assert_eq!(check_path(&board), [SQ::A1, SQ::B1, SQ::C1, SQ::D1, SQ::D3]);
+    }
+
+    #[test]
+    fn test_dead_positions() {
+        let dead = [
+            "4k3/8/8/8/8/8/8/4K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/1N2K3 b - - 0 1",
+            "4k3/8/8/2b5/8/8/8/2B1K3 w - - 0 1",
+            "2b1k3/8/8/8/8/8/8/3BKB2 w - - 0 1",
+        ];
+        for fen in dead {
+            assert!(is_dead_position(&Board::from_fen(fen).unwrap()), "{}", fen);
+        }
+
+        let alive = [
+            "4k3/8/8/8/8/8/8/2BBK3 w - - 0 1",
+            "4k3/8/8/3b4/8/8/8/2B1K3 w - - 0 1",
+            "4k3/8/8/8/8/8/8/1NN1K3 w - - 0 1",
+            "1n2k3/8/8/8/8/8/8/1N2K3 w - - 0 1",
+            "4k3/8/8/8/8/8/4P3/4K3 w - - 0 1",
+        ];
+        for fen in alive {
+            assert!(!is_dead_position(&Board::from_fen(fen).unwrap()), "{}", fen);
+        }
+        assert!(is_game_over(&Board::from_fen(dead[0]).unwrap()));
+        assert_eq!(game_result(&Board::from_fen(dead[0]).unwrap()), Some("Draw by dead position!"));
+        assert_eq!(game_result(&Board::default()), None);
+    }
+
+    #[test]
+    fn test_captured_square() {
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let en_passant = san_to_move("exd6", &board).unwrap();
+        assert!(en_passant.is_en_passant());
+        assert_eq!(captured_square(en_passant), Some(SQ::D5));
+
+        let board = Board::from_fen("4k3/8/2p5/3P4/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(captured_square(san_to_move("dxc6", &board).unwrap()), Some(SQ::C6));
+        assert_eq!(captured_square(san_to_move("d6", &board).unwrap()), None);
+    }
+
+    #[test]
+    fn test_castling_rook_squares() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R b KQkq - 0 1").unwrap();
+        assert_eq!(castling_rook_squares(san_to_move("O-O", &board).unwrap()), Some((SQ::H8, SQ::F8)));
+        assert_eq!(castling_rook_squares(san_to_move("O-O-O", &board).unwrap()), Some((SQ::A8, SQ::D8)));
+        assert_eq!(castling_rook_squares(san_to_move("Kf8", &board).unwrap()), None);
+    }
+
+    #[test]
+    fn test_move_invariant_violations() {
+        let before = Board::default();
+        let bit_move = san_to_move("e4", &before).unwrap();
+        let mut after = before.shallow_clone();
+        after.apply_move(bit_move);
+        assert!(move_invariant_violations(bit_move, &before, &after).is_empty());
+
+        // Comparing against the wrong board breaks every invariant
+        let capture = Board::from_fen("4k3/8/8/3p4/4P3/8/8/4K3 w - - 0 1").unwrap();
+        let exd5 = san_to_move("exd5", &capture).unwrap();
+        assert_eq!(
+            move_invariant_violations(exd5, &capture, &capture),
+            [
+                "4 pieces on the board instead of 3",
+                "White is to move twice in a row",
+                "0 moves played instead of 1",
+            ].map(String::from)
+        );
+    }
+
+    #[test]
+    fn test_material_difference() {
+        assert_eq!(material_difference(&Board::default()), 0);
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/3QP3/4K3 w - - 0 1").unwrap();
+        assert_eq!(material_difference(&board), 1000);
+
+        let board = Board::from_fen("3rk3/8/8/8/8/8/4P3/4K3 w - - 0 1").unwrap();
+        assert_eq!(material_difference(&board), -400);
     }
 }
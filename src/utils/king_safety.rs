@@ -0,0 +1,107 @@
+use pleco::{ BitBoard, Board, PieceType, Player, SQ };
+use ratatui::style::Color;
+
+/// Highest danger a king can be in, filling its gauge.
+pub const MAX_KING_DANGER: u8 = 10;
+
+/// Simple measure of how exposed a king is, from the pawns around it and the pieces attacking it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct KingSafety {
+    /// Files next to and of the king with an own pawn on one of the two squares in front of the king.
+    pub shelter: u8,
+    /// Files next to and of the king without any own pawn.
+    pub open_files: u8,
+    /// Enemy pieces attacking the king or the squares around it.
+    pub attackers: u8,
+}
+
+impl KingSafety {
+    /// Gets the danger of the king, one point per file missing shelter and two per open file and attacker.
+    ///
+    /// # Returns
+    ///
+    /// The danger, from 0 for a well covered king up to `MAX_KING_DANGER`.
+    pub fn danger(&self) -> u8 {
+        let missing_shelter = 3u8.saturating_sub(self.shelter);
+        (missing_shelter + 2 * self.open_files + 2 * self.attackers).min(MAX_KING_DANGER)
+    }
+
+    /// Gets the color of the gauge of the king.
+    pub fn color(&self) -> Color {
+        match self.danger() {
+            7.. => Color::Red,
+            4.. => Color::Yellow,
+            _ => Color::Green,
+        }
+    }
+}
+
+/// Measures the safety of the king of a player.
+///
+/// # Arguments
+///
+/// * `board` - The board to check.
+/// * `player` - The player whose king is measured.
+///
+/// # Returns
+///
+/// The shelter, open files and attackers around the king.
+pub fn king_safety(board: &Board, player: Player) -> KingSafety {
+    let king = board.king_sq(player);
+    let (king_file, king_rank) = ((king.0 % 8) as i8, (king.0 / 8) as i8);
+    let forward = if player == Player::White { 1 } else { -1 };
+    let pawns = board.piece_bb(player, PieceType::P);
+    let has_pawn = |file: i8, rank: i8| {
+        (0..8).contains(&rank) && (pawns & SQ((rank * 8 + file) as u8).to_bb()).is_not_empty()
+    };
+
+    let mut safety = KingSafety::default();
+    for file in (king_file - 1).max(0)..=(king_file + 1).min(7) {
+        if has_pawn(file, king_rank + forward) || has_pawn(file, king_rank + 2 * forward) {
+            safety.shelter += 1;
+        }
+        if !(0..8).any(|rank| has_pawn(file, rank)) {
+            safety.open_files += 1;
+        }
+    }
+
+    // The king and the squares around it
+    let zone = (-1..=1)
+        .flat_map(|rank| (-1..=1).map(move |file| (king_rank + rank, king_file + file)))
+        .filter(|(rank, file)| (0..8).contains(rank) && (0..8).contains(file))
+        .map(|(rank, file)| SQ((rank * 8 + file) as u8));
+    let attackers = zone.fold(BitBoard(0), |attackers, square| {
+        attackers | board.attackers_to(square, board.occupied())
+    });
+    safety.attackers = (attackers & board.get_occupied_player(player.other_player())).count_bits();
+    safety
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_king_safety() {
+        let board = Board::default();
+        assert_eq!(king_safety(&board, Player::White), KingSafety { shelter: 3, open_files: 0, attackers: 0 });
+        assert_eq!(king_safety(&board, Player::White).danger(), 0);
+
+        // Castled king with the g-pawn gone
+        let board = Board::from_fen("4k3/8/8/8/8/8/5P1P/6K1 w - - 0 1").unwrap();
+        let safety = king_safety(&board, Player::White);
+        assert_eq!(safety, KingSafety { shelter: 2, open_files: 1, attackers: 0 });
+        assert_eq!(safety.danger(), 3);
+
+        // The same king facing a rook on the open file and a queen next to it
+        let board = Board::from_fen("4k1r1/8/8/8/8/8/5PqP/6K1 w - - 0 1").unwrap();
+        let safety = king_safety(&board, Player::White);
+        assert_eq!(safety.attackers, 2);
+        assert_eq!(safety.danger(), 7);
+        assert_eq!(safety.color(), Color::Red);
+
+        // Black kings look down the board
+        let board = Board::from_fen("6k1/5p1p/8/8/8/8/8/4K3 b - - 0 1").unwrap();
+        assert_eq!(king_safety(&board, Player::Black).shelter, 2);
+    }
+}
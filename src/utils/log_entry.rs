@@ -0,0 +1,46 @@
+/// Kind of a log entry, used to style it in the log.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LogKind {
+    /// Plain message.
+    #[default]
+    Info,
+    /// Move capturing a piece.
+    Capture,
+    /// Move giving check.
+    Check,
+    /// Move giving checkmate.
+    Checkmate,
+    /// Move ending the game in a draw.
+    Draw,
+    /// Accomplished goal, like a correct answer in a training.
+    Success,
+    /// Rejected input, like an illegal move.
+    Warning,
+    /// Failed action.
+    Error,
+}
+
+/// Entry of the log.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LogEntry {
+    /// Message of the entry.
+    pub message: String,
+    /// Kind of the entry.
+    pub kind: LogKind,
+}
+
+impl LogEntry {
+    /// Creates a new `LogEntry` instance.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message of the entry.
+    /// * `kind` - The kind of the entry.
+    ///
+    /// # Returns
+    ///
+    /// A new `LogEntry` instance.
+    pub fn new(message: &str, kind: LogKind) -> Self {
+        LogEntry { message: message.to_string(), kind }
+    }
+}
@@ -0,0 +1,65 @@
+use pleco::SQ;
+
+/// Mark drawn on the board by the user to annotate a position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    /// A circle around a square.
+    Circle(SQ),
+    /// An arrow from one square to another.
+    Arrow(SQ, SQ),
+}
+
+impl Mark {
+    /// Gets the mark in coordinate notation, like `e4` for a circle or `e2e4` for an arrow.
+    pub fn to_notation(self) -> String {
+        match self {
+            Mark::Circle(square) => square_name(square),
+            Mark::Arrow(from, to) => format!("{}{}", square_name(from), square_name(to)),
+        }
+    }
+
+    /// Parses a mark from its coordinate notation.
+    ///
+    /// # Arguments
+    ///
+    /// * `notation` - The mark, like `e4` or `e2e4`.
+    ///
+    /// # Returns
+    ///
+    /// The mark, or `None` if the notation is invalid.
+    pub fn from_notation(notation: &str) -> Option<Mark> {
+        match notation.as_bytes() {
+            [file, rank] => Some(Mark::Circle(parse_square(*file, *rank)?)),
+            [from_file, from_rank, to_file, to_rank] =>
+                Some(Mark::Arrow(parse_square(*from_file, *from_rank)?, parse_square(*to_file, *to_rank)?)),
+            _ => None,
+        }
+    }
+}
+
+/// Gets the lowercase name of a square, like `e4`.
+fn square_name(square: SQ) -> String {
+    format!("{}{}", (b'a' + square.0 % 8) as char, square.0 / 8 + 1)
+}
+
+/// Parses a square from its file letter and rank digit.
+fn parse_square(file: u8, rank: u8) -> Option<SQ> {
+    let (file, rank) = (file.to_ascii_lowercase().checked_sub(b'a')?, rank.checked_sub(b'1')?);
+    (file < 8 && rank < 8).then(|| SQ(rank * 8 + file))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_notation() {
+        assert_eq!(Mark::Circle(SQ::E4).to_notation(), "e4");
+        assert_eq!(Mark::Arrow(SQ::G1, SQ::F3).to_notation(), "g1f3");
+        assert_eq!(Mark::from_notation("h8"), Some(Mark::Circle(SQ::H8)));
+        assert_eq!(Mark::from_notation("a1c3"), Some(Mark::Arrow(SQ::A1, SQ::C3)));
+        assert_eq!(Mark::from_notation("i1"), None);
+        assert_eq!(Mark::from_notation("e9"), None);
+        assert_eq!(Mark::from_notation("e2e"), None);
+    }
+}
@@ -3,18 +3,62 @@ pub use coord::Coord;
 pub use coord::CoordEvent;
 
 mod cell;
-pub use cell::Cell;
+pub use cell::{ Cell, CellMarks };
+
+mod fen;
+pub use fen::{ mirror_fen, parse_fen, validate_fen, validate_side_to_move, with_side_to_move, FenError };
+
+mod log_entry;
+pub use log_entry::{ LogEntry, LogKind };
+
+mod mark;
+pub use mark::Mark;
+
+mod orientation;
+pub use orientation::Orientation;
+
+mod king_safety;
+pub use king_safety::{ king_safety, KingSafety, MAX_KING_DANGER };
+
+mod draw_warning;
+pub use draw_warning::{ fifty_move_warning, repetition_warning, DrawWarning };
 
 mod general;
 pub use general::{
+    board_ascii,
     create_board_layout,
-    get_current_player,
+    capped_area,
+    centered_rect,
+    count_attackers,
+    captured_pieces,
+    captured_square,
+    castling_king_square,
+    castling_rook_squares,
+    check_path,
+    format_captured,
+    format_duration,
+    material_difference,
+    piece_value,
+    get_min_terminal_size,
+    get_cell_size,
+    get_cell_pitch,
+    get_board_size,
     moves_from_square,
     square_to_string,
     move_to_square,
     dest_in_moves,
+    piece_type_to_san,
     piece_to_char,
+    format_move,
+    move_number,
+    move_to_san,
+    san_to_move,
+    uci_to_move,
+    game_result,
+    RESET_HINT,
+    is_dead_position,
     is_game_over,
+    move_invariant_violations,
     get_file,
     get_rank,
 };
@@ -0,0 +1,147 @@
+use pleco::Player;
+
+/// Rotation of the board on the screen, on top of the perspective of the main player.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Orientation {
+    /// The main player's side is at the bottom.
+    #[default]
+    Deg0,
+    /// Rotated clockwise by 90°, the main player's side is on the left.
+    Deg90,
+    /// Rotated by 180°, the main player's side is at the top.
+    Deg180,
+    /// Rotated clockwise by 270°, the main player's side is on the right.
+    Deg270,
+}
+
+impl Orientation {
+    /// Gets the next orientation, rotating clockwise.
+    pub fn next(self) -> Self {
+        match self {
+            Orientation::Deg0 => Orientation::Deg90,
+            Orientation::Deg90 => Orientation::Deg180,
+            Orientation::Deg180 => Orientation::Deg270,
+            Orientation::Deg270 => Orientation::Deg0,
+        }
+    }
+
+    /// Gets the rotation in degrees.
+    pub fn degrees(self) -> u16 {
+        match self {
+            Orientation::Deg0 => 0,
+            Orientation::Deg90 => 90,
+            Orientation::Deg180 => 180,
+            Orientation::Deg270 => 270,
+        }
+    }
+
+    /// Gets where the sides of both players are shown on the screen.
+    ///
+    /// # Arguments
+    ///
+    /// * `main_player` - The player out of which perspective the board is viewed.
+    ///
+    /// # Returns
+    ///
+    /// The names of the edges with the player on that side, the top or left one first.
+    pub fn sides(self, main_player: Player) -> [(&'static str, Player); 2] {
+        let other_player = main_player.other_player();
+        match self {
+            Orientation::Deg0 => [("Top", other_player), ("Bottom", main_player)],
+            Orientation::Deg90 => [("Left", main_player), ("Right", other_player)],
+            Orientation::Deg180 => [("Top", main_player), ("Bottom", other_player)],
+            Orientation::Deg270 => [("Left", other_player), ("Right", main_player)],
+        }
+    }
+
+    /// Maps a cell drawn on the screen to the board coordinate shown in it.
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row on the screen.
+    /// * `col` - The column on the screen.
+    ///
+    /// # Returns
+    ///
+    /// The row and column of the board coordinate.
+    pub fn to_board(self, row: i8, col: i8) -> (i8, i8) {
+        match self {
+            Orientation::Deg0 => (row, col),
+            Orientation::Deg90 => (7 - col, row),
+            Orientation::Deg180 => (7 - row, 7 - col),
+            Orientation::Deg270 => (col, 7 - row),
+        }
+    }
+
+    /// Maps a board coordinate to the cell it is drawn in, the inverse of [`Orientation::to_board`].
+    ///
+    /// # Arguments
+    ///
+    /// * `row` - The row of the board coordinate.
+    /// * `col` - The column of the board coordinate.
+    ///
+    /// # Returns
+    ///
+    /// The row and column on the screen.
+    pub fn to_screen(self, row: i8, col: i8) -> (i8, i8) {
+        match self {
+            Orientation::Deg0 => (row, col),
+            Orientation::Deg90 => (col, 7 - row),
+            Orientation::Deg180 => (7 - row, 7 - col),
+            Orientation::Deg270 => (7 - col, row),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pleco::SQ;
+
+    use super::*;
+    use crate::utils::Coord;
+
+    fn screen_cell(square: SQ, orientation: Orientation) -> (i8, i8) {
+        let coord = Coord::from_square(square, Player::White);
+        orientation.to_screen(coord.row, coord.col)
+    }
+
+    #[test]
+    fn test_corner_squares() {
+        let corners = [SQ::A8, SQ::H8, SQ::H1, SQ::A1];
+        let expected = [
+            (Orientation::Deg0, [(0, 0), (0, 7), (7, 7), (7, 0)]),
+            (Orientation::Deg90, [(0, 7), (7, 7), (7, 0), (0, 0)]),
+            (Orientation::Deg180, [(7, 7), (7, 0), (0, 0), (0, 7)]),
+            (Orientation::Deg270, [(7, 0), (0, 0), (0, 7), (7, 7)]),
+        ];
+
+        for (orientation, cells) in expected {
+            for (square, cell) in corners.iter().zip(cells) {
+                assert_eq!(screen_cell(*square, orientation), cell, "{} at {:?}", square, orientation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_sides() {
+        let (white, black) = (Player::White, Player::Black);
+        assert_eq!(Orientation::Deg0.sides(white), [("Top", black), ("Bottom", white)]);
+        assert_eq!(Orientation::Deg180.sides(white), [("Top", white), ("Bottom", black)]);
+        assert_eq!(Orientation::Deg90.sides(black), [("Left", black), ("Right", white)]);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut orientation = Orientation::Deg0;
+        for _ in 0..4 {
+            for row in 0..8 {
+                for col in 0..8 {
+                    let (screen_row, screen_col) = orientation.to_screen(row, col);
+                    assert_eq!(orientation.to_board(screen_row, screen_col), (row, col));
+                }
+            }
+            orientation = orientation.next();
+        }
+        assert_eq!(orientation, Orientation::Deg0);
+    }
+}
@@ -1,12 +1,61 @@
+use pleco::{ Piece, Player, SQ };
 use ratatui::{
-    layout::Direction,
+    layout::{ Constraint, Direction, Layout, Position },
     prelude::{ Buffer, Rect },
-    style::Stylize,
-    text::Line,
-    widgets::{ Block, Widget },
+    style::{ palette::tailwind::{ AMBER, BLUE, GRAY, GREEN, RED, SLATE, WHITE }, Color, Stylize },
+    symbols::{ border, line },
+    text::{ Line, Text },
+    widgets::{ Block, Borders, Clear, Paragraph, Widget },
 };
 
-use crate::{ utils::{ create_board_layout, piece_to_char, square_to_string, Cell }, App };
+use crate::{
+    config::CoordinateLabels,
+    utils::{
+        create_board_layout,
+        dest_in_moves,
+        get_board_size,
+        get_cell_pitch,
+        get_cell_size,
+        material_difference,
+        piece_to_char,
+        square_to_string,
+        Cell,
+        CellMarks,
+        Coord,
+        Mark,
+        Orientation,
+    },
+    App,
+    PromotionMenu,
+    PROMOTION_PIECES,
+};
+
+/// Material difference in centipawns at which the material bar is completely filled.
+const MATERIAL_BAR_RANGE: i32 = 1500;
+
+/// Names of the pieces in the promotion menu.
+const PROMOTION_NAMES: [&str; 4] = ["Queen", "Rook", "Bishop", "Knight"];
+
+/// Size of the promotion menu including its border.
+const PROMOTION_MENU_SIZE: (u16, u16) = (12, 6);
+
+/// Dot drawn on target squares when highlight patterns are enabled.
+const TARGET_DOT: &str = "•";
+
+/// Character of the shaft of an arrow.
+const ARROW_SHAFT: &str = "·";
+
+/// Distance in squares beyond which move arrows are faded.
+const ARROW_FADE_DISTANCE: u8 = 2;
+
+/// Symbols drawn on the sides of a square marked with a circle.
+const MARK_CIRCLE: (&str, &str) = ("(", ")");
+
+/// Color of the marks drawn by the user.
+const MARK_COLOR: Color = GREEN.c600;
+
+/// Color of the white pieces when the pieces are colored by side.
+const WHITE_PIECE_COLOR: Color = AMBER.c400;
 
 /// Board widget.
 #[derive(Debug)]
@@ -23,45 +72,623 @@ pub struct Board<'a> {
     pub app: &'a mut App,
 }
 
+impl Board<'_> {
+    /// Gets the source and target square of the move to preview.
+    ///
+    /// # Returns
+    ///
+    /// The squares of the move, if the preview is enabled and the cursor is on a legal target.
+    fn preview_squares(&self) -> Option<(SQ, SQ)> {
+        if !self.app.move_preview || !self.app.selected_coord.active {
+            return None;
+        }
+        let src = self.app.selected_coord.to_square(self.app.main_player);
+        let dest = self.app.cursor_coord.to_square(self.app.main_player);
+
+        dest_in_moves(dest, &self.app.moves_from_selected_coord).then_some((src, dest))
+    }
+
+    /// Renders the material difference bar in the row above the board.
+    ///
+    /// The white part grows from the left, proportional to the advantage of white.
+    fn render_material_bar(&self, area: Rect, buf: &mut Buffer) {
+        let board_area = self.app.board_area;
+        // Only draw into the border, so the bar never covers squares or their labels
+        if board_area.y <= area.y || board_area.width == 0 {
+            return;
+        }
+
+        let difference = material_difference(self.app.displayed_board())
+            .clamp(-MATERIAL_BAR_RANGE, MATERIAL_BAR_RANGE);
+        let white_width =
+            (((difference + MATERIAL_BAR_RANGE) as u32) * (board_area.width as u32)) /
+            ((MATERIAL_BAR_RANGE * 2) as u32);
+        let bar = Rect::new(board_area.x, board_area.y - 1, board_area.width, 1);
+        let [white, black] = Layout::horizontal([
+            Constraint::Length(white_width as u16),
+            Constraint::Fill(1),
+        ]).areas(bar);
+
+        Block::default().bg(WHITE).render(white, buf);
+        Block::default().bg(GRAY.c900).render(black, buf);
+    }
+
+    /// Gets the area a square is drawn in.
+    fn square_area(&self, square: SQ) -> Rect {
+        let grid_lines = self.app.config.grid_lines;
+        let (cell_width, cell_height) = get_cell_size(self.app.zoom);
+        let (pitch_width, pitch_height) = get_cell_pitch(self.app.zoom, grid_lines);
+        let coord = Coord::from_square(square, self.app.main_player);
+        let (row, col) = self.app.orientation.to_screen(coord.row, coord.col);
+        Rect::new(
+            self.app.board_area.x + (grid_lines as u16) + (col as u16) * pitch_width,
+            self.app.board_area.y + (grid_lines as u16) + (row as u16) * pitch_height,
+            cell_width,
+            cell_height
+        )
+    }
+
+    /// Gets the coordinate label of a square.
+    ///
+    /// # Arguments
+    ///
+    /// * `square` - The square to label.
+    /// * `screen_row` - The row the square is drawn in on the screen.
+    /// * `screen_col` - The column the square is drawn in on the screen.
+    ///
+    /// # Returns
+    ///
+    /// The label, which names the file or rank of the cursor on the bottom and left edges
+    /// when only the cursor is labeled.
+    fn coordinate_label(&self, square: SQ, screen_row: i8, screen_col: i8) -> String {
+        match self.app.config.coordinate_labels {
+            CoordinateLabels::Full => String::from(square_to_string(square)),
+            CoordinateLabels::Hidden => String::new(),
+            CoordinateLabels::Cursor if !self.app.cursor_coord.active => String::new(),
+            CoordinateLabels::Cursor => {
+                let cursor = self.app.cursor_coord;
+                let (cursor_row, cursor_col) = self.app.orientation.to_screen(cursor.row, cursor.col);
+                let file = (b'A' + square.0 % 8) as char;
+                let rank = (b'1' + square.0 / 8) as char;
+                // Rotated by a quarter, the screen columns hold ranks instead of files
+                let (column_label, row_label) = match self.app.orientation {
+                    Orientation::Deg0 | Orientation::Deg180 => (file, rank),
+                    Orientation::Deg90 | Orientation::Deg270 => (rank, file),
+                };
+
+                let mut label = String::new();
+                if screen_row == 7 && screen_col == cursor_col {
+                    label.push(column_label);
+                }
+                if screen_col == 0 && screen_row == cursor_row {
+                    label.push(row_label);
+                }
+                label
+            }
+        }
+    }
+
+    /// Renders the grid lines between and around the squares.
+    fn render_grid(&self, buf: &mut Buffer) {
+        let area = self.app.board_area;
+        let (pitch_width, pitch_height) = get_cell_pitch(self.app.zoom, true);
+        // Position of a line along the board: 0 at the start, 8 at the end and between squares otherwise
+        let line_index = |offset: u16, pitch: u16| match offset % pitch {
+            0 => Some(offset / pitch),
+            _ => None,
+        };
+
+        for y in area.top()..area.bottom() {
+            for x in area.left()..area.right() {
+                let column = line_index(x - area.x, pitch_width);
+                let row = line_index(y - area.y, pitch_height);
+                let symbol = match (row, column) {
+                    (None, None) => continue,
+                    (None, Some(_)) => line::VERTICAL,
+                    (Some(_), None) => line::HORIZONTAL,
+                    (Some(0), Some(0)) => line::TOP_LEFT,
+                    (Some(0), Some(8)) => line::TOP_RIGHT,
+                    (Some(8), Some(0)) => line::BOTTOM_LEFT,
+                    (Some(8), Some(8)) => line::BOTTOM_RIGHT,
+                    (Some(0), Some(_)) => line::HORIZONTAL_DOWN,
+                    (Some(8), Some(_)) => line::HORIZONTAL_UP,
+                    (Some(_), Some(0)) => line::VERTICAL_RIGHT,
+                    (Some(_), Some(8)) => line::VERTICAL_LEFT,
+                    (Some(_), Some(_)) => line::CROSS,
+                };
+                buf[(x, y)].set_symbol(symbol).set_fg(GRAY.c500);
+            }
+        }
+    }
+
+    /// Renders arrows from the selected piece to each of its targets, fading the distant ones.
+    fn render_move_arrows(&self, buf: &mut Buffer) {
+        let src = self.app.selected_coord.to_square(self.app.main_player);
+        let mut targets: Vec<SQ> = self.app.moves_from_selected_coord
+            .iter()
+            .map(|bit_move| bit_move.get_dest())
+            .collect();
+        // Promotions have one move per piece to the same target
+        targets.dedup();
+
+        for dest in targets {
+            let color = if src.distance(dest) <= ARROW_FADE_DISTANCE { BLUE.c700 } else { SLATE.c400 };
+            self.render_arrow(src, dest, color, buf);
+        }
+    }
+
+    /// Renders a straight arrow between the centers of two squares, leaving pieces and labels untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `src` - The square the arrow starts at.
+    /// * `dest` - The square the arrow points to.
+    /// * `color` - The color of the arrow.
+    /// * `buf` - The buffer to render to.
+    fn render_arrow(&self, src: SQ, dest: SQ, color: Color, buf: &mut Buffer) {
+        let center = |area: Rect| Position::new(area.x + area.width / 2, area.y + area.height / 2);
+        let (from, to) = (center(self.square_area(src)), center(self.square_area(dest)));
+        let area = self.app.board_area;
+        let positions = line_positions(from, to);
+
+        for position in &positions[1..positions.len().saturating_sub(1)] {
+            if area.contains(*position) && buf[*position].symbol() == " " {
+                buf[*position].set_symbol(ARROW_SHAFT).set_fg(color);
+            }
+        }
+        if area.contains(to) {
+            let (dx, dy) = ((to.x as i32) - (from.x as i32), (to.y as i32) - (from.y as i32));
+            buf[to].set_symbol(arrow_head(dx, dy)).set_fg(color);
+        }
+    }
+
+    /// Renders the marks drawn by the user on the shown position.
+    ///
+    /// Circles are drawn as brackets on both sides of the square's center.
+    fn render_marks(&self, buf: &mut Buffer) {
+        for mark in self.app.shown_marks() {
+            match *mark {
+                Mark::Circle(square) => {
+                    let area = self.square_area(square);
+                    let y = area.y + area.height / 2;
+                    buf[(area.left(), y)].set_symbol(MARK_CIRCLE.0).set_fg(MARK_COLOR);
+                    buf[(area.right() - 1, y)].set_symbol(MARK_CIRCLE.1).set_fg(MARK_COLOR);
+                }
+                Mark::Arrow(src, dest) => self.render_arrow(src, dest, MARK_COLOR, buf),
+            }
+        }
+    }
+
+    /// Renders the promotion menu next to the promotion square.
+    fn render_promotion_menu(&self, menu: PromotionMenu, buf: &mut Buffer) {
+        let square = self.square_area(menu.bit_move.get_dest());
+        let area = menu_area(square, self.app.board_area, PROMOTION_MENU_SIZE.0, PROMOTION_MENU_SIZE.1);
+
+        let player = self.app.board.turn();
+        let entries = Text::from(
+            PROMOTION_PIECES.iter()
+                .zip(PROMOTION_NAMES)
+                .enumerate()
+                .map(|(index, (piece_type, name))| {
+                    let piece = piece_to_char(Piece::make_lossy(player, *piece_type));
+                    let line = Line::from(format!(" {} {}", piece, name));
+                    if index == menu.selected { line.reversed().bold() } else { line }
+                })
+                .collect::<Vec<Line>>()
+        );
+
+        Clear.render(area, buf);
+        Paragraph::new(entries)
+            .block(
+                Block::default()
+                    .title_top(Line::from("Promote").centered().bold())
+                    .borders(Borders::ALL)
+                    .border_set(border::ROUNDED)
+            )
+            .render(area, buf);
+    }
+}
+
+/// Gets the line showing a piece on its square.
+///
+/// # Arguments
+///
+/// * `piece` - The piece to show.
+/// * `colored` - Whether to use the filled glyph colored by side instead of outline and filled glyphs.
+///
+/// # Returns
+///
+/// The styled glyph of the piece.
+fn piece_line(piece: Piece, colored: bool) -> Line<'static> {
+    match piece.player() {
+        Some(player) if colored => {
+            let glyph = piece_to_char(Piece::make_lossy(Player::Black, piece.type_of()));
+            let color = if player == Player::White { WHITE_PIECE_COLOR } else { Color::Black };
+            Line::from(glyph).fg(color)
+        }
+        _ => Line::from(piece_to_char(piece)).black(),
+    }
+}
+
+/// Adds borders marking the cursor and the selection to the block of a cell.
+///
+/// The cursor is outlined, the selected square gets a double border and both together a thick one.
+///
+/// # Arguments
+///
+/// * `block` - The block of the cell.
+/// * `marks` - The highlights of the cell.
+///
+/// # Returns
+///
+/// The block with the pattern of its highlights.
+fn pattern_block(block: Block<'_>, marks: CellMarks) -> Block<'_> {
+    let border_set = match (marks.cursor, marks.selected) {
+        (true, true) => border::THICK,
+        (true, false) => border::PLAIN,
+        (false, true) => border::DOUBLE,
+        (false, false) => return block,
+    };
+    block.borders(Borders::ALL).border_set(border_set).border_style(Color::Black)
+}
+
+/// Places a menu at a square, keeping it inside the bounds.
+///
+/// The menu opens downwards from the top of the square,
+/// or upwards from its bottom if there is no room below.
+///
+/// # Arguments
+///
+/// * `square` - The square to anchor the menu at.
+/// * `bounds` - The area the menu has to stay inside.
+/// * `width` - The width of the menu.
+/// * `height` - The height of the menu.
+///
+/// # Returns
+///
+/// The area of the menu.
+fn menu_area(square: Rect, bounds: Rect, width: u16, height: u16) -> Rect {
+    let x = square.x.min(bounds.right().saturating_sub(width)).max(bounds.x);
+    let y = if square.y + height <= bounds.bottom() { square.y } else { square.bottom().saturating_sub(height) };
+
+    Rect::new(x, y.max(bounds.y), width, height).intersection(bounds)
+}
+
+/// Gets the head of an arrow pointing in a direction.
+///
+/// # Arguments
+///
+/// * `dx` - The horizontal direction, positive to the right.
+/// * `dy` - The vertical direction, positive downwards.
+///
+/// # Returns
+///
+/// The character of the arrow head.
+fn arrow_head(dx: i32, dy: i32) -> &'static str {
+    match (dx.signum(), dy.signum()) {
+        (1, 0) => "→",
+        (-1, 0) => "←",
+        (0, -1) => "↑",
+        (0, 1) => "↓",
+        (1, -1) => "↗",
+        (-1, -1) => "↖",
+        (1, 1) => "↘",
+        (-1, 1) => "↙",
+        _ => "•",
+    }
+}
+
+/// Gets the positions on the straight line between two positions.
+///
+/// # Arguments
+///
+/// * `from` - The start of the line.
+/// * `to` - The end of the line.
+///
+/// # Returns
+///
+/// The positions of the line from `from` to `to`, both included.
+fn line_positions(from: Position, to: Position) -> Vec<Position> {
+    let (mut x, mut y) = (from.x as i32, from.y as i32);
+    let (dx, dy) = ((to.x as i32) - x, (to.y as i32) - y);
+    let (step_x, step_y) = (dx.signum(), dy.signum());
+    let (dx, dy) = (dx.abs(), -dy.abs());
+    let mut error = dx + dy;
+    let mut positions = vec![from];
+
+    // Bresenham's line algorithm
+    while (x, y) != (to.x as i32, to.y as i32) {
+        let doubled_error = 2 * error;
+        if doubled_error >= dy {
+            error += dy;
+            x += step_x;
+        }
+        if doubled_error <= dx {
+            error += dx;
+            y += step_y;
+        }
+        positions.push(Position::new(x as u16, y as u16));
+    }
+    positions
+}
+
 impl Widget for Board<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let piece_locations = self.app.board.get_piece_locations();
+        let piece_locations = self.app.displayed_board().get_piece_locations();
+        let preview = self.preview_squares();
+        // Only animate the live position, not a position looked back at
+        let capture_animation = self.app.capture_animation
+            .filter(|animation| self.app.viewed_ply.is_none() && !animation.is_finished());
+        // The dragged piece is drawn at the mouse instead of on its square
+        let dragged_from = self.app.drag_position
+            .filter(|_| self.app.selected_coord.active)
+            .map(|_| self.app.selected_coord.to_square(self.app.main_player));
+        let grid_lines = self.app.config.grid_lines;
+        let grid = grid_lines as u16;
+        let (board_width, board_height) = get_board_size(self.app.zoom, grid_lines);
+        let (pitch_width, pitch_height) = get_cell_pitch(self.app.zoom, grid_lines);
 
-        // Create the layout for the rows while centering the board using the border size
-        let rows = create_board_layout(Direction::Vertical, area.height, 8, (area.height % 8) / 2, area);
+        // Remember where the board is drawn to map mouse positions back to coordinates
+        self.app.board_area = Rect::new(
+            area.x + area.width.saturating_sub(board_width) / 2,
+            area.y + area.height.saturating_sub(board_height) / 2,
+            board_width,
+            board_height
+        ).intersection(area);
+        if grid_lines {
+            self.render_grid(buf);
+        }
+        // The squares lie inside the outer grid lines
+        let squares_area = Rect::new(
+            self.app.board_area.x + grid,
+            self.app.board_area.y + grid,
+            pitch_width * 8,
+            pitch_height * 8
+        ).intersection(area);
+
+        // Create the layout for the rows, the board is already centered
+        let rows = create_board_layout(Direction::Vertical, pitch_height * 8, 8, 0, squares_area);
 
         // Loop over each row to create the individual cells while skipping the top and bottom borders
         for (row, area) in rows.iter().skip(1).take(8).enumerate() {
-            // Create the layout for the current cell while centering the board using the border size
-            let columns = create_board_layout(
-                Direction::Horizontal,
-                area.width,
-                8,
-                (area.width % 8) / 2,
-                *area
-            );
+            // Create the layout for the current cell
+            let columns = create_board_layout(Direction::Horizontal, pitch_width * 8, 8, 0, *area);
 
             // Loop over each cell in a column while skipping the left and right borders
-            for (col, square) in columns.iter().skip(1).take(8).enumerate() {
-                let cell = Cell::new(
-                    &self.app.moves_from_selected_coord,
-                    self.app.selected_coord,
-                    self.app.cursor_coord,
-                    self.app.main_player,
-                    row,
-                    col
+            for (col, slot) in columns.iter().skip(1).take(8).enumerate() {
+                // The grid lines take the last column and row of each slot
+                let square = Rect::new(
+                    slot.x,
+                    slot.y,
+                    slot.width.saturating_sub(grid),
+                    slot.height.saturating_sub(grid)
                 );
+                let (screen_row, screen_col) = (row as i8, col as i8);
+                let (row, col) = self.app.orientation.to_board(screen_row, screen_col);
+                let cell = Cell::new(self.app, row as usize, col as usize);
 
-                Block::default()
+                // Show the previewed move by moving the piece glyph without touching the board
+                let capture = capture_animation.filter(|animation| animation.square == cell.square);
+                let piece = match (capture, preview) {
+                    (Some(animation), _) => {
+                        let color = if animation.is_fading() { RED.c300 } else { RED.c600 };
+                        Line::from(piece_to_char(animation.piece)).fg(color)
+                    }
+                    _ if dragged_from == Some(cell.square) => Line::from(piece_to_char(Piece::None)),
+                    (_, Some((src, _))) if src == cell.square => Line::from(piece_to_char(Piece::None)),
+                    (_, Some((src, dest))) if dest == cell.square =>
+                        Line::from(piece_to_char(piece_locations.piece_at(src))).dark_gray(),
+                    _ => piece_line(piece_locations.piece_at(cell.square), self.app.config.colored_pieces),
+                };
+
+                let block = Block::default()
                     .bg(cell.color)
-                    .title_top(
-                        Line::from(piece_to_char(piece_locations.piece_at(cell.square))) // TODO: find a better way to display the piece
-                            .centered()
-                            .black()
-                    )
-                    .title_bottom(Line::from(square_to_string(cell.square)))
-                    .render(*square, buf);
+                    .title_top(piece.centered()) // TODO: find a better way to display the piece
+                    .title_bottom(Line::from(self.coordinate_label(cell.square, screen_row, screen_col)));
+
+                if self.app.config.highlight_patterns {
+                    pattern_block(block, cell.marks).render(square, buf);
+                    if cell.marks.target {
+                        let center = Rect::new(square.x, square.y + square.height / 2, square.width, 1);
+                        Line::from(TARGET_DOT).black().centered().render(center, buf);
+                    }
+                } else {
+                    block.render(square, buf);
+                }
+            }
+        }
+
+        if self.app.move_arrows && self.app.selected_coord.active {
+            self.render_move_arrows(buf);
+        }
+        self.render_marks(buf);
+        if let (Some(square), Some(position)) = (dragged_from, self.app.drag_position) {
+            if area.contains(position) {
+                let piece = piece_line(piece_locations.piece_at(square), self.app.config.colored_pieces);
+                buf.set_line(position.x, position.y, &piece.bold(), 1);
             }
         }
+        if self.app.material_bar {
+            self.render_material_bar(area, buf);
+        }
+        if let Some(menu) = self.app.promotion_menu {
+            self.render_promotion_menu(menu, buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preview_squares() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(6, 4);
+        app.update_selected_coord();
+        app.cursor_coord.move_to(4, 4);
+        assert_eq!(Board { app: &mut app }.preview_squares(), None);
+
+        app.toggle_move_preview();
+        assert_eq!(Board { app: &mut app }.preview_squares(), Some((SQ::E2, SQ::E4)));
+
+        app.cursor_coord.move_to(3, 4);
+        assert_eq!(Board { app: &mut app }.preview_squares(), None);
+    }
+
+    #[test]
+    fn test_move_arrows() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(7, 6);
+        app.update_selected_coord();
+        app.toggle_move_arrows();
+
+        let area = Rect::new(0, 0, 60, 26);
+        let mut buf = Buffer::empty(area);
+        Board { app: &mut app }.render(area, &mut buf);
+
+        let board = Board { app: &mut app };
+        let f3 = board.square_area(SQ::F3);
+        let h3 = board.square_area(SQ::H3);
+        assert_eq!(buf[(f3.x + f3.width / 2, f3.y + f3.height / 2)].symbol(), "↖");
+        assert_eq!(buf[(h3.x + h3.width / 2, h3.y + h3.height / 2)].symbol(), "↗");
+        assert_eq!(app.log.last().unwrap().message, "Move arrows enabled");
+    }
+
+    #[test]
+    fn test_arrow_lines() {
+        let positions = line_positions(Position::new(0, 0), Position::new(4, 4));
+        assert_eq!(positions.len(), 5);
+        assert_eq!(positions[2], Position::new(2, 2));
+        assert_eq!(line_positions(Position::new(6, 2), Position::new(2, 0)).len(), 5);
+
+        assert_eq!(arrow_head(4, 4), "↘");
+        assert_eq!(arrow_head(-4, -2), "↖");
+        assert_eq!(arrow_head(0, 0), "•");
+    }
+
+    #[test]
+    fn test_marks() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(4, 4);
+        app.toggle_mark();
+
+        let area = Rect::new(0, 0, 60, 26);
+        let mut buf = Buffer::empty(area);
+        Board { app: &mut app }.render(area, &mut buf);
+
+        let e4 = Board { app: &mut app }.square_area(SQ::E4);
+        let y = e4.y + e4.height / 2;
+        assert_eq!(buf[(e4.left(), y)].symbol(), "(");
+        assert_eq!(buf[(e4.right() - 1, y)].symbol(), ")");
+    }
+
+    #[test]
+    fn test_cursor_coordinate_labels() {
+        let mut app = App::new();
+        app.cursor_coord.move_to(4, 4);
+        assert_eq!(Board { app: &mut app }.coordinate_label(SQ::A1, 7, 0), "A1");
+
+        app.cycle_coordinate_labels();
+        let board = Board { app: &mut app };
+        assert_eq!(board.coordinate_label(SQ::E1, 7, 4), "E");
+        assert_eq!(board.coordinate_label(SQ::A4, 4, 0), "4");
+        assert_eq!(board.coordinate_label(SQ::A1, 7, 0), "");
+        assert_eq!(board.coordinate_label(SQ::E4, 4, 4), "");
+
+        // Rotated by a quarter, the bottom edge shows the rank of the cursor
+        app.rotate_board();
+        let (row, col) = app.orientation.to_screen(app.cursor_coord.row, app.cursor_coord.col);
+        let (edge_row, edge_col) = app.orientation.to_board(7, col);
+        let edge_square = Coord::new(edge_col, edge_row, true).to_square(app.main_player);
+        let board = Board { app: &mut app };
+        assert_eq!(board.coordinate_label(edge_square, 7, col), "4");
+        let (edge_row, edge_col) = board.app.orientation.to_board(row, 0);
+        let edge_square = Coord::new(edge_col, edge_row, true).to_square(board.app.main_player);
+        assert_eq!(board.coordinate_label(edge_square, row, 0), "E");
+
+        app.cycle_coordinate_labels();
+        assert_eq!(Board { app: &mut app }.coordinate_label(SQ::A1, 7, 0), "");
+    }
+
+    #[test]
+    fn test_pattern_block() {
+        let area = Rect::new(0, 0, 7, 3);
+        let render = |marks| {
+            let mut buf = Buffer::empty(area);
+            pattern_block(Block::default(), marks).render(area, &mut buf);
+            buf[(0, 0)].symbol().to_string()
+        };
+
+        assert_eq!(render(CellMarks::default()), " ");
+        assert_eq!(render(CellMarks { cursor: true, ..Default::default() }), "┌");
+        assert_eq!(render(CellMarks { selected: true, ..Default::default() }), "╔");
+        assert_eq!(render(CellMarks { cursor: true, selected: true, target: false }), "┏");
+    }
+
+    #[test]
+    fn test_colored_pieces() {
+        let white = piece_line(Piece::WhiteKnight, true);
+        assert_eq!(white.to_string(), "♞");
+        assert_eq!(white.style.fg, Some(WHITE_PIECE_COLOR));
+
+        let black = piece_line(Piece::BlackKnight, true);
+        assert_eq!(black.to_string(), "♞");
+        assert_eq!(black.style.fg, Some(Color::Black));
+
+        assert_eq!(piece_line(Piece::WhiteKnight, false).to_string(), "♘");
+    }
+
+    #[test]
+    fn test_menu_area_stays_inside_board() {
+        let bounds = Rect::new(10, 2, 56, 24);
+
+        let top_left = menu_area(Rect::new(10, 2, 7, 3), bounds, 12, 6);
+        assert_eq!(top_left, Rect::new(10, 2, 12, 6));
+
+        let bottom_right = menu_area(Rect::new(59, 23, 7, 3), bounds, 12, 6);
+        assert_eq!(bottom_right, Rect::new(54, 20, 12, 6));
+        assert_eq!(bottom_right.intersection(bounds), bottom_right);
+    }
+
+    #[test]
+    fn test_grid_lines() {
+        let mut app = App::new();
+        app.config.grid_lines = true;
+        let area = Rect::new(0, 0, 70, 35);
+        let mut buf = Buffer::empty(area);
+        Board { app: &mut app }.render(area, &mut buf);
+
+        let board_area = app.board_area;
+        assert_eq!(board_area, Rect::new(2, 1, 65, 33));
+        assert_eq!(buf[(board_area.x, board_area.y)].symbol(), "┌");
+        assert_eq!(buf[(board_area.right() - 1, board_area.bottom() - 1)].symbol(), "┘");
+        assert_eq!(buf[(board_area.x + 8, board_area.y + 4)].symbol(), "┼");
+        assert_eq!(buf[(board_area.x + 4, board_area.y + 4)].symbol(), "─");
+
+        // Squares sit between the lines, with their piece in the top row
+        let board = Board { app: &mut app };
+        let e1 = board.square_area(SQ::E1);
+        assert_eq!(e1, Rect::new(board_area.x + 33, board_area.y + 29, 7, 3));
+        assert_eq!(buf[(e1.x + 3, e1.y)].symbol(), "♔");
+        assert_eq!(buf[(e1.x + 3, e1.y - 1)].symbol(), "─");
+        assert_eq!(app.coord_at(e1.x, e1.y).unwrap().to_square(Player::White), SQ::E1);
+    }
+
+    #[test]
+    fn test_material_bar() {
+        let mut app = App::new();
+        app.toggle_material_bar();
+        app.load_fen("4k3/8/8/8/8/8/3QP3/4K3 w - - 0 1").unwrap();
+
+        let area = Rect::new(0, 0, 60, 26);
+        let mut buf = Buffer::empty(area);
+        Board { app: &mut app }.render(area, &mut buf);
+
+        let bar_row = app.board_area.y - 1;
+        let white_cells = (app.board_area.left()..app.board_area.right())
+            .filter(|x| buf[(*x, bar_row)].bg == WHITE)
+            .count();
+        assert_eq!(white_cells, (56 * 2500) / 3000);
     }
 }
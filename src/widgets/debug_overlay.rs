@@ -0,0 +1,79 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{ Style, Stylize },
+    symbols::border,
+    text::{ Line, Text },
+    widgets::{ Block, Borders, Paragraph, Widget, Wrap },
+};
+
+use crate::App;
+
+/// Width of the debug overlay.
+pub const DEBUG_OVERLAY_WIDTH: u16 = 44;
+
+/// Height of the debug overlay.
+pub const DEBUG_OVERLAY_HEIGHT: u16 = 12;
+
+/// Debug overlay widget.
+/// Shows internal state of the game over whatever is below it, which stays visible but faded.
+#[derive(Debug)]
+pub struct DebugOverlay<'a> {
+    /// App struct.
+    pub app: &'a App,
+}
+
+impl Widget for DebugOverlay<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let app = self.app;
+        let board = &app.board;
+        let lines = vec![
+            format!("cursor: {} ({})", app.cursor_coord, app.cursor_coord.to_square(app.main_player)),
+            format!(
+                "selected: {} ({}{})",
+                app.selected_coord,
+                app.selected_coord.to_square(app.main_player),
+                if app.selected_coord.active { "" } else { ", inactive" }
+            ),
+            format!("main player: {}", app.main_player),
+            format!("turn: {}", board.turn()),
+            format!("moves played: {}", board.moves_played()),
+            format!("zobrist: {:#018x}", board.zobrist()),
+            format!("block inputs: {}", app.block_inputs),
+            format!("fen: {}", board.fen()),
+        ];
+
+        // Fade the cells below instead of clearing them
+        buf.set_style(area, Style::new().dim());
+        Paragraph::new(Text::from(lines.into_iter().map(Line::from).collect::<Vec<Line>>()))
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title_top(Line::from("Debug").centered().bold())
+                    .title_bottom(Line::from("F12 to hide").centered())
+                    .borders(Borders::ALL)
+                    .border_set(border::ROUNDED)
+            )
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_debug_overlay() {
+        let mut app = App::new();
+        app.apply_san("e4").unwrap();
+        let mut buf = Buffer::empty(Rect::new(0, 0, DEBUG_OVERLAY_WIDTH, DEBUG_OVERLAY_HEIGHT));
+        DebugOverlay { app: &app }.render(buf.area, &mut buf);
+        let text: String = buf.content().iter().map(|cell| cell.symbol()).collect();
+
+        assert!(text.contains("turn: Black"));
+        assert!(text.contains("moves played: 1"));
+        assert!(text.contains("block inputs: false"));
+        assert!(text.contains("fen:"));
+        assert!(text.contains("4P3/8/PPPP1PPP"));
+    }
+}
@@ -0,0 +1,90 @@
+use pleco::Player;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::{ Line, Text },
+    widgets::{ Block, Borders, Clear, Paragraph, Widget, Wrap },
+};
+
+use crate::{ utils::{ captured_pieces, format_captured }, Adjudication, App };
+
+/// Width of the game summary.
+pub const GAME_SUMMARY_WIDTH: u16 = 46;
+
+/// Height of the game summary.
+pub const GAME_SUMMARY_HEIGHT: u16 = 11;
+
+/// Game summary widget.
+/// Shows the result and statistics of a finished game.
+#[derive(Debug)]
+pub struct GameSummary<'a> {
+    /// App struct.
+    pub app: &'a App,
+}
+
+impl Widget for GameSummary<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let board = &self.app.board;
+        let result = self.app.final_result().unwrap_or_default();
+        // The player who resigned loses, or the one to move if mated
+        let loser = match self.app.adjudication {
+            Some(Adjudication::Resigned(player)) => Some(player),
+            Some(Adjudication::DrawAgreed) => None,
+            None => board.checkmate().then(|| board.turn()),
+        };
+        let result = match loser {
+            Some(loser) => format!("{} {} wins", result, self.app.player_label(loser.other_player())),
+            None => result.to_string(),
+        };
+        let grouped = self.app.config.group_captured;
+        let captured_by = |player: Player| {
+            let pieces = captured_pieces(player.other_player(), board);
+            Line::from(format!("{} took: {}", player, format_captured(&pieces, grouped)))
+        };
+
+        let lines = vec![
+            Line::from(result).bold(),
+            Line::from(""),
+            Line::from(format!("Moves: {}", self.app.move_history.len().div_ceil(2))),
+            captured_by(Player::White),
+            captured_by(Player::Black),
+            Line::from(""),
+            Line::from(format!("FEN: {}", board.fen())).gray(),
+        ];
+
+        Clear.render(area, buf);
+        Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .title_top(Line::from("Game over").centered().bold())
+                    .title_bottom(Line::from("p PGN · f FEN · r reset · Esc close").centered())
+                    .borders(Borders::ALL)
+                    .border_set(border::ROUNDED)
+            )
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_game_summary() {
+        let mut app = App::new();
+        for san in ["e4", "f5", "exf5", "g5", "Qh5#"] {
+            app.apply_san(san).unwrap();
+        }
+        let mut buf = Buffer::empty(Rect::new(0, 0, GAME_SUMMARY_WIDTH, GAME_SUMMARY_HEIGHT));
+        GameSummary { app: &app }.render(buf.area, &mut buf);
+        let text: String = buf.content().iter().map(|cell| cell.symbol()).collect();
+
+        assert!(text.contains("Checkmate! White wins"));
+        assert!(text.contains("Moves: 3"));
+        assert!(text.contains("White took: ♟"));
+        assert!(text.contains("rnbqkbnr/ppppp2p/"));
+    }
+}
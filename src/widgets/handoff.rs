@@ -0,0 +1,34 @@
+use pleco::Player;
+use ratatui::{
+    widgets::{ Block, BorderType, Paragraph, Widget },
+    prelude::{ Buffer, Line, Rect },
+    style::Stylize,
+};
+
+/// Handoff widget.
+/// Hides the board until the next player is ready.
+#[derive(Debug)]
+pub struct Handoff {
+    /// The player to move next.
+    pub player: Player,
+}
+
+impl Widget for Handoff {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title_top(Line::from(" Pass the device ").centered().bold())
+            .border_type(BorderType::Rounded);
+
+        Paragraph::new(
+            vec![
+                Line::from(""),
+                Line::from(format!("{}'s turn", self.player).bold()),
+                Line::from(""),
+                Line::from("Press space to continue")
+            ]
+        )
+            .centered()
+            .block(block)
+            .render(area, buf);
+    }
+}
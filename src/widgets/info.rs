@@ -1,14 +1,91 @@
-use pleco::PieceType;
+use pleco::{ PieceType, Player };
 use ratatui::{
     buffer::Buffer,
-    layout::{ Constraint, Direction, Layout, Rect },
-    style::Stylize,
+    layout::{ Margin, Rect },
+    style::{ Color, Stylize },
     symbols::border,
     text::{ Line, Text },
-    widgets::{ Block, Borders, Paragraph, Widget },
+    widgets::{ Block, Borders, Paragraph, Widget, Wrap },
 };
 
-use crate::{ utils::{ get_current_player, is_game_over }, App };
+use crate::{
+    config::FenDisplay,
+    engine::format_eval,
+    utils::{
+        captured_pieces,
+        count_attackers,
+        fifty_move_warning,
+        format_captured,
+        format_duration,
+        king_safety,
+        repetition_warning,
+        KingSafety,
+        MAX_KING_DANGER,
+        RESET_HINT,
+    },
+    App,
+    Panel,
+};
+
+/// Number of plies shown in the last moves line.
+const LAST_MOVES_COUNT: usize = 4;
+
+/// Number of cells of each king danger gauge, each standing for two points of danger.
+const KING_GAUGE_CELLS: u8 = MAX_KING_DANGER / 2;
+
+/// Frames of the spinner shown while the engine is thinking, advancing every `SPINNER_FRAME_MS`.
+const SPINNER_FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+
+/// Milliseconds each frame of the spinner is shown.
+const SPINNER_FRAME_MS: u128 = 250;
+
+/// Labels of the fields of a FEN, in their order.
+const FEN_FIELD_LABELS: [&str; 6] = [
+    "Placement",
+    "Side to move",
+    "Castling",
+    "En passant",
+    "Halfmove clock",
+    "Fullmove",
+];
+
+/// Gets the lines showing the FEN of a position.
+///
+/// # Arguments
+///
+/// * `board` - The board to show the FEN of.
+/// * `display` - How to show the FEN.
+///
+/// # Returns
+///
+/// No lines if the FEN is hidden, one line for the raw FEN or one labeled line per field.
+fn fen_lines(board: &pleco::Board, display: FenDisplay) -> Vec<Line<'static>> {
+    let fen = board.fen();
+    match display {
+        FenDisplay::Hidden => Vec::new(),
+        FenDisplay::Raw => vec![format!("FEN: {}", fen).bold().into_left_aligned_line()],
+        FenDisplay::Split =>
+            FEN_FIELD_LABELS
+                .iter()
+                .zip(fen.split_whitespace())
+                .map(|(label, field)| format!("{}: {}", label, field).bold().into_left_aligned_line())
+                .collect(),
+    }
+}
+
+/// Draws the danger of a king as a small gauge, like `██░░░`.
+///
+/// # Arguments
+///
+/// * `safety` - The safety of the king.
+///
+/// # Returns
+///
+/// The filled cells followed by the empty ones.
+fn king_gauge(safety: KingSafety) -> String {
+    let filled = safety.danger().div_ceil(2);
+    format!("{}{}", "█".repeat(filled as usize), "░".repeat((KING_GAUGE_CELLS - filled) as usize))
+}
 
 /// Info widget.
 #[derive(Debug)]
@@ -27,9 +104,16 @@ impl Widget for Info<'_> {
         let blank = Line::from("");
 
         let moves = format!("Move: {}", self.app.board.moves_played() + 1);
-        let player = format!("{}", get_current_player(self.app.board.moves_played() + 1));
+        let player = format!("{}", self.app.board.turn());
         let top_line = format!("{} | {}", moves, player).bold().into_centered_line();
 
+        // Label the players by the edge of the board their side is shown at
+        let side_labels = self.app.orientation
+            .sides(self.app.main_player)
+            .map(|(edge, player)| {
+                format!("{}: {}", edge, self.app.player_label(player)).bold().into_left_aligned_line()
+            });
+
         let current_square = format!("Current square: {}", square.to_string().to_uppercase())
             .bold()
             .into_left_aligned_line();
@@ -41,76 +125,256 @@ impl Widget for Info<'_> {
             .bold()
             .into_left_aligned_line();
 
-        let info_text = Text::from(Vec::from([top_line, blank.clone(), current_square, current_piece]));
-        let checkmate_text = Text::from(
-            Vec::from([
-                (if self.app.board.checkmate() { "Checkmate!" } else { "Stalemate!" })
-                    .bold()
-                    .into_centered_line(),
-                blank,
-                "Press r to reset.".bold().into_centered_line(),
-            ])
-        );
+        let (white_attackers, black_attackers) = count_attackers(square, &self.app.board);
+        let attackers = format!("Attackers: W{} B{}", white_attackers, black_attackers)
+            .bold()
+            .into_left_aligned_line();
 
-        let promotion = Text::from(
-            Vec::from([
-                "Promotion piece: ".bold().into_left_aligned_line(),
-                (
-                    if self.app.promotion_piece == PieceType::Q {
-                        format!("  1: Queen").white()
-                    } else {
-                        format!("  1: Queen").gray()
-                    }
-                )
-                    .bold()
-                    .into_left_aligned_line(),
-                (
-                    if self.app.promotion_piece == PieceType::R {
-                        format!("  2: Rook").white()
-                    } else {
-                        format!("  2: Rook").gray()
-                    }
-                )
-                    .bold()
-                    .into_left_aligned_line(),
-                (
-                    if self.app.promotion_piece == PieceType::B {
-                        format!("  3: Bishop").white()
-                    } else {
-                        format!("  3: Bishop").gray()
-                    }
-                )
-                    .bold()
-                    .into_left_aligned_line(),
-                (
-                    if self.app.promotion_piece == PieceType::N {
-                        format!("  4: Knight").white()
-                    } else {
-                        format!("  4: Knight").gray()
-                    }
-                )
-                    .bold()
-                    .into_left_aligned_line(),
-            ])
-        );
+        let grouped = self.app.config.group_captured;
+        let captured_by = |player: Player| {
+            let pieces = captured_pieces(player.other_player(), &self.app.board);
+            format!("{} took: {}", player, format_captured(&pieces, grouped)).bold().into_left_aligned_line()
+        };
+
+        let history = self.app.notated_history();
+        let first_shown = history.len().saturating_sub(LAST_MOVES_COUNT);
+        let last_moves = format!("Last moves: {}", if history.is_empty() {
+            String::from("none")
+        } else {
+            history[first_shown..]
+                .iter()
+                .zip(&self.app.move_evals[first_shown..])
+                .map(|(notated, eval)| format!("{}{}", notated, eval.map_or("", |eval| eval.marker())))
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+            .bold()
+            .into_left_aligned_line();
+
+        // Color the draw counters toward red as a draw by rule approaches
+        let half_moves = self.app.board.rule_50();
+        let fifty_moves = format!("50-move rule: {}/50", half_moves / 2)
+            .bold()
+            .fg(fifty_move_warning(half_moves).color())
+            .into_left_aligned_line();
+        let repetition_count = self.app.repetition_count();
+        let repetitions = format!("Repetitions: {}/3", repetition_count)
+            .bold()
+            .fg(repetition_warning(repetition_count).color())
+            .into_left_aligned_line();
 
-        let layout = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([Constraint::Percentage(75), Constraint::Percentage(25)].as_ref())
-            .margin(1)
-            .split(area);
+        // Gauges of how exposed each king is in the shown position, filling up as its pawn cover opens and
+        // attackers gather
+        let board = self.app.displayed_board();
+        let (white_safety, black_safety) =
+            (king_safety(board, Player::White), king_safety(board, Player::Black));
+        let king_danger = Line::from(
+            vec![
+                "King danger: W ".bold(),
+                king_gauge(white_safety).fg(white_safety.color()),
+                " B ".bold(),
+                king_gauge(black_safety).fg(black_safety.color())
+            ]
+        ).left_aligned();
 
-        Paragraph::new(if !is_game_over(&self.app.board) { info_text } else { checkmate_text }).render(
-            layout[0],
-            buf
+        let time_spent = format!(
+            "Time: White {}, Black {}",
+            format_duration(self.app.thinking_time(Player::White)),
+            format_duration(self.app.thinking_time(Player::Black))
+        ).bold().into_left_aligned_line();
+
+        let mut info_text = Text::from(
+            Vec::from([
+                top_line,
+                blank.clone(),
+                side_labels[0].clone(),
+                side_labels[1].clone(),
+                current_square,
+                current_piece,
+                attackers,
+                captured_by(Player::White),
+                captured_by(Player::Black),
+                last_moves,
+                fifty_moves,
+                repetitions,
+                king_danger,
+                time_spent,
+            ])
         );
+        for line in fen_lines(&self.app.board, self.app.config.fen_display) {
+            info_text.push_line(line);
+        }
+        if let Some(Some(eval)) = self.app.move_evals.last() {
+            let line = format!("Eval: {} ({})", format_eval(eval.eval), format_eval(eval.delta));
+            info_text.push_line(line.bold().into_left_aligned_line());
+        }
+        if let Some(analysis) = &self.app.analysis {
+            info_text.push_line(format!("Analysis: {}", analysis.summary()).bold().into_left_aligned_line());
+        }
+        if let Some(search) = &self.app.engine_search {
+            let frame = (search.elapsed().as_millis() / SPINNER_FRAME_MS) as usize % SPINNER_FRAMES.len();
+            let line = format!("Engine: thinking {}", SPINNER_FRAMES[frame]);
+            info_text.push_line(line.bold().into_left_aligned_line());
+        } else if let Some(stats) = self.app.engine_stats {
+            info_text.push_line(format!("Engine: {}", stats.summary()).bold().into_left_aligned_line());
+        }
+        if let Some(opening) = self.app.opening {
+            let line = format!("Opening: {} {}", opening.eco, opening.name);
+            info_text.push_line(line.bold().into_left_aligned_line());
+        }
+        if let Some(scenario) = self.app.scenario {
+            info_text.push_line(blank.clone());
+            info_text.push_line(format!("Goal: {}", scenario.goal).italic().into_left_aligned_line());
+        }
+        let text = if let Some(training) = self.app.coordinate_training {
+            Text::from(
+                Vec::from([
+                    "Coordinate training".bold().into_centered_line(),
+                    Line::from(""),
+                    format!("Find: {}", training.target.to_string().to_uppercase())
+                        .bold()
+                        .into_left_aligned_line(),
+                    format!("Score: {}/{}", training.correct, training.attempts)
+                        .bold()
+                        .into_left_aligned_line(),
+                    format!("Last time: {}", match training.last_time {
+                        Some(time) => format!("{:.1}s", time.as_secs_f64()),
+                        None => String::from("none"),
+                    })
+                        .bold()
+                        .into_left_aligned_line(),
+                ])
+            )
+        } else if let Some(result) = self.app.final_result() {
+            Text::from(
+                Vec::from([
+                    result.bold().into_centered_line(),
+                    blank,
+                    RESET_HINT.bold().into_centered_line(),
+                ])
+            )
+        } else {
+            info_text
+        };
+        self.app.info_scroll = self.app.info_scroll.min(text.lines.len().saturating_sub(1) as u16);
 
-        Paragraph::new(promotion).render(layout[1], buf);
+        Paragraph::new(text)
+            .wrap(Wrap { trim: true })
+            .scroll((self.app.info_scroll, 0))
+            .render(area.inner(Margin::new(1, 1)), buf);
 
-        Block::default()
+        let block = Block::default()
             .title_top(Line::from("Info").centered().bold())
             .borders(Borders::ALL)
-            .border_set(border::ROUNDED)
-            .render(area, buf);
+            .border_set(border::ROUNDED);
+
+        let block = if self.app.focused_panel == Panel::Info { block.border_style(Color::Yellow) } else { block };
+        block.render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pleco::SQ;
+
+    use crate::utils::{ move_to_square, moves_from_square };
+
+    use super::*;
+
+    fn render_to_string(app: &mut App) -> String {
+        let area = Rect::new(0, 0, 40, 24);
+        let mut buf = Buffer::empty(area);
+        Info { app }.render(area, &mut buf);
+        buf.content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect()
+    }
+
+    #[test]
+    fn test_king_danger_gauges() {
+        let mut app = App::new();
+        assert!(render_to_string(&mut app).contains("King danger: W ░░░░░ B ░░░░░"));
+
+        // Castled white king without its g-pawn
+        app.load_fen("4k3/pppppppp/8/8/8/8/5P1P/6K1 w - - 0 1").unwrap();
+        assert!(render_to_string(&mut app).contains("King danger: W ██░░░ B ░░░░░"));
+    }
+
+    #[test]
+    fn test_displayed_player_matches_turn() {
+        let mut app = App::new();
+        assert!(render_to_string(&mut app).contains("| White"));
+
+        for (src, dest) in [(SQ::E2, SQ::E4), (SQ::E7, SQ::E5), (SQ::G1, SQ::F3)] {
+            app.handle_move(move_to_square(dest, &moves_from_square(src, &app.board)));
+        }
+        assert_eq!(app.board.turn(), Player::Black);
+        assert!(render_to_string(&mut app).contains("| Black"));
+
+        app.load_fen("4k3/8/8/8/8/8/4P3/4K3 b - - 0 1").unwrap();
+        assert!(render_to_string(&mut app).contains("| Black"));
+    }
+
+    #[test]
+    fn test_fen_lines() {
+        let board = pleco::Board::from_fen("r3k2r/8/8/3pP3/8/8/8/R3K2R w KQq d6 0 12").unwrap();
+        assert!(fen_lines(&board, FenDisplay::Hidden).is_empty());
+        assert_eq!(fen_lines(&board, FenDisplay::Raw)[0].to_string(), format!("FEN: {}", board.fen()));
+
+        let split: Vec<String> = fen_lines(&board, FenDisplay::Split).iter().map(Line::to_string).collect();
+        assert_eq!(split, [
+            "Placement: r3k2r/8/8/3pP3/8/8/8/R3K2R",
+            "Side to move: w",
+            "Castling: KQq",
+            "En passant: d6",
+            "Halfmove clock: 0",
+            "Fullmove: 12",
+        ]);
+    }
+
+    #[test]
+    fn test_player_labels() {
+        let mut app = App::new();
+        app.black_label = String::from("Magnus");
+        let rendered = render_to_string(&mut app);
+        assert!(rendered.contains("Top: Magnus"));
+        assert!(rendered.contains("Bottom: White"));
+
+        app.toggle_engine();
+        app.rotate_board();
+        app.rotate_board();
+        let rendered = render_to_string(&mut app);
+        assert!(rendered.contains("Top: White"));
+        assert!(rendered.contains(&format!("Bottom: Magnus (engine, depth {})", app.engine_depth)));
+    }
+
+    #[test]
+    fn test_coordinate_training() {
+        let mut app = App::new();
+        app.toggle_coordinate_training();
+        let target = app.coordinate_training.unwrap().target.to_string().to_uppercase();
+
+        let rendered = render_to_string(&mut app);
+        assert!(rendered.contains(&format!("Find: {}", target)));
+        assert!(rendered.contains("Score: 0/0"));
+    }
+
+    #[test]
+    fn test_last_moves() {
+        let mut app = App::new();
+        assert!(render_to_string(&mut app).contains("Last moves: none"));
+
+        let moves = [
+            (SQ::E2, SQ::E4),
+            (SQ::E7, SQ::E5),
+            (SQ::G1, SQ::F3),
+            (SQ::B8, SQ::C6),
+            (SQ::F1, SQ::B5),
+        ];
+        for (src, dest) in moves {
+            app.handle_move(move_to_square(dest, &moves_from_square(src, &app.board)));
+        }
+        assert!(render_to_string(&mut app).contains("Last moves: e5 Nf3 Nc6 Bb5"));
     }
 }
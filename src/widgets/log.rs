@@ -1,28 +1,53 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::Stylize,
+    style::{ palette::tailwind::ORANGE, Color, Stylize },
     symbols::border,
     text::{ Line, Text },
     widgets::{ Block, Borders, Paragraph, Widget },
 };
 
+use crate::utils::{ LogEntry, LogKind };
+
 /// Log widget.
 #[derive(Debug)]
 pub struct Log {
     /// Log vector to render.
-    pub log: Vec<String>,
+    pub log: Vec<LogEntry>,
+    /// Scroll offset of the log.
+    pub scroll: u16,
+    /// Whether the log panel has focus.
+    pub focused: bool,
 }
 
 impl Widget for Log {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        Paragraph::new(Text::from(self.log.into_iter().rev().collect::<Vec<String>>().join("\n")))
-            .block(
-                Block::default()
-                    .title_top(Line::from("Log").centered().bold())
-                    .borders(Borders::ALL)
-                    .border_set(border::ROUNDED)
-            )
+        let block = Block::default()
+            .title_top(Line::from("Log").centered().bold())
+            .borders(Borders::ALL)
+            .border_set(border::ROUNDED);
+
+        let lines = self.log
+            .into_iter()
+            .rev()
+            .map(|entry| {
+                let line = Line::from(entry.message);
+                match entry.kind {
+                    LogKind::Info => line,
+                    LogKind::Capture => line.fg(ORANGE.c400),
+                    LogKind::Check => line.yellow(),
+                    LogKind::Checkmate => line.red().bold(),
+                    LogKind::Draw => line.green().bold(),
+                    LogKind::Success => line.green(),
+                    LogKind::Warning => line.yellow().italic(),
+                    LogKind::Error => line.red(),
+                }
+            })
+            .collect::<Vec<Line>>();
+
+        Paragraph::new(Text::from(lines))
+            .scroll((self.scroll, 0))
+            .block(if self.focused { block.border_style(Color::Yellow) } else { block })
             .render(area, buf);
     }
 }
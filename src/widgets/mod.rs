@@ -9,3 +9,30 @@ pub use info::Info;
 
 mod terminal_too_small;
 pub use terminal_too_small::TerminalTooSmall;
+
+mod save_slots;
+pub use save_slots::SaveSlots;
+
+mod handoff;
+pub use handoff::Handoff;
+
+mod game_summary;
+pub use game_summary::{ GameSummary, GAME_SUMMARY_HEIGHT, GAME_SUMMARY_WIDTH };
+
+mod opening_explorer;
+pub use opening_explorer::{ OpeningExplorer, OPENING_EXPLORER_WIDTH };
+
+mod paused;
+pub use paused::{ Paused, PAUSED_HEIGHT, PAUSED_WIDTH };
+
+mod scenarios;
+pub use scenarios::Scenarios;
+
+mod start_screen;
+pub use start_screen::{ StartScreen, START_SCREEN_HEIGHT, START_SCREEN_WIDTH };
+
+mod debug_overlay;
+pub use debug_overlay::{ DebugOverlay, DEBUG_OVERLAY_HEIGHT, DEBUG_OVERLAY_WIDTH };
+
+mod timeline;
+pub use timeline::{ ply_at_column, ply_column, Timeline };
@@ -0,0 +1,83 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::{ Line, Text },
+    widgets::{ Block, Borders, Clear, Paragraph, Widget },
+};
+
+use crate::openings::BookMove;
+
+/// Width of the opening explorer.
+pub const OPENING_EXPLORER_WIDTH: u16 = 36;
+
+/// Opening explorer widget.
+/// Lists the continuations of the played moves from the table of openings.
+#[derive(Debug)]
+pub struct OpeningExplorer {
+    /// The continuations, most common first.
+    pub moves: Vec<BookMove>,
+    /// Index of the highlighted continuation.
+    pub selected: usize,
+}
+
+impl OpeningExplorer {
+    /// Gets the height of the explorer including its border.
+    pub fn height(&self) -> u16 {
+        (self.moves.len().max(1) as u16) + 2
+    }
+}
+
+impl Widget for OpeningExplorer {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let entries = if self.moves.is_empty() {
+            Text::from(Line::from(" No book moves").gray())
+        } else {
+            Text::from(
+                self.moves
+                    .iter()
+                    .enumerate()
+                    .map(|(index, book_move)| {
+                        let line = Line::from(format!(" {:<6}{} ×{}", book_move.san, book_move.name, book_move.count));
+                        if index == self.selected { line.reversed().bold() } else { line }
+                    })
+                    .collect::<Vec<Line>>()
+            )
+        };
+
+        Clear.render(area, buf);
+        Paragraph::new(entries)
+            .block(
+                Block::default()
+                    .title_top(Line::from("Openings").centered().bold())
+                    .title_bottom(Line::from("Enter to play, Esc to close").centered())
+                    .borders(Borders::ALL)
+                    .border_set(border::ROUNDED)
+            )
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::openings::book_moves;
+
+    #[test]
+    fn test_render_opening_explorer() {
+        let explorer = OpeningExplorer { moves: book_moves(&[]), selected: 1 };
+        let mut buf = Buffer::empty(Rect::new(0, 0, OPENING_EXPLORER_WIDTH, explorer.height()));
+        explorer.render(buf.area, &mut buf);
+        let text: String = buf.content().iter().map(|cell| cell.symbol()).collect();
+
+        assert!(text.contains("e4    Scandinavian Defense ×8"));
+        assert!(text.contains("d4    Queen's Gambit ×2"));
+
+        let empty = OpeningExplorer { moves: Vec::new(), selected: 0 };
+        let mut buf = Buffer::empty(Rect::new(0, 0, OPENING_EXPLORER_WIDTH, empty.height()));
+        empty.render(buf.area, &mut buf);
+        let text: String = buf.content().iter().map(|cell| cell.symbol()).collect();
+        assert!(text.contains("No book moves"));
+    }
+}
@@ -0,0 +1,30 @@
+use ratatui::{
+    widgets::{ Block, BorderType, Clear, Paragraph, Widget },
+    prelude::{ Buffer, Line, Rect },
+    style::Stylize,
+};
+
+/// Width of the paused overlay.
+pub const PAUSED_WIDTH: u16 = 28;
+
+/// Height of the paused overlay.
+pub const PAUSED_HEIGHT: u16 = 5;
+
+/// Paused widget.
+/// Shown over the board until the first key starts the game.
+#[derive(Debug)]
+pub struct Paused {}
+
+impl Widget for Paused {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::bordered()
+            .title_top(Line::from(" Paused ").centered().bold())
+            .border_type(BorderType::Rounded);
+
+        Clear.render(area, buf);
+        Paragraph::new(vec![Line::from(""), Line::from("Press any key to begin")])
+            .centered()
+            .block(block)
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,51 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::{ Line, Text },
+    widgets::{ Block, Borders, Clear, Paragraph, Widget },
+};
+
+use crate::save::{ SlotAction, SLOT_COUNT };
+
+/// Save slots widget.
+#[derive(Debug)]
+pub struct SaveSlots {
+    /// The action to perform on the chosen slot.
+    pub action: SlotAction,
+    /// The save slots which contain a game.
+    pub occupied_slots: Vec<u8>,
+}
+
+impl Widget for SaveSlots {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = match self.action {
+            SlotAction::Save => "Save to slot",
+            SlotAction::Load => "Load from slot",
+        };
+
+        let slots = Text::from(
+            (1..=SLOT_COUNT)
+                .map(|slot| {
+                    if self.occupied_slots.contains(&slot) {
+                        format!(" {}: Occupied", slot).white().bold().into_left_aligned_line()
+                    } else {
+                        format!(" {}: Empty", slot).gray().into_left_aligned_line()
+                    }
+                })
+                .collect::<Vec<Line>>()
+        );
+
+        Clear.render(area, buf);
+        Paragraph::new(slots)
+            .block(
+                Block::default()
+                    .title_top(Line::from(title).centered().bold())
+                    .title_bottom(Line::from("Esc to cancel").centered())
+                    .borders(Borders::ALL)
+                    .border_set(border::ROUNDED)
+            )
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,38 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::{ Line, Text },
+    widgets::{ Block, Borders, Clear, Paragraph, Widget },
+};
+
+use crate::scenarios::SCENARIOS;
+
+/// Scenario menu widget.
+#[derive(Debug, Default)]
+pub struct Scenarios {}
+
+impl Widget for Scenarios {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let scenarios = Text::from(
+            SCENARIOS.iter()
+                .enumerate()
+                .map(|(index, scenario)| {
+                    format!(" {}: {}", index + 1, scenario.name).white().bold().into_left_aligned_line()
+                })
+                .collect::<Vec<Line>>()
+        );
+
+        Clear.render(area, buf);
+        Paragraph::new(scenarios)
+            .block(
+                Block::default()
+                    .title_top(Line::from("Practice").centered().bold())
+                    .title_bottom(Line::from("Esc to cancel").centered())
+                    .borders(Borders::ALL)
+                    .border_set(border::ROUNDED)
+            )
+            .render(area, buf);
+    }
+}
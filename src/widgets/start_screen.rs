@@ -0,0 +1,67 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::{ Line, Text },
+    widgets::{ Block, Borders, Clear, Paragraph, Widget },
+};
+
+use crate::start_menu::{ StartMenu, START_CHOICES };
+
+/// Width of the start screen.
+pub const START_SCREEN_WIDTH: u16 = 36;
+
+/// Height of the start screen including its border.
+pub const START_SCREEN_HEIGHT: u16 = (START_CHOICES.len() as u16) + 2;
+
+/// Start screen widget.
+/// Lists the ways to start playing before the first game.
+#[derive(Debug)]
+pub struct StartScreen {
+    /// The state of the start menu.
+    pub menu: StartMenu,
+}
+
+impl Widget for StartScreen {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let entries = Text::from(
+            START_CHOICES
+                .iter()
+                .enumerate()
+                .map(|(index, choice)| {
+                    let line = Line::from(format!(" {}", self.menu.label(*choice)));
+                    if index == self.menu.selected { line.reversed().bold() } else { line }
+                })
+                .collect::<Vec<Line>>()
+        );
+
+        Clear.render(area, buf);
+        Paragraph::new(entries)
+            .block(
+                Block::default()
+                    .title_top(Line::from("New game").centered().bold())
+                    .title_bottom(Line::from("Enter to start, Esc to quit").centered())
+                    .borders(Borders::ALL)
+                    .border_set(border::ROUNDED)
+            )
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_start_screen() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, START_SCREEN_WIDTH, START_SCREEN_HEIGHT));
+        StartScreen { menu: StartMenu::new(Some(5)) }.render(buf.area, &mut buf);
+        let text: String = buf.content().iter().map(|cell| cell.symbol()).collect();
+
+        assert!(text.contains("New game"));
+        assert!(text.contains("Two players"));
+        assert!(text.contains("Engine skill: < 5 >"));
+        assert!(buf[(1, 1)].modifier.contains(ratatui::style::Modifier::REVERSED));
+    }
+}
@@ -6,8 +6,13 @@ use ratatui::{
 };
 
 /// Terminal too small widget.
-#[derive(Debug, Default)]
-pub struct TerminalTooSmall {}
+#[derive(Debug)]
+pub struct TerminalTooSmall {
+    /// Minimum width of the terminal.
+    pub min_width: u16,
+    /// Minimum height of the terminal.
+    pub min_height: u16,
+}
 
 impl Widget for TerminalTooSmall {
     fn render(self, area: Rect, buf: &mut Buffer) {
@@ -18,19 +23,21 @@ impl Widget for TerminalTooSmall {
         Paragraph::new(
             vec![
                 Line::from(""),
-                Line::from("Please resize your terminal to at least 106x24".bold()),
+                Line::from(
+                    format!("Please resize your terminal to at least {}x{}", self.min_width, self.min_height).bold()
+                ),
                 Line::from(""),
                 Line::from(
                     vec![
                         Span::raw("Width = "),
                         Span::styled(
                             format!("{} ", area.width),
-                            Style::new().fg(if area.width >= 106 { GREEN.c500 } else { RED.c500 })
+                            Style::new().fg(if area.width >= self.min_width { GREEN.c500 } else { RED.c500 })
                         ),
                         Span::raw("Height = "),
                         Span::styled(
                             format!("{} ", area.height),
-                            Style::new().fg(if area.height >= 24 { GREEN.c500 } else { RED.c500 })
+                            Style::new().fg(if area.height >= self.min_height { GREEN.c500 } else { RED.c500 })
                         )
                     ]
                 )
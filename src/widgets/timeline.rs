@@ -0,0 +1,94 @@
+use ratatui::{
+    prelude::{ Buffer, Rect },
+    style::{ Color, Style },
+    widgets::Widget,
+};
+
+/// Character of the bar.
+const BAR: &str = "─";
+
+/// Character marking the shown ply.
+const MARKER: &str = "●";
+
+/// Timeline widget.
+/// Shows all plies of the game as a bar with a marker at the shown ply.
+#[derive(Debug)]
+pub struct Timeline {
+    /// The number of plies played.
+    pub plies: usize,
+    /// The ply shown on the board.
+    pub current: usize,
+}
+
+/// Gets the column of a ply on a timeline, scaled to its width.
+///
+/// # Arguments
+///
+/// * `ply` - The ply to place.
+/// * `plies` - The number of plies played.
+/// * `width` - The width of the timeline.
+///
+/// # Returns
+///
+/// The offset of the ply from the left of the timeline.
+pub fn ply_column(ply: usize, plies: usize, width: u16) -> u16 {
+    if plies == 0 {
+        return 0;
+    }
+    ((ply.min(plies) * (width.saturating_sub(1) as usize)) / plies) as u16
+}
+
+/// Gets the ply at a column of a timeline, scaled to its width.
+///
+/// # Arguments
+///
+/// * `column` - The offset from the left of the timeline.
+/// * `plies` - The number of plies played.
+/// * `width` - The width of the timeline.
+///
+/// # Returns
+///
+/// The ply closest to the column.
+pub fn ply_at_column(column: u16, plies: usize, width: u16) -> usize {
+    let last_column = width.saturating_sub(1).max(1) as usize;
+    ((column.min(width) as usize) * plies + last_column / 2) / last_column
+}
+
+impl Widget for Timeline {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        let bar = BAR.repeat(area.width as usize);
+        buf.set_string(area.x, area.y, bar, Style::new().fg(Color::DarkGray));
+        let marker_x = area.x + ply_column(self.current, self.plies, area.width);
+        buf.set_string(marker_x, area.y, MARKER, Style::new().fg(Color::Yellow));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ply_columns() {
+        assert_eq!(ply_column(0, 10, 21), 0);
+        assert_eq!(ply_column(5, 10, 21), 10);
+        assert_eq!(ply_column(10, 10, 21), 20);
+        assert_eq!(ply_column(3, 0, 21), 0);
+
+        for ply in 0..=10 {
+            assert_eq!(ply_at_column(ply_column(ply, 10, 21), 10, 21), ply);
+        }
+        assert_eq!(ply_at_column(1, 200, 5), 50);
+    }
+
+    #[test]
+    fn test_render_marker() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 11, 1));
+        Timeline { plies: 4, current: 2 }.render(buf.area, &mut buf);
+        assert_eq!(buf[(5, 0)].symbol(), MARKER);
+        assert_eq!(buf[(0, 0)].symbol(), BAR);
+    }
+}